@@ -11,7 +11,7 @@ import ProjectPresetModal from './components/ProjectPresetModal.tsx';
 import ExportModal from './components/ExportModal.tsx';
 // Tauri 环境下的本地文件适配器
 import { useLocalProjects, type RecentProjectItem } from './hooks/useLocalProjects.ts';
-import { ProjectState, Layer, DecorationTemplate } from './types.ts';
+import { ProjectState, Layer, DecorationTemplate, TemplateInfo } from './types.ts';
 import {
   createIndexedDBAdapter,
   createLocalFileAdapter,
@@ -193,13 +193,14 @@ const App: React.FC = () => {
   const [activeTab, setActiveTab] = useState('assets');
   const [isExporting, setIsExporting] = useState(false);
   const [toast, setToast] = useState<{ msg: string, type: 'success' | 'error' } | null>(null);
-  const [confirmDialog, setConfirmDialog] = useState<{ message: string, onConfirm: () => void } | null>(null);
+  const [confirmDialog, setConfirmDialog] = useState<{ message: string, onConfirm: () => void, onCancel?: () => void, extra?: { label: string, onConfirm: () => void } } | null>(null);
   const [isStorageSettingsOpen, setIsStorageSettingsOpen] = useState(false);
   const [projectSearchTerm, setProjectSearchTerm] = useState('');
   const [cloudPage, setCloudPage] = useState(1);
   const [cloudHasMore, setCloudHasMore] = useState(true);
   const [cloudLoading, setCloudLoading] = useState(false);
   const [isPresetModalOpen, setIsPresetModalOpen] = useState(false);
+  const [templates, setTemplates] = useState<TemplateInfo[]>([]);
   const [selectedPreset, setSelectedPreset] = useState(() => PRESET_RATIOS[0]);
   const [customPresetSize, setCustomPresetSize] = useState(() => ({
     width: PRESET_RATIOS[0].width,
@@ -249,11 +250,23 @@ const App: React.FC = () => {
     openLocalProjectDialog,
     openLocalProjectFromPath,
     removeLocalRecentByPath,
+    trashLocalProject,
     saveLocalProjectToFile,
-    clearActiveLocalFilePath
+    saveLocalProjectAs,
+    saveLocalProjectCopy,
+    restoreRecovery,
+    discardRecovery,
+    clearActiveLocalFilePath,
+    isLocalProjectDirty,
+    reloadLocalProject
   } = useLocalProjects({
     view,
     parseFailedMessage: t.parseFailed,
+    migratedMessage: t.projectMigrated,
+    readOnlyMessage: t.projectReadOnly,
+    shortcutFailedMessage: t.shortcutFailed,
+    changedExternallyMessage: t.fileChangedExternally,
+    backupCreatedMessage: t.backupCreated,
     showToast,
     onOpenProject: (nextProject) => {
       setProject(nextProject);
@@ -262,8 +275,21 @@ const App: React.FC = () => {
     },
     onUpdateProject: (nextProject) => {
       setProject(nextProject);
+    },
+    onRecoveryAvailable: (snapshots) => {
+      // 一次只提示最新的快照，其余的下次启动再提示
+      const snapshot = snapshots[0];
+      setConfirmDialog({
+        message: t.recoveryAvailable.replace('{title}', snapshot.project.title),
+        onConfirm: () => { restoreRecoveryRef.current?.(snapshot); },
+        onCancel: () => { discardRecoveryRef.current?.(snapshot); }
+      });
     }
   });
+  const restoreRecoveryRef = useRef(restoreRecovery);
+  restoreRecoveryRef.current = restoreRecovery;
+  const discardRecoveryRef = useRef(discardRecovery);
+  discardRecoveryRef.current = discardRecovery;
 
   const handlePickStorageFolder = useCallback(async () => {
     if (!localFileAdapter.isAvailable()) {
@@ -401,8 +427,17 @@ const App: React.FC = () => {
       return;
     }
 
-    // tauri 模式下本地文件编辑关闭自动保存
+    // tauri 模式下本地文件编辑关闭自动保存，只写恢复快照
     if (activeLocalFilePath && isTauri && reason === 'auto') {
+      lastSavedAtRef.current = now;
+      // 内容与磁盘一致（例如只切换了选中图层）时不写快照
+      isLocalProjectDirty(currentProject).then(async (dirty) => {
+        if (!dirty) return;
+        const { invoke } = await import('@tauri-apps/api/core');
+        await invoke('autosave', { project: currentProject, originalPath: activeLocalFilePath });
+      }).catch(() => {
+        // ignore autosave errors
+      });
       return;
     }
 
@@ -414,7 +449,19 @@ const App: React.FC = () => {
         if (reason === 'manual') {
           showToast(t.save || 'Saved');
         }
-      }).catch(() => {
+      }).catch((err) => {
+        // 后端拒绝覆盖只读文件，改为另存为
+        if (typeof err === 'string' && err.startsWith('read-only:')) {
+          showToast(t.projectReadOnly, 'error');
+          if (reason === 'manual') {
+            saveLocalProjectAs(currentProject).then((nextProject) => {
+              if (nextProject) showToast(t.save || 'Saved');
+            }).catch(() => {
+              showToast(t.storageSaveFailed, 'error');
+            });
+          }
+          return;
+        }
         showToast(t.storageSaveFailed, 'error');
       });
       return;
@@ -430,7 +477,7 @@ const App: React.FC = () => {
     }).catch(() => {
       showToast(t.storageSaveFailed, 'error');
     });
-  }, [activeLocalFilePath, isTauri, saveLocalProjectToFile, storageAdapter, showToast, t.storageSaveFailed]);
+  }, [activeLocalFilePath, isTauri, isLocalProjectDirty, saveLocalProjectAs, saveLocalProjectToFile, storageAdapter, showToast, t.projectReadOnly, t.save, t.storageSaveFailed]);
 
   const persistProject = useCallback(async (nextProject: ProjectState) => {
     try {
@@ -469,9 +516,8 @@ const App: React.FC = () => {
   useEffect(() => {
     if (isCloudMode) return;
     if (view !== 'editor' || !project) return;
-    if (activeLocalFilePath && isTauri) return;
     performAutoSave('auto');
-  }, [project, view, performAutoSave, isCloudMode, activeLocalFilePath, isTauri]);
+  }, [project, view, performAutoSave, isCloudMode]);
 
   useEffect(() => {
     if (prevViewRef.current === 'editor' && view !== 'editor') {
@@ -491,6 +537,64 @@ const App: React.FC = () => {
     return () => window.removeEventListener('beforeunload', handleBeforeUnload);
   }, [performAutoSave]);
 
+  const performAutoSaveRef = useRef(performAutoSave);
+  performAutoSaveRef.current = performAutoSave;
+
+  useEffect(() => {
+    if (!isTauri) return;
+    let active = true;
+    const stops: Array<() => void> = [];
+    const init = async () => {
+      const { listen } = await import('@tauri-apps/api/event');
+      // 原生菜单的保存 / 另存为
+      const stopSave = await listen('menu://save', () => performAutoSaveRef.current('manual'));
+      const stopSaveAs = await listen('menu://save-as', () => {
+        const currentProject = latestProjectRef.current;
+        if (!currentProject) return;
+        saveLocalProjectAs(currentProject).then((nextProject) => {
+          if (nextProject) showToast(t.save || 'Saved');
+        }).catch(() => {
+          showToast(t.storageSaveFailed, 'error');
+        });
+      });
+      const stopSaveCopy = await listen('menu://save-copy', () => {
+        const currentProject = latestProjectRef.current;
+        if (!currentProject) return;
+        saveLocalProjectCopy(currentProject).then((path) => {
+          if (path) showToast(t.savedCopy || 'Copy saved');
+        }).catch((err) => {
+          showToast(typeof err === 'string' ? `${t.storageSaveFailed} (${err})` : t.storageSaveFailed, 'error');
+        });
+      });
+      // 重新加载会丢弃未保存的修改，有修改时先确认
+      const stopReload = await listen('menu://reload', async () => {
+        const currentProject = latestProjectRef.current;
+        if (!currentProject) return;
+        const reload = () => {
+          reloadLocalProject().then((nextProject) => {
+            if (nextProject) showToast(t.projectReloaded);
+          }).catch((err) => {
+            const message = typeof err === 'string' && err.startsWith('source missing:') ? t.projectSourceMissing : t.parseFailed;
+            showToast(typeof err === 'string' ? `${message} (${err})` : message, 'error');
+          });
+        };
+        const dirty = await isLocalProjectDirty(currentProject).catch(() => true);
+        if (dirty) {
+          setConfirmDialog({ message: t.confirmReload, onConfirm: reload });
+        } else {
+          reload();
+        }
+      });
+      stops.push(stopSave, stopSaveAs, stopSaveCopy, stopReload);
+      if (!active) stops.forEach(stop => stop());
+    };
+    init();
+    return () => {
+      active = false;
+      stops.forEach(stop => stop());
+    };
+  }, [isTauri, saveLocalProjectAs, saveLocalProjectCopy, reloadLocalProject, isLocalProjectDirty, showToast, t.save, t.savedCopy, t.storageSaveFailed, t.projectReloaded, t.projectSourceMissing, t.parseFailed, t.confirmReload]);
+
   useEffect(() => {
     if (project && view === 'editor') {
       setProjects(prev => prev.map(p => p.id === project.id ? { ...project, updatedAt: Date.now() } : p));
@@ -641,6 +745,67 @@ const App: React.FC = () => {
     setIsPresetModalOpen(true);
   };
 
+  // 模板列表只在首次打开新建窗口时加载
+  useEffect(() => {
+    if (!isTauri || !isPresetModalOpen || templates.length > 0) return;
+    let active = true;
+    const load = async () => {
+      try {
+        const { invoke } = await import('@tauri-apps/api/core');
+        const list = await invoke<TemplateInfo[]>('list_templates');
+        if (active) setTemplates(list);
+      } catch (err) {
+        // 没有模板时只显示画布比例
+      }
+    };
+    load();
+    return () => {
+      active = false;
+    };
+  }, [isTauri, isPresetModalOpen, templates.length]);
+
+  const createFromTemplate = async (id: string) => {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const newProject = await invoke<ProjectState>('new_from_template', { id });
+      setIsPresetModalOpen(false);
+      setProject(newProject);
+      setProjects(prev => [newProject, ...prev]);
+      setView('editor');
+      initProjectHistory(newProject);
+      clearActiveLocalFilePath();
+      persistProject(newProject);
+    } catch (err) {
+      showToast(t.templateLoadFailed.replace('{detail}', String(err)), 'error');
+    }
+  };
+
+  const trayNewProjectRef = useRef(() => {});
+  trayNewProjectRef.current = () => {
+    if (view === 'editor') {
+      performAutoSave('leave');
+      setProject(null);
+      setView('landing');
+    }
+    openPresetModal();
+  };
+
+  // 托盘菜单“新建项目”：先离开编辑器再打开尺寸选择
+  useEffect(() => {
+    if (!isTauri) return;
+    let active = true;
+    let stop: (() => void) | null = null;
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+      const unlisten = await listen('tray://new-project', () => trayNewProjectRef.current());
+      if (active) stop = unlisten;
+      else unlisten();
+    });
+    return () => {
+      active = false;
+      if (stop) stop();
+    };
+  }, [isTauri]);
+
   const updateExportSettings = useCallback((updates: Partial<typeof exportSettings>) => {
     setExportSettings(prev => {
       const next = { ...prev, ...updates };
@@ -1500,6 +1665,19 @@ const App: React.FC = () => {
 
       const trimmed = text.trim();
 
+      // 粘贴图片网址时由后端下载（限制大小并校验文件头）
+      if (isTauri && /^https?:\/\/\S+$/i.test(trimmed)) {
+        e.preventDefault();
+        try {
+          const { invoke } = await import('@tauri-apps/api/core');
+          const image = await invoke<{ dataUrl: string }>('fetch_image', { url: trimmed, maxBytes: 20 * 1024 * 1024 });
+          addImageLayerWithContent(image.dataUrl);
+        } catch (err) {
+          showToast(t.imageFetchFailed.replace('{detail}', String(err)), 'error');
+        }
+        return;
+      }
+
       if (trimmed.toLowerCase().startsWith('<svg')) {
         e.preventDefault();
         addSvgLayerWithContent(trimmed);
@@ -1523,7 +1701,51 @@ const App: React.FC = () => {
 
     window.addEventListener('paste', handlePaste);
     return () => window.removeEventListener('paste', handlePaste);
-  }, [view, project, lang]);
+  }, [view, project, lang, isTauri]);
+
+  const addImageLayerRef = useRef(addImageLayerWithContent);
+  addImageLayerRef.current = addImageLayerWithContent;
+
+  // 拖放到窗口的图片（后端已校验路径）与不支持的文件
+  useEffect(() => {
+    if (!isTauri) return;
+    let active = true;
+    const stops: Array<() => void> = [];
+    const mimeTypes: Record<string, string> = { png: 'image/png', jpg: 'image/jpeg', jpeg: 'image/jpeg', webp: 'image/webp' };
+    const init = async () => {
+      const { listen } = await import('@tauri-apps/api/event');
+      const stopImages = await listen<string[]>('cover://add-image', async (event) => {
+        const { readFile } = await import('@tauri-apps/plugin-fs');
+        for (const path of event.payload) {
+          try {
+            const bytes = await readFile(path);
+            const ext = path.split('.').pop()?.toLowerCase() || '';
+            const blob = new Blob([bytes], { type: mimeTypes[ext] || 'application/octet-stream' });
+            const dataUrl = await new Promise<string>((resolve, reject) => {
+              const reader = new FileReader();
+              reader.onload = () => resolve(reader.result as string);
+              reader.onerror = () => reject(reader.error);
+              reader.readAsDataURL(blob);
+            });
+            addImageLayerRef.current(dataUrl);
+          } catch (err) {
+            showToast(t.dropRejected.replace('{detail}', path), 'error');
+          }
+        }
+      });
+      const stopRejected = await listen<string[]>('drop://rejected', (event) => {
+        const names = event.payload.map(path => path.split(/[\\/]/).pop() || path);
+        showToast(t.dropRejected.replace('{detail}', names.join(', ')), 'error');
+      });
+      stops.push(stopImages, stopRejected);
+      if (!active) stops.forEach(stop => stop());
+    };
+    init();
+    return () => {
+      active = false;
+      stops.forEach(stop => stop());
+    };
+  }, [isTauri, showToast, t.dropRejected]);
 
   const handleAddText = () => {
     if (!project) return;
@@ -1888,7 +2110,7 @@ const App: React.FC = () => {
     return (
       <div className="min-h-screen bg-slate-950 text-slate-200 p-8 flex flex-col gap-12 max-w-8xl mx-auto h-screen overflow-hidden" style={{ maxWidth: '95%' }}>
         {toast && <Toast message={toast.msg} type={toast.type} />}
-        {confirmDialog && <ConfirmModal isOpen={true} message={confirmDialog.message} lang={lang} onConfirm={() => { confirmDialog.onConfirm(); setConfirmDialog(null); }} onCancel={() => setConfirmDialog(null)} />}
+        {confirmDialog && <ConfirmModal isOpen={true} message={confirmDialog.message} lang={lang} onConfirm={() => { confirmDialog.onConfirm(); setConfirmDialog(null); }} onCancel={() => { confirmDialog.onCancel?.(); setConfirmDialog(null); }} extraLabel={confirmDialog.extra?.label} onExtra={confirmDialog.extra ? () => { confirmDialog.extra?.onConfirm(); setConfirmDialog(null); } : undefined} />}
         {!isCloudMode && renderStorageSettingsModal()}
         <ProjectPresetModal
           isOpen={isPresetModalOpen}
@@ -1906,6 +2128,8 @@ const App: React.FC = () => {
             createNewProject(selectedPreset, customPresetSize);
             setIsPresetModalOpen(false);
           }}
+          templates={templates}
+          onCreateFromTemplate={createFromTemplate}
         />
         <div className="flex justify-between items-center flex-shrink-0">
           <div className="flex items-center gap-4"><div className="bg-blue-600 p-2.5 rounded-2xl shadow-xl shadow-blue-900/20 text-white"><span className="w-8 h-8 block" dangerouslySetInnerHTML={{ __html: logoSvg }} /></div><div className="relative"><h1 className="text-3xl font-black tracking-tight text-white">{t.title}</h1><p className="text-slate-500 text-sm font-medium">{t.landingHeader}</p>
@@ -2009,11 +2233,20 @@ const App: React.FC = () => {
                         }} 
                         onDelete={(e) => { 
                           e.stopPropagation(); 
-                          // 本地项目删除时，仅删除打开记录信息
+                          // 本地项目默认只删除打开记录，也可将文件移到回收站
                           if (item.source === 'local' && item.localPath) {
+                            const localPath = item.localPath;
                             setConfirmDialog({
                               message: t.confirmRemoveLocalProject,
-                              onConfirm: () => removeLocalRecentByPath(item.localPath as string)
+                              onConfirm: () => removeLocalRecentByPath(localPath),
+                              extra: {
+                                label: t.moveToTrash,
+                                onConfirm: () => {
+                                  trashLocalProject(localPath).catch((err) => {
+                                    showToast(typeof err === 'string' ? `${t.moveToTrashFailed} (${err})` : t.moveToTrashFailed, 'error');
+                                  });
+                                }
+                              }
                             });
                             return;
                           }
@@ -2058,7 +2291,7 @@ const App: React.FC = () => {
   return (
     <div className="flex flex-col h-screen bg-slate-950 text-slate-200 overflow-hidden">
       {toast && <Toast message={toast.msg} type={toast.type} />}
-      {confirmDialog && <ConfirmModal isOpen={true} message={confirmDialog.message} lang={lang} onConfirm={() => { confirmDialog.onConfirm(); setConfirmDialog(null); }} onCancel={() => setConfirmDialog(null)} />}
+      {confirmDialog && <ConfirmModal isOpen={true} message={confirmDialog.message} lang={lang} onConfirm={() => { confirmDialog.onConfirm(); setConfirmDialog(null); }} onCancel={() => { confirmDialog.onCancel?.(); setConfirmDialog(null); }} extraLabel={confirmDialog.extra?.label} onExtra={confirmDialog.extra ? () => { confirmDialog.extra?.onConfirm(); setConfirmDialog(null); } : undefined} />}
       <ExportModal
         isOpen={isExportModalOpen}
         lang={lang}
@@ -8,13 +8,18 @@ const ConfirmModal = ({
   message,
   onConfirm,
   onCancel,
-  lang
+  lang,
+  extraLabel,
+  onExtra
 }: {
   isOpen: boolean;
   message: string;
   onConfirm: () => void;
   onCancel: () => void;
   lang: Language;
+  // 可选的第二个操作，显示在取消与确认之间
+  extraLabel?: string;
+  onExtra?: () => void;
 }) => {
   if (!isOpen) return null;
   const t = translations[lang];
@@ -37,6 +42,14 @@ const ConfirmModal = ({
           >
             {t.cancel}
           </button>
+          {extraLabel && onExtra && (
+            <button
+              onClick={onExtra}
+              className="px-4 py-2.5 rounded-xl bg-slate-800 hover:bg-slate-700 text-red-400 text-xs font-bold transition-colors"
+            >
+              {extraLabel}
+            </button>
+          )}
           <button
             onClick={onConfirm}
             className="px-4 py-2.5 rounded-xl bg-red-600 hover:bg-red-500 text-white text-xs font-bold shadow-lg shadow-red-900/20 transition-all active:scale-95"
@@ -134,6 +134,24 @@ const LayersPanel: React.FC<LayersPanelProps> = ({
   // 加载本地字体
   useEffect(() => {
     const fetchLocalFonts = async () => {
+      // 桌面端由后端枚举，与导出时使用的字体库一致
+      if (import.meta.env.VITE_APP_MODE === 'tauri') {
+        try {
+          const { invoke } = await import('@tauri-apps/api/core');
+          const families = await invoke<Array<{ family: string }>>('list_system_fonts');
+          if (families.length > 0) {
+            setLocalFonts(families.map(f => ({
+              fullName: f.family,
+              family: f.family,
+              isChinese: isChineseText(f.family),
+              value: `"${f.family}", sans-serif`
+            })));
+            return;
+          }
+        } catch (e) {
+          console.warn("Failed to list system fonts", e);
+        }
+      }
       if ('queryLocalFonts' in window) {
         try {
           // @ts-ignore
@@ -349,6 +367,159 @@ const LayersPanel: React.FC<LayersPanelProps> = ({
     [displayLayers]
   );
 
+  // 指针拖动图层排序；不用 HTML5 拖放，Windows 上开启原生文件拖放后 WebView2 不再派发这些事件
+  const dragStartRef = useRef<{ index: number; x: number; y: number } | null>(null);
+
+  const endLayerDrag = () => {
+    dragStartRef.current = null;
+    setDraggedIndex(null);
+    setDragOverIndex(null);
+  };
+
+  const startLayerDrag = (e: React.PointerEvent, index: number) => {
+    if (e.button !== 0 || (e.target as HTMLElement).closest('button, input, textarea')) return;
+    dragStartRef.current = { index, x: e.clientX, y: e.clientY };
+  };
+
+  useEffect(() => {
+    const handleMove = (e: PointerEvent) => {
+      const start = dragStartRef.current;
+      if (!start || draggedIndex !== null) return;
+      if (Math.hypot(e.clientX - start.x, e.clientY - start.y) > 4) setDraggedIndex(start.index);
+    };
+    // 落点上的 onPointerUp 先执行，这里只负责收尾（包括松开在列表之外）
+    const handleUp = () => endLayerDrag();
+    window.addEventListener('pointermove', handleMove);
+    window.addEventListener('pointerup', handleUp);
+    window.addEventListener('pointercancel', handleUp);
+    window.addEventListener('blur', handleUp);
+    return () => {
+      window.removeEventListener('pointermove', handleMove);
+      window.removeEventListener('pointerup', handleUp);
+      window.removeEventListener('pointercancel', handleUp);
+      window.removeEventListener('blur', handleUp);
+    };
+  }, [draggedIndex]);
+
+  const dropOnRow = (index: number) => {
+    const layer = displayLayers[index]?.layer;
+    if (!layer || draggedIndex === null || draggedIndex === index) return;
+    const draggedId = displayLayers[draggedIndex]?.layer.id;
+    const draggedLayer = draggedId ? layerMap.get(draggedId) : undefined;
+    const targetGroupId = layer.type === 'group' ? layer.id : layer.parentId || null;
+    const draggedParentId = draggedLayer?.parentId || null;
+    const canJoinGroup = Boolean(targetGroupId && draggedLayer && !draggedLayer.parentId && draggedLayer.type !== 'group' && draggedLayer.id !== targetGroupId);
+    const canLeaveGroup = Boolean(draggedLayer && draggedParentId && !targetGroupId);
+
+    if (canJoinGroup) {
+      const nextLayers = project.layers.map(l => {
+        if (l.id === draggedLayer!.id) return { ...l, parentId: targetGroupId as string };
+        if (l.id === targetGroupId) {
+          const nextChildren = Array.from(new Set([...(l.children || []), draggedLayer!.id]));
+          return { ...l, children: nextChildren };
+        }
+        if (l.type === 'group' && (l.children || []).includes(draggedLayer!.id)) {
+          return { ...l, children: (l.children || []).filter(id => id !== draggedLayer!.id) };
+        }
+        return l;
+      });
+
+      const normalized = nextLayers.map(l => {
+        if (l.type !== 'group') return l;
+        const bounds = getGroupBounds(nextLayers, l.children || []);
+        return { ...l, ...bounds };
+      });
+
+      onReorderLayers(normalized);
+    } else if (canLeaveGroup) {
+      const targetId = layer.id;
+      let nextLayers = project.layers.map(l => {
+        if (l.id === draggedLayer!.id) return { ...l, parentId: undefined };
+        if (l.id === draggedParentId) {
+          const nextChildren = (l.children || []).filter(id => id !== draggedLayer!.id);
+          return { ...l, children: nextChildren };
+        }
+        return l;
+      });
+
+      nextLayers = nextLayers.filter(l => l.type !== 'group' || (l.children || []).length > 0);
+
+      const nextLayerMap = new Map(nextLayers.map(l => [l.id, l]));
+      let newSorted = [...sortedLayers].filter(l => nextLayerMap.has(l.id));
+      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
+      const toIndex = newSorted.findIndex(l => l.id === targetId);
+      if (fromIndex !== -1 && toIndex !== -1) {
+        const [removed] = newSorted.splice(fromIndex, 1);
+        newSorted.splice(toIndex, 0, removed);
+      }
+
+      const normalized = newSorted.map(l => {
+        const updated = nextLayerMap.get(l.id) as Layer;
+        if (updated.type !== 'group') return updated;
+        const bounds = getGroupBounds(nextLayers, updated.children || []);
+        return { ...updated, ...bounds };
+      });
+
+      onReorderLayers([...normalized].reverse());
+    } else if (draggedId) {
+      const newSorted = [...sortedLayers];
+      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
+      const toIndex = newSorted.findIndex(l => l.id === layer.id);
+      if (fromIndex !== -1 && toIndex !== -1) {
+        const [removed] = newSorted.splice(fromIndex, 1);
+        newSorted.splice(toIndex, 0, removed);
+        onReorderLayers([...newSorted].reverse());
+      }
+    }
+  };
+
+  const dropAtEnd = () => {
+    if (draggedIndex === null) return;
+    const draggedId = displayLayers[draggedIndex]?.layer.id;
+    if (!draggedId) return;
+
+    const draggedLayer = layerMap.get(draggedId);
+    const draggedParentId = draggedLayer?.parentId || null;
+
+    if (draggedParentId) {
+      let nextLayers = project.layers.map(l => {
+        if (l.id === draggedId) return { ...l, parentId: undefined };
+        if (l.id === draggedParentId) {
+          return { ...l, children: (l.children || []).filter(id => id !== draggedId) };
+        }
+        return l;
+      });
+
+      nextLayers = nextLayers.filter(l => l.type !== 'group' || (l.children || []).length > 0);
+
+      const nextLayerMap = new Map(nextLayers.map(l => [l.id, l]));
+      const newSorted = [...sortedLayers].filter(l => nextLayerMap.has(l.id));
+      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
+
+      if (fromIndex !== -1) {
+        const [removed] = newSorted.splice(fromIndex, 1);
+        newSorted.push(removed);
+      }
+
+      const normalized = newSorted.map(l => {
+        const updated = nextLayerMap.get(l.id) as Layer;
+        if (updated.type !== 'group') return updated;
+        const bounds = getGroupBounds(nextLayers, updated.children || []);
+        return { ...updated, ...bounds };
+      });
+
+      onReorderLayers([...normalized].reverse());
+    } else {
+      const newSorted = [...sortedLayers];
+      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
+      if (fromIndex !== -1) {
+        const [removed] = newSorted.splice(fromIndex, 1);
+        newSorted.push(removed);
+        onReorderLayers([...newSorted].reverse());
+      }
+    }
+  };
+
   const menuSelection = contextMenu
     ? (selectedLayerIds.includes(contextMenu.layerId) ? selectedLayerIds : [contextMenu.layerId])
     : [];
@@ -857,83 +1028,9 @@ const LayersPanel: React.FC<LayersPanelProps> = ({
             return (
               <div
                 key={layer.id}
-                draggable
-                onDragStart={(e) => { setDraggedIndex(index); e.dataTransfer.effectAllowed = 'move'; }}
-                onDragOver={(e) => { e.preventDefault(); setDragOverIndex(index); }}
-                onDrop={(e) => {
-                  e.preventDefault();
-                  if (draggedIndex !== null && draggedIndex !== index) {
-                    const draggedId = displayLayers[draggedIndex]?.layer.id;
-                    const draggedLayer = draggedId ? layerMap.get(draggedId) : undefined;
-                    const targetGroupId = layer.type === 'group' ? layer.id : layer.parentId || null;
-                    const draggedParentId = draggedLayer?.parentId || null;
-                    const canJoinGroup = Boolean(targetGroupId && draggedLayer && !draggedLayer.parentId && draggedLayer.type !== 'group' && draggedLayer.id !== targetGroupId);
-                    const canLeaveGroup = Boolean(draggedLayer && draggedParentId && !targetGroupId);
-
-                    if (canJoinGroup) {
-                      const nextLayers = project.layers.map(l => {
-                        if (l.id === draggedLayer!.id) return { ...l, parentId: targetGroupId as string };
-                        if (l.id === targetGroupId) {
-                          const nextChildren = Array.from(new Set([...(l.children || []), draggedLayer!.id]));
-                          return { ...l, children: nextChildren };
-                        }
-                        if (l.type === 'group' && (l.children || []).includes(draggedLayer!.id)) {
-                          return { ...l, children: (l.children || []).filter(id => id !== draggedLayer!.id) };
-                        }
-                        return l;
-                      });
-
-                      const normalized = nextLayers.map(l => {
-                        if (l.type !== 'group') return l;
-                        const bounds = getGroupBounds(nextLayers, l.children || []);
-                        return { ...l, ...bounds };
-                      });
-
-                      onReorderLayers(normalized);
-                    } else if (canLeaveGroup) {
-                      const targetId = layer.id;
-                      let nextLayers = project.layers.map(l => {
-                        if (l.id === draggedLayer!.id) return { ...l, parentId: undefined };
-                        if (l.id === draggedParentId) {
-                          const nextChildren = (l.children || []).filter(id => id !== draggedLayer!.id);
-                          return { ...l, children: nextChildren };
-                        }
-                        return l;
-                      });
-
-                      nextLayers = nextLayers.filter(l => l.type !== 'group' || (l.children || []).length > 0);
-
-                      const nextLayerMap = new Map(nextLayers.map(l => [l.id, l]));
-                      let newSorted = [...sortedLayers].filter(l => nextLayerMap.has(l.id));
-                      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
-                      const toIndex = newSorted.findIndex(l => l.id === targetId);
-                      if (fromIndex !== -1 && toIndex !== -1) {
-                        const [removed] = newSorted.splice(fromIndex, 1);
-                        newSorted.splice(toIndex, 0, removed);
-                      }
-
-                      const normalized = newSorted.map(l => {
-                        const updated = nextLayerMap.get(l.id) as Layer;
-                        if (updated.type !== 'group') return updated;
-                        const bounds = getGroupBounds(nextLayers, updated.children || []);
-                        return { ...updated, ...bounds };
-                      });
-
-                      onReorderLayers([...normalized].reverse());
-                    } else if (draggedId) {
-                      const newSorted = [...sortedLayers];
-                      const fromIndex = newSorted.findIndex(l => l.id === draggedId);
-                      const toIndex = newSorted.findIndex(l => l.id === layer.id);
-                      if (fromIndex !== -1 && toIndex !== -1) {
-                        const [removed] = newSorted.splice(fromIndex, 1);
-                        newSorted.splice(toIndex, 0, removed);
-                        onReorderLayers([...newSorted].reverse());
-                      }
-                    }
-                  }
-                  setDraggedIndex(null); setDragOverIndex(null);
-                }}
-                onDragEnd={() => { setDraggedIndex(null); setDragOverIndex(null); }}
+                onPointerDown={(e) => startLayerDrag(e, index)}
+                onPointerEnter={() => { if (draggedIndex !== null) setDragOverIndex(index); }}
+                onPointerUp={() => { if (draggedIndex !== null) dropOnRow(index); }}
                 onClick={(e) => {
                   if (e.shiftKey) {
                     onSelectLayer(layer.id, (e.ctrlKey || e.metaKey) ? 'range-add' : 'range', selectableOrder);
@@ -942,7 +1039,7 @@ const LayersPanel: React.FC<LayersPanelProps> = ({
                   onSelectLayer(layer.id, e.ctrlKey || e.metaKey ? 'toggle' : 'replace');
                 }}
                 onContextMenu={(e) => handleLayerContextMenu(e, layer.id)}
-                className={`group relative flex items-center gap-2.5 p-2 rounded-md cursor-pointer transition-all ${
+                className={`group relative flex items-center gap-2.5 p-2 rounded-md cursor-pointer select-none transition-all ${
                   isSelected ? 'bg-blue-600 text-white shadow-md' : 'hover:bg-slate-800 text-slate-400'
                 } ${draggedIndex === index ? 'opacity-40' : ''} ${dragOverIndex === index && draggedIndex !== index ? 'border-t-2 border-blue-400' : ''} ${isChild ? 'pl-7' : ''}`}
               >
@@ -1002,65 +1099,8 @@ const LayersPanel: React.FC<LayersPanelProps> = ({
         })}
 
         <div
-          onDragOver={(e) => {
-            e.preventDefault();
-            setDragOverIndex(displayLayers.length);
-          }}
-          onDrop={(e) => {
-            e.preventDefault();
-            if (draggedIndex === null) return;
-
-            const draggedId = displayLayers[draggedIndex]?.layer.id;
-            if (!draggedId) {
-              setDraggedIndex(null);
-              setDragOverIndex(null);
-              return;
-            }
-
-            const draggedLayer = layerMap.get(draggedId);
-            const draggedParentId = draggedLayer?.parentId || null;
-
-            if (draggedParentId) {
-              let nextLayers = project.layers.map(l => {
-                if (l.id === draggedId) return { ...l, parentId: undefined };
-                if (l.id === draggedParentId) {
-                  return { ...l, children: (l.children || []).filter(id => id !== draggedId) };
-                }
-                return l;
-              });
-
-              nextLayers = nextLayers.filter(l => l.type !== 'group' || (l.children || []).length > 0);
-
-              const nextLayerMap = new Map(nextLayers.map(l => [l.id, l]));
-              const newSorted = [...sortedLayers].filter(l => nextLayerMap.has(l.id));
-              const fromIndex = newSorted.findIndex(l => l.id === draggedId);
-
-              if (fromIndex !== -1) {
-                const [removed] = newSorted.splice(fromIndex, 1);
-                newSorted.push(removed);
-              }
-
-              const normalized = newSorted.map(l => {
-                const updated = nextLayerMap.get(l.id) as Layer;
-                if (updated.type !== 'group') return updated;
-                const bounds = getGroupBounds(nextLayers, updated.children || []);
-                return { ...updated, ...bounds };
-              });
-
-              onReorderLayers([...normalized].reverse());
-            } else {
-              const newSorted = [...sortedLayers];
-              const fromIndex = newSorted.findIndex(l => l.id === draggedId);
-              if (fromIndex !== -1) {
-                const [removed] = newSorted.splice(fromIndex, 1);
-                newSorted.push(removed);
-                onReorderLayers([...newSorted].reverse());
-              }
-            }
-
-            setDraggedIndex(null);
-            setDragOverIndex(null);
-          }}
+          onPointerEnter={() => { if (draggedIndex !== null) setDragOverIndex(displayLayers.length); }}
+          onPointerUp={() => { if (draggedIndex !== null) dropAtEnd(); }}
           className={`h-6 rounded-md transition-all ${dragOverIndex === displayLayers.length ? 'border-t-2 border-blue-400 bg-blue-500/10' : 'border-t border-transparent'}`}
           title={t.sendToBack}
         />
@@ -1,6 +1,6 @@
 import React, { useEffect, useState } from 'react';
 import { Layout, X } from 'lucide-react';
-import { PresetRatio } from '../types.ts';
+import { PresetRatio, TemplateInfo } from '../types.ts';
 import { translations, Language } from '../translations.ts';
 
 interface PresetModalProps {
@@ -13,6 +13,8 @@ interface PresetModalProps {
   onSizeChange: (size: { width: number; height: number }) => void;
   onClose: () => void;
   onCreate: () => void;
+  templates?: TemplateInfo[];
+  onCreateFromTemplate?: (id: string) => void;
 }
 
 const clampSize = (value: number) => Math.max(10, Math.round(value));
@@ -26,7 +28,9 @@ const ProjectPresetModal: React.FC<PresetModalProps> = ({
   onSelectPreset,
   onSizeChange,
   onClose,
-  onCreate
+  onCreate,
+  templates = [],
+  onCreateFromTemplate
 }) => {
   const [widthInput, setWidthInput] = useState(() => String(size.width));
   const [heightInput, setHeightInput] = useState(() => String(size.height));
@@ -75,6 +79,31 @@ const ProjectPresetModal: React.FC<PresetModalProps> = ({
           </button>
         </div>
 
+        {templates.length > 0 && onCreateFromTemplate && (
+          <div className="space-y-3">
+            <div className="text-[10px] font-black uppercase tracking-widest text-slate-500">{t.startFromTemplate}</div>
+            <div className="grid grid-cols-2 md:grid-cols-4 gap-3">
+              {templates.map(template => (
+                <button
+                  key={template.id}
+                  type="button"
+                  title={template.description}
+                  onClick={() => onCreateFromTemplate(template.id)}
+                  className="group p-2 rounded-2xl border border-slate-800 bg-slate-900 hover:border-blue-500 hover:bg-slate-800/50 text-left transition-all"
+                >
+                  <div className="aspect-video rounded-xl bg-slate-950 overflow-hidden flex items-center justify-center">
+                    {template.thumbnail
+                      ? <img src={template.thumbnail} alt={template.name} className="max-w-full max-h-full object-contain" />
+                      : <Layout className="w-5 h-5 text-slate-600" />}
+                  </div>
+                  <div className="px-1 pt-2 text-sm font-bold text-slate-100 truncate">{template.name}</div>
+                  <div className="px-1 text-[10px] text-slate-500 truncate">{template.description}</div>
+                </button>
+              ))}
+            </div>
+          </div>
+        )}
+
         <div className="space-y-4">
           <div className="grid grid-cols-2 md:grid-cols-4 gap-3">
             {presets.map(preset => {
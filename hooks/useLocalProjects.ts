@@ -1,4 +1,4 @@
-import { useCallback, useEffect, useState } from 'react';
+import { useCallback, useEffect, useRef, useState } from 'react';
 import { ProjectState } from '../types.ts';
 
 const LOCAL_RECENT_KEY = 'coverflow_recent_local_files_v1';
@@ -16,12 +16,33 @@ type LocalRecentMeta = {
   lastOpenedAt: number;
 };
 
+// 另存为覆盖或删除项目文件时原文件的去向
+type DisplacedFile = {
+  path: string;
+  method: 'trash' | 'backup';
+  backupPath?: string;
+};
+
+export type RecoverySnapshot = {
+  id: string;
+  originalPath: string | null;
+  savedAt: number;
+  project: ProjectState;
+};
+
 type UseLocalProjectsOptions = {
   view: 'landing' | 'editor';
   parseFailedMessage: string;
+  migratedMessage: string;
+  readOnlyMessage: string;
+  shortcutFailedMessage: string;
+  changedExternallyMessage: string;
+  // {path} 替换为备份文件路径
+  backupCreatedMessage: string;
   showToast: (msg: string, type?: 'success' | 'error') => void;
   onOpenProject: (project: ProjectState) => void;
   onUpdateProject: (project: ProjectState) => void;
+  onRecoveryAvailable?: (snapshots: RecoverySnapshot[]) => void;
 };
 
 const readLocalRecentMeta = () => {
@@ -44,12 +65,22 @@ const writeLocalRecentMeta = (items: LocalRecentMeta[]) => {
 export const useLocalProjects = ({
   view,
   parseFailedMessage,
+  migratedMessage,
+  readOnlyMessage,
+  shortcutFailedMessage,
+  changedExternallyMessage,
+  backupCreatedMessage,
   showToast,
   onOpenProject,
-  onUpdateProject
+  onUpdateProject,
+  onRecoveryAvailable
 }: UseLocalProjectsOptions) => {
   const [localRecentItems, setLocalRecentItems] = useState<RecentProjectItem[]>([]);
   const [activeLocalFilePath, setActiveLocalFilePath] = useState<string | null>(null);
+  // 上次加载/保存时后端计算的内容哈希
+  const savedHashRef = useRef<string | null>(null);
+  // 当前文件被其他程序修改后尚未重新加载或覆盖保存
+  const [changedExternally, setChangedExternally] = useState(false);
   const isTauri = import.meta.env.VITE_APP_MODE === 'tauri';
 
   const removeLocalRecentByPath = useCallback((path: string) => {
@@ -74,11 +105,16 @@ export const useLocalProjects = ({
   const openLocalProjectFromPath = useCallback(async (path: string) => {
     if (!isTauri) return;
     try {
-      const { readTextFile } = await import('@tauri-apps/plugin-fs');
-      const text = await readTextFile(path);
-      const parsed = JSON.parse(text) as ProjectState;
-      if (!parsed?.id || !parsed.layers) throw new Error('Invalid project file');
+      const { invoke } = await import('@tauri-apps/api/core');
+      const loaded = await invoke<{ project: ProjectState; migrated: boolean; hash: string; readOnly: boolean }>('load_cfj', { path });
+      const parsed = loaded.project;
+      savedHashRef.current = loaded.hash;
+      setChangedExternally(false);
       onOpenProject(parsed);
+      // 旧版本文件已自动升级，提示保存为新格式
+      if (loaded.migrated) showToast(migratedMessage);
+      // 只读位置的文件保存时会被拒绝，提前告知
+      else if (loaded.readOnly) showToast(readOnlyMessage);
       setActiveLocalFilePath(path);
       updateLocalRecentMeta(path, parsed);
       setLocalRecentItems(prev => {
@@ -87,26 +123,28 @@ export const useLocalProjects = ({
       });
     } catch (err) {
       removeLocalRecentByPath(path);
-      showToast(parseFailedMessage, 'error');
+      // load_cfj 返回带类型的错误描述
+      showToast(typeof err === 'string' ? `${parseFailedMessage} (${err})` : parseFailedMessage, 'error');
     }
-  }, [isTauri, onOpenProject, parseFailedMessage, removeLocalRecentByPath, showToast, updateLocalRecentMeta]);
+  }, [isTauri, onOpenProject, parseFailedMessage, migratedMessage, readOnlyMessage, removeLocalRecentByPath, showToast, updateLocalRecentMeta]);
 
   const openLocalProjectDialog = useCallback(async () => {
     if (!isTauri) return;
     const { open } = await import('@tauri-apps/plugin-dialog');
     const selection = await open({
       multiple: false,
-      filters: [{ name: 'CoverFlow Project', extensions: ['cfj'] }]
+      filters: [{ name: 'CoverFlow Project', extensions: ['cfj', 'cfjz'] }]
     });
     if (typeof selection === 'string') {
       await openLocalProjectFromPath(selection);
     }
   }, [isTauri, openLocalProjectFromPath]);
 
-  const saveLocalProjectToFile = useCallback(async (path: string, projectData: ProjectState) => {
-    const { writeTextFile } = await import('@tauri-apps/plugin-fs');
+  const saveLocalProjectToFile = useCallback(async (path: string, projectData: ProjectState, saveAs = false) => {
+    const { invoke } = await import('@tauri-apps/api/core');
     const nextProject = { ...projectData, updatedAt: Date.now() };
-    await writeTextFile(path, JSON.stringify(nextProject, null, 2));
+    savedHashRef.current = await invoke<string>('save_cfj', { path, project: nextProject, saveAs });
+    setChangedExternally(false);
     onUpdateProject(nextProject);
     updateLocalRecentMeta(path, nextProject);
     setLocalRecentItems(prev => {
@@ -116,10 +154,108 @@ export const useLocalProjects = ({
     return nextProject;
   }, [onUpdateProject, updateLocalRecentMeta]);
 
+  const saveLocalProjectAs = useCallback(async (projectData: ProjectState) => {
+    if (!isTauri) return null;
+    const { save } = await import('@tauri-apps/plugin-dialog');
+    const selection = await save({
+      defaultPath: `${projectData.title || 'cover'}.cfj`,
+      filters: [{ name: 'CoverFlow Project', extensions: ['cfj', 'cfjz'] }]
+    });
+    if (typeof selection !== 'string') return null;
+    // .cfjz 由后端按扩展名压缩保存
+    const path = /\.cfjz?$/i.test(selection) ? selection : `${selection}.cfj`;
+    // 覆盖其他已有文件时后端先把它移到回收站
+    const nextProject = await saveLocalProjectToFile(path, projectData, path !== activeLocalFilePath);
+    setActiveLocalFilePath(path);
+    return nextProject;
+  }, [isTauri, activeLocalFilePath, saveLocalProjectToFile]);
+
+  // 另存副本：当前编辑的仍是原文件，引用的本地图片由后端一并复制
+  const saveLocalProjectCopy = useCallback(async (projectData: ProjectState) => {
+    if (!isTauri) return null;
+    const { save } = await import('@tauri-apps/plugin-dialog');
+    const selection = await save({
+      defaultPath: `${projectData.title || 'cover'} copy.cfj`,
+      filters: [{ name: 'CoverFlow Project', extensions: ['cfj', 'cfjz'] }]
+    });
+    if (typeof selection !== 'string') return null;
+    const path = /\.cfjz?$/i.test(selection) ? selection : `${selection}.cfj`;
+    const { invoke } = await import('@tauri-apps/api/core');
+    // 系统保存对话框已确认过覆盖
+    const copy = await invoke<ProjectState>('duplicate_project', {
+      project: projectData,
+      newPath: path,
+      sourcePath: activeLocalFilePath,
+      overwrite: true
+    });
+    updateLocalRecentMeta(path, copy);
+    setLocalRecentItems(prev => {
+      const next = prev.filter(item => item.localPath !== path);
+      return [{ project: copy, source: 'local', localPath: path }, ...next];
+    });
+    return path;
+  }, [isTauri, activeLocalFilePath, updateLocalRecentMeta]);
+
+  // 从最近列表删除本地文件：移到回收站，不支持时在原位置留下备份
+  const trashLocalProject = useCallback(async (path: string) => {
+    const { invoke } = await import('@tauri-apps/api/core');
+    const displaced = await invoke<DisplacedFile>('move_to_trash', { path });
+    removeLocalRecentByPath(path);
+    if (path === activeLocalFilePath) {
+      savedHashRef.current = null;
+      setChangedExternally(false);
+      setActiveLocalFilePath(null);
+    }
+    if (displaced.method === 'backup' && displaced.backupPath) {
+      showToast(backupCreatedMessage.replace('{path}', displaced.backupPath));
+    }
+  }, [activeLocalFilePath, backupCreatedMessage, removeLocalRecentByPath, showToast]);
+
+  // 恢复快照后保留原文件路径，下次保存直接覆盖原文件
+  const restoreRecovery = useCallback(async (snapshot: RecoverySnapshot) => {
+    const { invoke } = await import('@tauri-apps/api/core');
+    onOpenProject(snapshot.project);
+    savedHashRef.current = null;
+    setChangedExternally(false);
+    setActiveLocalFilePath(snapshot.originalPath);
+    await invoke('discard_recovery', { id: snapshot.id });
+  }, [onOpenProject]);
+
+  const discardRecovery = useCallback(async (snapshot: RecoverySnapshot) => {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('discard_recovery', { id: snapshot.id });
+  }, []);
+
   const clearActiveLocalFilePath = useCallback(() => {
+    savedHashRef.current = null;
+    setChangedExternally(false);
     setActiveLocalFilePath(null);
   }, []);
 
+  // 放弃未保存的修改，按磁盘上的内容重新打开当前文件
+  const reloadLocalProject = useCallback(async () => {
+    if (!isTauri || !activeLocalFilePath) return null;
+    const { invoke } = await import('@tauri-apps/api/core');
+    const loaded = await invoke<{ project: ProjectState; migrated: boolean; hash: string }>('reload_cfj', { path: activeLocalFilePath });
+    savedHashRef.current = loaded.hash;
+    setChangedExternally(false);
+    onOpenProject(loaded.project);
+    if (loaded.migrated) showToast(migratedMessage);
+    updateLocalRecentMeta(activeLocalFilePath, loaded.project);
+    setLocalRecentItems(prev => prev.map(item => (
+      item.localPath === activeLocalFilePath ? { ...item, project: loaded.project } : item
+    )));
+    return loaded.project;
+  }, [isTauri, activeLocalFilePath, migratedMessage, onOpenProject, showToast, updateLocalRecentMeta]);
+
+  // 与磁盘上的版本比较，键顺序、选中图层和时间戳不影响结果
+  const isLocalProjectDirty = useCallback(async (projectData: ProjectState) => {
+    if (!savedHashRef.current) return true;
+    const { invoke } = await import('@tauri-apps/api/core');
+    const hash = await invoke<string>('project_hash', { project: projectData });
+    return hash !== savedHashRef.current;
+  }, []);
+
   useEffect(() => {
     if (!isTauri || view !== 'landing') return;
     let active = true;
@@ -129,13 +265,13 @@ export const useLocalProjects = ({
         if (active) setLocalRecentItems([]);
         return;
       }
-      const { readTextFile } = await import('@tauri-apps/plugin-fs');
+      const { invoke } = await import('@tauri-apps/api/core');
       const nextItems: RecentProjectItem[] = [];
       const nextMeta: LocalRecentMeta[] = [];
       for (const item of meta) {
         try {
-          const text = await readTextFile(item.path);
-          const parsed = JSON.parse(text) as ProjectState;
+          // 由后端解析，.cfjz 会先解压
+          const parsed = await invoke<ProjectState>('preview_cfj', { path: item.path });
           if (!parsed?.id || !parsed.layers) continue;
           nextItems.push({ project: parsed, source: 'local', localPath: item.path });
           nextMeta.push({
@@ -158,32 +294,110 @@ export const useLocalProjects = ({
     };
   }, [isTauri, view]);
 
+  const openFromPathRef = useRef(openLocalProjectFromPath);
+  openFromPathRef.current = openLocalProjectFromPath;
+  const recoveryRef = useRef(onRecoveryAvailable);
+  recoveryRef.current = onRecoveryAvailable;
+  const shortcutFailedRef = useRef(() => {});
+  shortcutFailedRef.current = () => showToast(shortcutFailedMessage, 'error');
+  const changedExternallyRef = useRef(() => {});
+  changedExternallyRef.current = () => {
+    setChangedExternally(true);
+    showToast(changedExternallyMessage, 'error');
+  };
+  const replacedRef = useRef((_: DisplacedFile) => {});
+  replacedRef.current = (displaced: DisplacedFile) => {
+    if (displaced.method === 'backup' && displaced.backupPath) {
+      showToast(backupCreatedMessage.replace('{path}', displaced.backupPath));
+    }
+  };
+
+  // 监视当前打开的本地文件，被其他程序修改时提示
+  useEffect(() => {
+    if (!isTauri) return;
+    const sync = async () => {
+      try {
+        const { invoke } = await import('@tauri-apps/api/core');
+        if (activeLocalFilePath) {
+          await invoke('watch_project', { path: activeLocalFilePath });
+        } else {
+          await invoke('unwatch_project');
+          await invoke('revoke_project_scope');
+        }
+      } catch (err) {
+        // ignore watcher errors
+      }
+    };
+    sync();
+  }, [isTauri, activeLocalFilePath]);
+
   useEffect(() => {
     if (!isTauri) return;
+    let active = true;
+    let unlisten: (() => void) | null = null;
     const init = async () => {
       try {
-        const pendingFromGlobal = (window as any).__CFJ_PENDING__;
-        if (Array.isArray(pendingFromGlobal)) {
-          (window as any).__CFJ_PENDING__ = [];
-          for (const path of pendingFromGlobal) {
-            if (path) await openLocalProjectFromPath(path);
+        const { listen } = await import('@tauri-apps/api/event');
+        const { invoke } = await import('@tauri-apps/api/core');
+        // 处理完再确认，页面在处理途中被替换时后端会重发
+        const stopOpen = await listen<{ token: number; path: string }>('cfj://open', async (event) => {
+          const { token, path } = event.payload || {};
+          if (!path) return;
+          try {
+            await openFromPathRef.current(path);
+          } finally {
+            invoke('ack_open', { token }).catch(() => {});
           }
+        });
+        const stopRecovery = await listen<RecoverySnapshot[]>('recovery://available', (event) => {
+          if (event.payload?.length) recoveryRef.current?.(event.payload);
+        });
+        // 全局快捷键被其他应用占用时提示
+        const stopShortcut = await listen('shortcut://failed', () => shortcutFailedRef.current());
+        const stopChanged = await listen('file://changed-externally', () => changedExternallyRef.current());
+        // 另存为覆盖了已有文件；移到回收站时不打扰，留下备份时告知位置
+        const stopReplaced = await listen<DisplacedFile>('file://replaced', (event) => replacedRef.current(event.payload));
+        const stop = () => {
+          stopOpen();
+          stopRecovery();
+          stopShortcut();
+          stopChanged();
+          stopReplaced();
+        };
+        if (!active) {
+          stop();
+          return;
         }
+        unlisten = stop;
+        // 订阅完成后通知后端补发缓存的打开请求
+        await invoke('frontend_ready');
       } catch (err) {
         // ignore pending open errors
       }
     };
     init();
-  }, [isTauri, openLocalProjectFromPath]);
+    return () => {
+      active = false;
+      if (unlisten) unlisten();
+    };
+  }, [isTauri]);
 
   return {
     isTauri,
     localRecentItems,
     activeLocalFilePath,
+    changedExternally,
     openLocalProjectDialog,
     openLocalProjectFromPath,
     removeLocalRecentByPath,
+    trashLocalProject,
     saveLocalProjectToFile,
-    clearActiveLocalFilePath
+    saveLocalProjectAs,
+    saveLocalProjectCopy,
+    restoreRecovery,
+    discardRecovery,
+    clearActiveLocalFilePath,
+    isLocalProjectDirty,
+    reloadLocalProject
   };
 };
@@ -0,0 +1,294 @@
+// 模块：项目引用的本地图片文件（背景图与图片图层中的路径 / file:// 地址）及批量重新链接
+use crate::cfj::{BackgroundType, CfjProject, LayerType};
+use crate::{atomic, limits, render};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetRef {
+  // 背景图为 None
+  pub layer_id: Option<String>,
+  pub label: String,
+  pub href: String,
+}
+
+// data:、blob: 与网络地址不是本地文件
+pub fn local_path(href: &str) -> Option<PathBuf> {
+  let href = href.trim();
+  if href.is_empty() || render::is_remote(href) {
+    return None;
+  }
+  let lower = href.to_ascii_lowercase();
+  if lower.starts_with("file:") {
+    return tauri::Url::parse(href).ok()?.to_file_path().ok();
+  }
+  if lower.starts_with("data:") || lower.starts_with("blob:") || lower.contains("://") {
+    return None;
+  }
+  Some(PathBuf::from(href))
+}
+
+// 相对路径按项目文件所在目录解析
+pub fn resolve(path: &Path, base_dir: Option<&Path>) -> PathBuf {
+  match base_dir {
+    Some(dir) if path.is_relative() => dir.join(path),
+    _ => path.to_path_buf(),
+  }
+}
+
+pub fn references(project: &CfjProject) -> Vec<AssetRef> {
+  let mut refs = Vec::new();
+  if project.background.background_type == BackgroundType::Image {
+    refs.push(AssetRef {
+      layer_id: None,
+      label: "background".to_string(),
+      href: project.background.value.clone(),
+    });
+  }
+  for layer in &project.layers {
+    if layer.layer_type == LayerType::Image {
+      refs.push(AssetRef {
+        layer_id: Some(layer.id.clone()),
+        label: format!("layer \"{}\"", layer.name),
+        href: layer.content.clone(),
+      });
+    }
+  }
+  refs
+}
+
+// 按 layer_id 写回引用，背景图 layer_id 为 None
+pub fn replace(project: &mut CfjProject, asset: &AssetRef, href: String) {
+  match &asset.layer_id {
+    Some(id) => {
+      if let Some(layer) = project.layers.iter_mut().find(|layer| &layer.id == id) {
+        layer.content = href;
+      }
+    }
+    None => project.background.value = href,
+  }
+}
+
+// 写入 dir 下的新文件；同名文件内容一致时直接复用，否则加序号
+pub fn write_unique(dir: &Path, stem: &str, extension: &str, bytes: &[u8]) -> std::io::Result<PathBuf> {
+  std::fs::create_dir_all(dir)?;
+  let suffix = if extension.is_empty() { String::new() } else { format!(".{}", extension) };
+  let mut index = 1;
+  loop {
+    let name = if index == 1 {
+      format!("{}{}", stem, suffix)
+    } else {
+      format!("{}_{}{}", stem, index, suffix)
+    };
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+      atomic::write(&candidate, bytes)?;
+      return Ok(candidate);
+    }
+    if std::fs::read(&candidate).is_ok_and(|existing| existing == bytes) {
+      return Ok(candidate);
+    }
+    index += 1;
+  }
+}
+
+// 只保留指向本地文件且文件不存在的引用
+pub fn missing(project: &CfjProject, base_dir: Option<&Path>) -> Vec<(AssetRef, PathBuf)> {
+  references(project)
+    .into_iter()
+    .filter_map(|asset| {
+      let path = resolve(&local_path(&asset.href)?, base_dir);
+      (!path.is_file()).then_some((asset, path))
+    })
+    .collect()
+}
+
+// 目录层级与文件数上限，防止误选根目录时长时间扫描
+const MAX_SEARCH_DEPTH: usize = 8;
+const MAX_SEARCH_FILES: usize = 100_000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetRelink {
+  pub layer_id: Option<String>,
+  pub old_path: String,
+  // 按可信度排序：文件名完全一致（且大小一致）的在前
+  pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkResult {
+  pub relinks: Vec<AssetRelink>,
+  pub unresolved: Vec<String>,
+}
+
+fn collect_files(dir: &Path, depth: usize, recursive: bool, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    if files.len() >= MAX_SEARCH_FILES {
+      return;
+    }
+    // 不跟随符号链接目录，避免循环
+    let Ok(kind) = entry.file_type() else {
+      continue;
+    };
+    let path = entry.path();
+    if kind.is_dir() {
+      if recursive && depth < MAX_SEARCH_DEPTH {
+        collect_files(&path, depth + 1, recursive, files);
+      }
+    } else if path.is_file() {
+      files.push(path);
+    }
+  }
+}
+
+// 旧文件记录的原始大小（若有），用于在同名文件中排除误匹配
+fn size_hint(project: &CfjProject, layer_id: Option<&str>) -> Option<u64> {
+  let layer = project.layers.iter().find(|layer| Some(layer.id.as_str()) == layer_id)?;
+  layer.extra.get("fileSize")?.as_u64()
+}
+
+fn rank(candidates: &[PathBuf], name: &OsStr, size: Option<u64>) -> Vec<String> {
+  let mut exact: Vec<&PathBuf> = candidates.iter().filter(|path| path.file_name() == Some(name)).collect();
+  if exact.is_empty() {
+    let lower = name.to_string_lossy().to_lowercase();
+    exact = candidates
+      .iter()
+      .filter(|path| path.file_name().is_some_and(|file| file.to_string_lossy().to_lowercase() == lower))
+      .collect();
+  }
+  if let Some(size) = size {
+    let sized: Vec<&PathBuf> = exact
+      .iter()
+      .copied()
+      .filter(|path| std::fs::metadata(path).is_ok_and(|meta| meta.len() == size))
+      .collect();
+    if !sized.is_empty() {
+      exact = sized;
+    }
+  }
+  exact.iter().map(|path| path.to_string_lossy().into_owned()).collect()
+}
+
+// 只给出候选路径，不修改项目；由前端确认后再替换
+pub fn relink(project: &CfjProject, search_dirs: &[PathBuf], recursive: bool) -> RelinkResult {
+  let mut files = Vec::new();
+  for dir in search_dirs {
+    collect_files(dir, 0, recursive, &mut files);
+  }
+  let mut result = RelinkResult::default();
+  for (asset, path) in missing(project, None) {
+    // 保留原始写法，前端按原值查找替换
+    let old_path = asset.href.trim().to_string();
+    let candidates = match path.file_name() {
+      Some(name) => rank(&files, name, size_hint(project, asset.layer_id.as_deref())),
+      None => Vec::new(),
+    };
+    if candidates.is_empty() {
+      result.unresolved.push(old_path);
+    } else {
+      result.relinks.push(AssetRelink {
+        layer_id: asset.layer_id,
+        old_path,
+        candidates,
+      });
+    }
+  }
+  result
+}
+
+#[tauri::command]
+pub async fn relink_assets(
+  app: AppHandle,
+  project: CfjProject,
+  search_dirs: Vec<String>,
+  recursive: Option<bool>,
+) -> Result<RelinkResult, String> {
+  limits::check(&app, "relink_assets", std::slice::from_ref(&project))?;
+  let dirs: Vec<PathBuf> = search_dirs.into_iter().map(PathBuf::from).collect();
+  let recursive = recursive.unwrap_or(true);
+  let result = tauri::async_runtime::spawn_blocking(move || relink(&project, &dirs, recursive))
+    .await
+    .map_err(|err| err.to_string())?;
+  log::info!(
+    "relink_assets: {} relinked, {} unresolved",
+    result.relinks.len(),
+    result.unresolved.len()
+  );
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+
+  // 背景图与一个图片图层都指向已被移走的文件
+  fn moved_project(old_dir: &Path) -> CfjProject {
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.background.background_type = BackgroundType::Image;
+    project.background.value = old_dir.join("bg.png").to_string_lossy().into_owned();
+    let mut layer = project.layers[0].clone();
+    layer.id = "img".to_string();
+    layer.layer_type = LayerType::Image;
+    layer.content = tauri::Url::from_file_path(old_dir.join("photo.png")).unwrap().to_string();
+    layer.extra.insert("fileSize".to_string(), 5.into());
+    project.layers.push(layer);
+    project
+  }
+
+  #[test]
+  fn finds_assets_moved_into_subfolder() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_dir = dir.path().join("old");
+    let nested = dir.path().join("new").join("images");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("bg.png"), b"bg").unwrap();
+    // 同名文件按记录的大小挑出正确的一个
+    std::fs::write(nested.join("photo.png"), b"12345").unwrap();
+    std::fs::create_dir_all(dir.path().join("new").join("other")).unwrap();
+    std::fs::write(dir.path().join("new").join("other").join("photo.png"), b"123").unwrap();
+    let project = moved_project(&old_dir);
+
+    let result = relink(&project, &[dir.path().join("new")], true);
+    assert!(result.unresolved.is_empty(), "{:?}", result.unresolved);
+    assert_eq!(result.relinks.len(), 2);
+    assert_eq!(result.relinks[0].layer_id, None);
+    assert_eq!(result.relinks[0].old_path, project.background.value);
+    assert_eq!(result.relinks[0].candidates, vec![nested.join("bg.png").to_string_lossy().into_owned()]);
+    assert_eq!(result.relinks[1].layer_id.as_deref(), Some("img"));
+    assert_eq!(result.relinks[1].candidates, vec![nested.join("photo.png").to_string_lossy().into_owned()]);
+  }
+
+  #[test]
+  fn non_recursive_search_stays_in_top_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("images");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("bg.png"), b"bg").unwrap();
+    let project = moved_project(&dir.path().join("old"));
+
+    let result = relink(&project, &[dir.path().to_path_buf()], false);
+    assert!(result.relinks.is_empty());
+    assert_eq!(result.unresolved.len(), 2);
+  }
+
+  #[test]
+  fn existing_and_remote_assets_are_not_missing() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("bg.png"), b"bg").unwrap();
+    let mut project = moved_project(dir.path());
+    project.layers.last_mut().unwrap().content = "https://example.com/photo.png".to_string();
+    assert!(missing(&project, None).is_empty());
+    // 相对路径按项目所在目录解析
+    project.background.value = "bg.png".to_string();
+    assert!(missing(&project, Some(dir.path())).is_empty());
+    assert_eq!(missing(&project, Some(&dir.path().join("elsewhere"))).len(), 1);
+  }
+}
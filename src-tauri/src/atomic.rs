@@ -0,0 +1,139 @@
+// 模块：原子写入（同目录临时文件 + fsync + 重命名覆盖）
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn parent_dir(path: &Path) -> PathBuf {
+  match path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+    _ => PathBuf::from("."),
+  }
+}
+
+fn temp_path(dir: &Path, target: &Path) -> PathBuf {
+  let name = target
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_else(|| "file".to_string());
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.subsec_nanos())
+    .unwrap_or(0);
+  dir.join(format!(".{}.{}.{}.tmp", name, std::process::id(), nanos))
+}
+
+// 由 writer 写入内容；任一步失败都会删除临时文件，原文件保持不变
+pub fn write_with<F>(path: &Path, writer: F) -> io::Result<()>
+where
+  F: FnOnce(&mut File) -> io::Result<()>,
+{
+  let dir = parent_dir(path);
+  if !dir.is_dir() {
+    return Err(io::Error::new(
+      io::ErrorKind::NotFound,
+      format!("directory not found: {}", dir.display()),
+    ));
+  }
+
+  let tmp = temp_path(&dir, path);
+  let mut file = OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&tmp)
+    .map_err(|err| match err.kind() {
+      io::ErrorKind::PermissionDenied => io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("directory not writable: {}", dir.display()),
+      ),
+      _ => err,
+    })?;
+
+  let result = writer(&mut file)
+    .and_then(|_| file.flush())
+    .and_then(|_| file.sync_all());
+  drop(file);
+  if let Err(err) = result.and_then(|_| std::fs::rename(&tmp, path)) {
+    let _ = std::fs::remove_file(&tmp);
+    return Err(err);
+  }
+
+  #[cfg(unix)]
+  if let Ok(dir_handle) = File::open(&dir) {
+    let _ = dir_handle.sync_all();
+  }
+  Ok(())
+}
+
+// 原子写入需要在所在目录创建临时文件；目标已存在时还要求其本身可写，
+// 否则重命名会悄悄替换掉只读文件。不可写时返回 PermissionDenied
+pub fn check_writable(path: &Path) -> io::Result<()> {
+  let dir = parent_dir(path);
+  if !dir.is_dir() {
+    return Err(io::Error::new(
+      io::ErrorKind::NotFound,
+      format!("directory not found: {}", dir.display()),
+    ));
+  }
+  let denied = |what: String| io::Error::new(io::ErrorKind::PermissionDenied, what);
+  if path.exists() && OpenOptions::new().write(true).open(path).is_err() {
+    return Err(denied(format!("file is read-only: {}", path.display())));
+  }
+  let probe = temp_path(&dir, path);
+  match OpenOptions::new().write(true).create_new(true).open(&probe) {
+    Ok(file) => {
+      drop(file);
+      let _ = std::fs::remove_file(&probe);
+      Ok(())
+    }
+    Err(_) => Err(denied(format!("directory not writable: {}", dir.display()))),
+  }
+}
+
+pub fn write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+  write_with(path, |file| file.write_all(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn leftover_temp_files(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+      .unwrap()
+      .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().ends_with(".tmp"))
+      .count()
+  }
+
+  #[test]
+  fn replaces_file_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    std::fs::write(&path, "old").unwrap();
+    write(&path, b"new").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(leftover_temp_files(dir.path()), 0);
+  }
+
+  #[test]
+  fn failed_write_keeps_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    std::fs::write(&path, "original").unwrap();
+    let result = write_with(&path, |file| {
+      file.write_all(b"partial")?;
+      Err(io::Error::other("simulated failure"))
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+    assert_eq!(leftover_temp_files(dir.path()), 0);
+  }
+
+  #[test]
+  fn missing_directory_is_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("missing").join("cover.cfj");
+    assert_eq!(write(&path, b"x").unwrap_err().kind(), io::ErrorKind::NotFound);
+    assert_eq!(check_writable(&path).unwrap_err().kind(), io::ErrorKind::NotFound);
+  }
+}
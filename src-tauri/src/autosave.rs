@@ -0,0 +1,241 @@
+// 模块：自动保存恢复快照（崩溃后下次启动提示恢复）
+use crate::{atomic, limits};
+use crate::cfj::CfjProject;
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const RECOVERY_EVENT: &str = "recovery://available";
+
+const RECOVERY_DIR: &str = "recovery";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverySnapshot {
+  pub id: String,
+  pub original_path: Option<String>,
+  pub saved_at: u64,
+  pub project: CfjProject,
+}
+
+// 启动时扫描到的快照，等前端就绪后再发送
+#[derive(Default)]
+pub struct PendingRecovery(Mutex<Vec<RecoverySnapshot>>);
+
+fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+pub fn recovery_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_data_dir()
+    .map(|dir| dir.join(RECOVERY_DIR))
+    .map_err(|err| err.to_string())
+}
+
+// 项目 id 来自前端，用其哈希作文件名：既是安全字符，不同 id 也不会落到同一个文件
+fn snapshot_file(dir: &Path, id: &str) -> PathBuf {
+  dir.join(format!("{}.json", blake3::hash(id.as_bytes()).to_hex()))
+}
+
+fn modified_ms(path: &Path) -> Option<u64> {
+  let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+  modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+// 未保存过的项目总是可恢复；已保存的项目仅当快照比文件新时才需要恢复
+fn is_newer(snapshot: &RecoverySnapshot) -> bool {
+  match &snapshot.original_path {
+    None => true,
+    Some(path) => modified_ms(Path::new(path)).is_none_or(|saved| snapshot.saved_at > saved),
+  }
+}
+
+pub fn scan_dir(dir: &Path) -> Vec<RecoverySnapshot> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut snapshots = Vec::new();
+  for entry in entries.flatten() {
+    let file = entry.path();
+    if file.extension().and_then(|ext| ext.to_str()) != Some("json") {
+      continue;
+    }
+    let snapshot = std::fs::read_to_string(&file)
+      .ok()
+      .and_then(|text| serde_json::from_str::<RecoverySnapshot>(&text).ok());
+    match snapshot {
+      Some(snapshot) if is_newer(&snapshot) => {
+        // 旧版本按清理后的 id 命名，改成现在的文件名，之后才能按 id 清除
+        let expected = snapshot_file(dir, &snapshot.id);
+        if file != expected {
+          let _ = std::fs::rename(&file, &expected);
+        }
+        snapshots.push(snapshot);
+      }
+      // 损坏或已过期的快照直接清理
+      _ => {
+        let _ = std::fs::remove_file(&file);
+      }
+    }
+  }
+  snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.saved_at));
+  snapshots
+}
+
+pub fn scan(app: &AppHandle) {
+  let Ok(dir) = recovery_dir(app) else {
+    return;
+  };
+  let snapshots = scan_dir(&dir);
+  if !snapshots.is_empty() {
+    log::info!("found {} recovery snapshot(s)", snapshots.len());
+  }
+  if let Ok(mut pending) = app.state::<PendingRecovery>().0.lock() {
+    *pending = snapshots;
+  }
+}
+
+// 前端订阅就绪后调用，只发送一次
+pub fn announce(app: &AppHandle) {
+  let snapshots = match app.state::<PendingRecovery>().0.lock() {
+    Ok(mut pending) => std::mem::take(&mut *pending),
+    Err(_) => return,
+  };
+  if snapshots.is_empty() {
+    return;
+  }
+  if let Err(err) = app.emit(RECOVERY_EVENT, &snapshots) {
+    log::error!("emit {} failed: {}", RECOVERY_EVENT, err);
+  }
+}
+
+fn write_snapshot(dir: &Path, snapshot: &RecoverySnapshot) -> std::io::Result<()> {
+  std::fs::create_dir_all(dir)?;
+  atomic::write_with(&snapshot_file(dir, &snapshot.id), |handle| {
+    let mut writer = BufWriter::new(handle);
+    serde_json::to_writer(&mut writer, snapshot)?;
+    writer.flush()
+  })
+}
+
+fn remove_snapshot(dir: &Path, id: &str) -> std::io::Result<()> {
+  std::fs::remove_file(snapshot_file(dir, id))
+}
+
+pub fn clear(app: &AppHandle, id: &str) {
+  let Ok(dir) = recovery_dir(app) else {
+    return;
+  };
+  match remove_snapshot(&dir, id) {
+    Ok(()) => log::debug!("recovery snapshot cleared: {}", id),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+    Err(err) => log::warn!("recovery snapshot not cleared: {}: {}", id, err),
+  }
+}
+
+#[tauri::command]
+pub fn autosave(app: AppHandle, project: CfjProject, original_path: Option<String>) -> Result<(), String> {
+  limits::check(&app, "autosave", std::slice::from_ref(&project))?;
+  let dir = recovery_dir(&app)?;
+  let snapshot = RecoverySnapshot {
+    id: project.id.clone(),
+    original_path,
+    saved_at: now_ms(),
+    project,
+  };
+  write_snapshot(&dir, &snapshot).map_err(|err| {
+    log::warn!("autosave failed: {}: {}", snapshot.id, err);
+    err.to_string()
+  })
+}
+
+#[tauri::command]
+pub fn discard_recovery(app: AppHandle, id: String) {
+  clear(&app, &id);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+
+  fn snapshot(id: &str, original_path: Option<&Path>, saved_at: u64) -> RecoverySnapshot {
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.id = id.to_string();
+    RecoverySnapshot {
+      id: id.to_string(),
+      original_path: original_path.map(|path| path.to_string_lossy().into_owned()),
+      saved_at,
+      project,
+    }
+  }
+
+  fn ids(snapshots: &[RecoverySnapshot]) -> Vec<&str> {
+    snapshots.iter().map(|snapshot| snapshot.id.as_str()).collect()
+  }
+
+  #[test]
+  fn never_saved_project_is_offered() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(dir.path(), &snapshot("draft", None, 1)).unwrap();
+    assert_eq!(ids(&scan_dir(dir.path())), ["draft"]);
+  }
+
+  #[test]
+  fn snapshot_is_kept_only_when_newer_than_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let recovery = dir.path().join("recovery");
+    let project = dir.path().join("cover.cfj");
+    std::fs::write(&project, "{}").unwrap();
+    let saved = modified_ms(&project).unwrap();
+
+    write_snapshot(&recovery, &snapshot("newer", Some(&project), saved + 1000)).unwrap();
+    write_snapshot(&recovery, &snapshot("older", Some(&project), saved - 1000)).unwrap();
+    // 原文件已不存在时也提示恢复
+    write_snapshot(&recovery, &snapshot("moved", Some(&dir.path().join("gone.cfj")), 1)).unwrap();
+    assert_eq!(ids(&scan_dir(&recovery)), ["newer", "moved"]);
+    // 过期的快照已删除
+    assert!(!snapshot_file(&recovery, "older").exists());
+    assert!(snapshot_file(&recovery, "newer").exists());
+  }
+
+  #[test]
+  fn saving_clears_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    write_snapshot(dir.path(), &snapshot("draft", None, 1)).unwrap();
+    remove_snapshot(dir.path(), "draft").unwrap();
+    assert!(scan_dir(dir.path()).is_empty());
+    assert_eq!(remove_snapshot(dir.path(), "draft").unwrap_err().kind(), std::io::ErrorKind::NotFound);
+  }
+
+  #[test]
+  fn similar_ids_do_not_share_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    for id in ["a/b", "a_b", "a:b", "../a"] {
+      write_snapshot(dir.path(), &snapshot(id, None, 1)).unwrap();
+    }
+    assert_ne!(snapshot_file(dir.path(), "a/b"), snapshot_file(dir.path(), "a_b"));
+    let mut found = ids(&scan_dir(dir.path())).into_iter().map(str::to_string).collect::<Vec<_>>();
+    found.sort();
+    assert_eq!(found, ["../a", "a/b", "a:b", "a_b"]);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 4);
+  }
+
+  #[test]
+  fn legacy_file_names_are_renamed() {
+    let dir = tempfile::tempdir().unwrap();
+    let legacy = dir.path().join("old_id.json");
+    std::fs::write(&legacy, serde_json::to_string(&snapshot("old id", None, 1)).unwrap()).unwrap();
+    assert_eq!(ids(&scan_dir(dir.path())), ["old id"]);
+    assert!(!legacy.exists());
+    remove_snapshot(dir.path(), "old id").unwrap();
+  }
+}
@@ -0,0 +1,880 @@
+// 模块：.cfj 项目文件（与前端 ProjectState 保持一致）
+use crate::{atomic, autosave, limits, recent, recycle, scope, watcher};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::time::SystemTime;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerType {
+  Svg,
+  Text,
+  Image,
+  Group,
+  Decoration,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FontWeight {
+  Number(f64),
+  Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextGradient {
+  pub enabled: bool,
+  pub from: String,
+  pub to: String,
+  pub angle: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextShadow {
+  pub enabled: bool,
+  pub color: String,
+  pub blur: f64,
+  pub offset_x: f64,
+  pub offset_y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Layer {
+  pub id: String,
+  pub name: String,
+  #[serde(rename = "type")]
+  pub layer_type: LayerType,
+  pub content: String,
+  pub x: f64,
+  pub y: f64,
+  pub width: f64,
+  pub height: f64,
+  #[serde(default)]
+  pub rotation: f64,
+  #[serde(default)]
+  pub z_index: f64,
+  #[serde(default = "default_true")]
+  pub visible: bool,
+  #[serde(default)]
+  pub locked: bool,
+  #[serde(default = "default_opacity")]
+  pub opacity: f64,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub color: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub font_size: Option<f64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub font_family: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub font_weight: Option<FontWeight>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub font_style: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text_align: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub writing_mode: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub ratio_locked: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text_gradient: Option<TextGradient>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text_shadow: Option<TextShadow>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub children: Option<Vec<String>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub parent_id: Option<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundType {
+  Color,
+  Gradient,
+  Image,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundConfig {
+  #[serde(rename = "type")]
+  pub background_type: BackgroundType,
+  pub value: String,
+  #[serde(default = "default_overlay_type")]
+  pub overlay_type: String,
+  #[serde(default)]
+  pub overlay_color: String,
+  #[serde(default)]
+  pub overlay_opacity: f64,
+  #[serde(default = "default_overlay_scale")]
+  pub overlay_scale: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanvasConfig {
+  pub width: f64,
+  pub height: f64,
+  #[serde(default)]
+  pub ratio: String,
+}
+
+// 项目引用的字体文件：path 为本机绝对路径，data 为 base64 或 data URL（二选一）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontAsset {
+  pub family: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub weight: Option<u16>,
+  #[serde(default)]
+  pub italic: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub path: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CfjProject {
+  pub id: String,
+  pub title: String,
+  pub layers: Vec<Layer>,
+  pub background: BackgroundConfig,
+  pub canvas_config: CanvasConfig,
+  #[serde(default)]
+  pub selected_layer_id: Option<String>,
+  #[serde(default)]
+  pub updated_at: f64,
+  // 导出时写入的应用版本号，本地保存的文件可能没有
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+  // 文件结构版本，保存时写入
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub schema_version: Option<u32>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub fonts: Vec<FontAsset>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedProject {
+  pub project: CfjProject,
+  // 为 true 时前端应提示用户另存为新格式
+  pub migrated: bool,
+  pub from_schema: u32,
+  pub hash: String,
+  // 文件或所在目录不可写（只读磁盘映像、锁定的网络共享等），前端应改为另存为
+  pub read_only: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+fn default_opacity() -> f64 {
+  1.0
+}
+
+fn default_overlay_type() -> String {
+  "none".to_string()
+}
+
+fn default_overlay_scale() -> f64 {
+  20.0
+}
+
+#[derive(Debug)]
+pub enum CfjError {
+  NotFound(String),
+  // 重新加载时文件已被删除或移走
+  SourceMissing(String),
+  Io(String),
+  InvalidUtf8(String),
+  Schema(String),
+  UnknownVersion(String),
+  NotWritable(String),
+  // 保存前检测到目标只读，与写入过程中的失败区分开
+  ReadOnly(String),
+  Write(String),
+  Decompress(String),
+  TooLarge(u64),
+}
+
+impl fmt::Display for CfjError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CfjError::NotFound(path) => write!(f, "file not found: {}", path),
+      CfjError::SourceMissing(path) => write!(f, "source missing: {} was deleted or moved since it was opened", path),
+      CfjError::Io(detail) => write!(f, "read failed: {}", detail),
+      CfjError::InvalidUtf8(path) => write!(f, "invalid UTF-8: {}", path),
+      CfjError::Schema(detail) => write!(f, "schema mismatch: {}", detail),
+      CfjError::UnknownVersion(version) => write!(f, "unknown version: {}", version),
+      CfjError::NotWritable(detail) => write!(f, "not writable: {}", detail),
+      CfjError::ReadOnly(detail) => write!(f, "read-only: {}", detail),
+      CfjError::Write(detail) => write!(f, "write failed: {}", detail),
+      CfjError::Decompress(detail) => write!(f, "decompress failed: {}", detail),
+      CfjError::TooLarge(limit) => write!(f, "decompressed project exceeds {} bytes", limit),
+    }
+  }
+}
+
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+  version
+    .trim()
+    .trim_start_matches('v')
+    .split('.')
+    .map(|part| part.parse::<u64>().ok())
+    .collect()
+}
+
+// 拒绝无法识别或比当前应用更新的版本
+fn check_version(version: &str) -> Result<Vec<u64>, MigrationError> {
+  let current = parse_version(env!("CARGO_PKG_VERSION")).unwrap_or_default();
+  match parse_version(version) {
+    Some(parsed) if parsed <= current => Ok(parsed),
+    _ => Err(MigrationError::UnknownVersion(version.to_string())),
+  }
+}
+
+pub const SCHEMA_VERSION: u32 = 3;
+
+// .cfjz 为 gzip 压缩的同一 JSON 结构
+pub const EXTENSIONS: [&str; 2] = ["cfj", "cfjz"];
+pub const COMPRESSED_EXTENSION: &str = "cfjz";
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const MAX_DECOMPRESSED_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum MigrationError {
+  UnknownVersion(String),
+  Schema(String),
+}
+
+impl fmt::Display for MigrationError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MigrationError::UnknownVersion(version) => write!(f, "unknown version: {}", version),
+      MigrationError::Schema(detail) => write!(f, "schema mismatch: {}", detail),
+    }
+  }
+}
+
+impl From<MigrationError> for CfjError {
+  fn from(err: MigrationError) -> Self {
+    match err {
+      MigrationError::UnknownVersion(version) => CfjError::UnknownVersion(version),
+      MigrationError::Schema(detail) => CfjError::Schema(detail),
+    }
+  }
+}
+
+// 优先使用 schemaVersion；旧文件按导出时写入的应用版本推断
+// v1：2.0.0 之前（canvas 字段、背景为字符串）；v2：4.0.0 之前（背景叠加层为嵌套对象）
+pub fn detect_schema(raw: &Map<String, Value>) -> Result<u32, MigrationError> {
+  if let Some(schema) = raw.get("schemaVersion") {
+    return match schema.as_u64() {
+      Some(schema) if (1..=SCHEMA_VERSION as u64).contains(&schema) => Ok(schema as u32),
+      _ => Err(MigrationError::UnknownVersion(format!("schema {}", schema))),
+    };
+  }
+  if let Some(version) = raw.get("version").and_then(Value::as_str) {
+    let parsed = check_version(version)?;
+    return Ok(if parsed < vec![2] {
+      1
+    } else if parsed < vec![4] {
+      2
+    } else {
+      SCHEMA_VERSION
+    });
+  }
+  // 未写入版本号的文件来自本地保存，只有带 canvas 字段的才是旧结构
+  if raw.contains_key("canvas") && !raw.contains_key("canvasConfig") {
+    return Ok(1);
+  }
+  Ok(SCHEMA_VERSION)
+}
+
+fn background_type_of(value: &str) -> &'static str {
+  let lower = value.trim().to_ascii_lowercase();
+  if lower.contains("gradient(") {
+    "gradient"
+  } else if lower.starts_with("url(") || lower.starts_with("data:") || lower.starts_with("http") {
+    "image"
+  } else {
+    "color"
+  }
+}
+
+// v1 → v2：canvas 改名为 canvasConfig，背景由字符串改为对象，补齐图层 zIndex
+fn migrate_v1(raw: &mut Map<String, Value>) {
+  if let Some(canvas) = raw.remove("canvas") {
+    raw.entry("canvasConfig").or_insert(canvas);
+  }
+  if let Some(Value::String(value)) = raw.get("background").cloned() {
+    let mut background = Map::new();
+    background.insert("type".to_string(), Value::from(background_type_of(&value)));
+    background.insert("value".to_string(), Value::from(value));
+    raw.insert("background".to_string(), Value::Object(background));
+  }
+  if let Some(Value::Array(layers)) = raw.get_mut("layers") {
+    for (index, layer) in layers.iter_mut().enumerate() {
+      if let Some(layer) = layer.as_object_mut() {
+        layer.entry("zIndex").or_insert_with(|| Value::from(index + 1));
+      }
+    }
+  }
+}
+
+// v2 → v3：背景叠加层展开为 overlay* 字段，文字渐变改名为 textGradient，补齐画布比例
+fn migrate_v2(raw: &mut Map<String, Value>) {
+  if let Some(background) = raw.get_mut("background").and_then(Value::as_object_mut) {
+    if let Some(Value::Object(overlay)) = background.remove("overlay") {
+      for (from, to) in [("type", "overlayType"), ("color", "overlayColor"), ("opacity", "overlayOpacity"), ("scale", "overlayScale")] {
+        if let Some(value) = overlay.get(from) {
+          background.entry(to).or_insert_with(|| value.clone());
+        }
+      }
+    }
+  }
+  if let Some(Value::Array(layers)) = raw.get_mut("layers") {
+    for layer in layers.iter_mut().filter_map(Value::as_object_mut) {
+      if let Some(gradient) = layer.remove("gradient") {
+        layer.entry("textGradient").or_insert(gradient);
+      }
+    }
+  }
+  if let Some(canvas) = raw.get_mut("canvasConfig").and_then(Value::as_object_mut) {
+    let missing = canvas.get("ratio").and_then(Value::as_str).is_none_or(str::is_empty);
+    if missing {
+      let width = canvas.get("width").and_then(Value::as_f64).unwrap_or(0.0);
+      let height = canvas.get("height").and_then(Value::as_f64).unwrap_or(0.0);
+      canvas.insert("ratio".to_string(), Value::from(format!("{}:{}", width, height)));
+    }
+  }
+}
+
+// 依次执行迁移步骤（v1 → v2 → 当前）
+pub fn migrate(raw: Value) -> Result<CfjProject, MigrationError> {
+  let Value::Object(mut raw) = raw else {
+    return Err(MigrationError::Schema("expected a JSON object".to_string()));
+  };
+  let from = detect_schema(&raw)?;
+  if from < 2 {
+    migrate_v1(&mut raw);
+  }
+  if from < 3 {
+    migrate_v2(&mut raw);
+  }
+  serde_json::from_value(Value::Object(raw)).map_err(|err| MigrationError::Schema(err.to_string()))
+}
+
+// 只表示文件格式或界面状态、不算内容修改的字段
+const HASH_IGNORED_FIELDS: [&str; 3] = ["schemaVersion", "selectedLayerId", "updatedAt"];
+
+// 键按字典序输出、不带空白，保证相同内容得到相同文本
+fn write_canonical(value: &Value, out: &mut String) {
+  match value {
+    Value::Object(map) => {
+      let mut entries: Vec<_> = map.iter().collect();
+      entries.sort_by(|a, b| a.0.cmp(b.0));
+      out.push('{');
+      for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+          out.push(',');
+        }
+        out.push_str(&Value::String(key.clone()).to_string());
+        out.push(':');
+        write_canonical(value, out);
+      }
+      out.push('}');
+    }
+    Value::Array(items) => {
+      out.push('[');
+      for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+          out.push(',');
+        }
+        write_canonical(item, out);
+      }
+      out.push(']');
+    }
+    other => out.push_str(&other.to_string()),
+  }
+}
+
+pub fn canonical_json(project: &CfjProject) -> String {
+  let mut value = serde_json::to_value(project).unwrap_or(Value::Null);
+  if let Value::Object(map) = &mut value {
+    for field in HASH_IGNORED_FIELDS {
+      map.remove(field);
+    }
+  }
+  let mut out = String::new();
+  write_canonical(&value, &mut out);
+  out
+}
+
+// 前端与上次加载/保存时的哈希比较，判断是否有未保存的修改
+pub fn content_hash(project: &CfjProject) -> String {
+  blake3::hash(canonical_json(project).as_bytes()).to_hex().to_string()
+}
+
+// 与前端 generateId 相同：9 位小写字母数字
+pub fn new_id() -> String {
+  let mut value = RandomState::new().hash_one(SystemTime::now());
+  let mut id = String::with_capacity(9);
+  for _ in 0..9 {
+    id.push(char::from_digit((value % 36) as u32, 36).unwrap_or('0'));
+    value /= 36;
+  }
+  id
+}
+
+// 复制项目时换成新的项目与图层 id，父子关系与选中图层同步替换
+pub fn reassign_ids(project: &mut CfjProject) {
+  let ids: HashMap<String, String> = project.layers.iter().map(|layer| (layer.id.clone(), new_id())).collect();
+  let remap = |id: &mut String| {
+    if let Some(next) = ids.get(id.as_str()) {
+      *id = next.clone();
+    }
+  };
+  project.id = new_id();
+  for layer in &mut project.layers {
+    remap(&mut layer.id);
+    layer.parent_id.iter_mut().for_each(remap);
+    layer.children.iter_mut().flatten().for_each(remap);
+  }
+  project.selected_layer_id.iter_mut().for_each(remap);
+}
+
+pub fn parse(text: &str) -> Result<LoadedProject, CfjError> {
+  let raw: Value = serde_json::from_str(text).map_err(|err| CfjError::Schema(err.to_string()))?;
+  let from_schema = match &raw {
+    Value::Object(map) => detect_schema(map)?,
+    _ => SCHEMA_VERSION,
+  };
+  let project = migrate(raw)?;
+  Ok(LoadedProject {
+    hash: content_hash(&project),
+    project,
+    migrated: from_schema < SCHEMA_VERSION,
+    from_schema,
+    read_only: false,
+  })
+}
+
+pub fn is_project_path(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| EXTENSIONS.iter().any(|known| ext.eq_ignore_ascii_case(known)))
+}
+
+pub fn is_compressed_path(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| ext.eq_ignore_ascii_case(COMPRESSED_EXTENSION))
+}
+
+// 多读 1 字节判断是否超限，防止压缩炸弹占满内存
+pub fn decompress(bytes: &[u8], limit: u64) -> Result<Vec<u8>, CfjError> {
+  let mut out = Vec::new();
+  GzDecoder::new(bytes)
+    .take(limit + 1)
+    .read_to_end(&mut out)
+    .map_err(|err| CfjError::Decompress(err.to_string()))?;
+  if out.len() as u64 > limit {
+    return Err(CfjError::TooLarge(limit));
+  }
+  Ok(out)
+}
+
+// 按文件头识别 gzip，扩展名写错的文件也能打开
+pub fn read_text(path: &Path) -> Result<String, CfjError> {
+  let bytes = std::fs::read(path).map_err(|err| match err.kind() {
+    std::io::ErrorKind::NotFound => CfjError::NotFound(path.display().to_string()),
+    _ => CfjError::Io(err.to_string()),
+  })?;
+  let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+    decompress(&bytes, MAX_DECOMPRESSED_BYTES)?
+  } else {
+    bytes
+  };
+  String::from_utf8(bytes).map_err(|_| CfjError::InvalidUtf8(path.display().to_string()))
+}
+
+pub fn load_report(path: &Path) -> Result<LoadedProject, CfjError> {
+  parse(&read_text(path)?)
+}
+
+// 与 load_report 相同，只是文件不存在时返回 SourceMissing
+pub fn reload(path: &Path) -> Result<LoadedProject, CfjError> {
+  load_report(path).map_err(|err| match err {
+    CfjError::NotFound(path) => CfjError::SourceMissing(path),
+    err => err,
+  })
+}
+
+pub fn load(path: &Path) -> Result<CfjProject, CfjError> {
+  load_report(path).map(|loaded| loaded.project)
+}
+
+// 可写性探测，正常为 atomic::check_writable，测试中可替换
+pub type WritableCheck = fn(&Path) -> std::io::Result<()>;
+
+// 打开给用户编辑（load_cfj/reload_cfj）：在加载结果上标记是否只读；
+// 预览、模板与副本的加载不调用，免得在磁盘上留下探测文件
+pub fn open_for_edit(path: &Path, reopen: bool, writable: WritableCheck) -> Result<LoadedProject, CfjError> {
+  let mut loaded = if reopen { reload(path)? } else { load_report(path)? };
+  loaded.read_only = writable(path).is_err();
+  Ok(loaded)
+}
+
+// 压缩格式不缩进，体积主要来自内嵌图片
+pub fn save(path: &Path, project: &CfjProject, compress: bool) -> Result<(), CfjError> {
+  save_with(path, project, compress, atomic::check_writable)
+}
+
+pub fn save_with(path: &Path, project: &CfjProject, compress: bool, writable: WritableCheck) -> Result<(), CfjError> {
+  if let Err(err) = writable(path) {
+    if err.kind() == std::io::ErrorKind::PermissionDenied {
+      return Err(CfjError::ReadOnly(err.to_string()));
+    }
+  }
+  let mut project = project.clone();
+  project.schema_version = Some(SCHEMA_VERSION);
+  atomic::write_with(path, |file| {
+    if compress {
+      let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+      serde_json::to_writer(&mut encoder, &project)?;
+      encoder.finish()?.flush()
+    } else {
+      let mut writer = BufWriter::new(file);
+      serde_json::to_writer_pretty(&mut writer, &project)?;
+      writer.flush()
+    }
+  })
+  .map_err(|err| match err.kind() {
+    std::io::ErrorKind::PermissionDenied => CfjError::NotWritable(err.to_string()),
+    _ => CfjError::Write(err.to_string()),
+  })
+}
+
+#[tauri::command]
+pub fn load_cfj(app: AppHandle, path: String) -> Result<LoadedProject, String> {
+  let loaded = open_for_edit(Path::new(&path), false, atomic::check_writable).map_err(|err| {
+    log::warn!("load_cfj failed: {}: {}", path, err);
+    err.to_string()
+  })?;
+  if loaded.read_only {
+    log::info!("load_cfj: {} is read-only", path);
+  }
+  if loaded.migrated {
+    log::info!("load_cfj: {} (migrated from schema {})", path, loaded.from_schema);
+  } else {
+    log::info!("load_cfj: {}", path);
+  }
+  recent::push(&app, &path);
+  scope::grant_project(&app, Path::new(&path));
+  Ok(loaded)
+}
+
+// 首页最近项目的预览：只解析（含 .cfjz），不记入最近列表也不授权目录，且仅限最近列表中的文件
+#[tauri::command]
+pub fn preview_cfj(app: AppHandle, path: String) -> Result<CfjProject, String> {
+  let path = recent::require(&app, &path)?;
+  load(Path::new(&path)).map_err(|err| err.to_string())
+}
+
+// 放弃内存中的修改，按磁盘上的内容重新加载；前端用返回的 hash 重置未保存状态
+#[tauri::command]
+pub fn reload_cfj(app: AppHandle, path: String) -> Result<LoadedProject, String> {
+  let loaded = open_for_edit(Path::new(&path), true, atomic::check_writable).map_err(|err| {
+    log::warn!("reload_cfj failed: {}: {}", path, err);
+    err.to_string()
+  })?;
+  watcher::reset_baseline(&app, Path::new(&path));
+  scope::grant_project(&app, Path::new(&path));
+  log::info!("reload_cfj: {}{}", path, if loaded.migrated { " (migrated)" } else { "" });
+  Ok(loaded)
+}
+
+// compress 缺省时按扩展名决定（.cfjz 压缩）；save_as 为 true 时已有的目标文件先移到回收站
+#[tauri::command]
+pub fn save_cfj(
+  app: AppHandle,
+  path: String,
+  project: CfjProject,
+  compress: Option<bool>,
+  save_as: Option<bool>,
+) -> Result<String, String> {
+  limits::check(&app, "save_cfj", std::slice::from_ref(&project))?;
+  let compress = compress.unwrap_or_else(|| is_compressed_path(Path::new(&path)));
+  let displaced = if save_as.unwrap_or(false) {
+    recycle::before_overwrite(&app, Path::new(&path))?
+  } else {
+    None
+  };
+  save(Path::new(&path), &project, compress).map_err(|err| {
+    log::error!("save_cfj failed: {}: {}", path, err);
+    if let Some(displaced) = &displaced {
+      recycle::restore(displaced);
+    }
+    err.to_string()
+  })?;
+  log::info!("save_cfj: {}", path);
+  recycle::report(&app, displaced);
+  watcher::reset_baseline(&app, Path::new(&path));
+  recent::push(&app, &path);
+  scope::grant_project(&app, Path::new(&path));
+  autosave::clear(&app, &project.id);
+  Ok(content_hash(&project))
+}
+
+#[tauri::command]
+pub fn is_writable(path: String) -> bool {
+  atomic::check_writable(Path::new(&path)).is_ok()
+}
+
+#[tauri::command]
+pub fn project_hash(project: CfjProject) -> String {
+  content_hash(&project)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALID: &str = include_str!("../tests/fixtures/valid.cfj");
+  const TRUNCATED: &str = include_str!("../tests/fixtures/truncated.cfj");
+  const FUTURE_VERSION: &str = include_str!("../tests/fixtures/future-version.cfj");
+
+  #[test]
+  fn parses_valid_file() {
+    let loaded = parse(VALID).unwrap();
+    assert!(!loaded.migrated);
+    assert_eq!(loaded.from_schema, SCHEMA_VERSION);
+    assert_eq!(loaded.project.title, "Valid");
+    assert_eq!(loaded.project.layers.len(), 1);
+    // 未知字段原样保留
+    assert!(loaded.project.extra.contains_key("github"));
+    assert_eq!(loaded.hash, content_hash(&loaded.project));
+  }
+
+  #[test]
+  fn rejects_truncated_file() {
+    assert!(matches!(parse(TRUNCATED), Err(CfjError::Schema(_))));
+  }
+
+  #[test]
+  fn rejects_newer_version() {
+    assert!(matches!(parse(FUTURE_VERSION), Err(CfjError::UnknownVersion(_))));
+    let unknown_schema = VALID.replace("\"schemaVersion\": 3", "\"schemaVersion\": 99");
+    assert!(matches!(parse(&unknown_schema), Err(CfjError::UnknownVersion(_))));
+  }
+
+  #[test]
+  fn load_reports_read_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    assert!(matches!(load(&path), Err(CfjError::NotFound(_))));
+    std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+    assert!(matches!(load(&path), Err(CfjError::InvalidUtf8(_))));
+    std::fs::write(&path, TRUNCATED).unwrap();
+    assert!(matches!(load(&path), Err(CfjError::Schema(_))));
+    std::fs::write(&path, FUTURE_VERSION).unwrap();
+    assert!(matches!(load(&path), Err(CfjError::UnknownVersion(_))));
+  }
+
+  #[test]
+  fn save_then_load_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    let project = parse(VALID).unwrap().project;
+    save(&path, &project, false).unwrap();
+    let loaded = load_report(&path).unwrap();
+    assert_eq!(loaded.hash, content_hash(&project));
+    assert_eq!(loaded.project.layers, project.layers);
+  }
+
+  #[test]
+  fn upgrades_schema_v1() {
+    let loaded = parse(include_str!("../tests/fixtures/migrate/v1.cfj")).unwrap();
+    assert!(loaded.migrated);
+    assert_eq!(loaded.from_schema, 1);
+    let project = loaded.project;
+    assert_eq!(project.canvas_config.width, 900.0);
+    assert_eq!(project.canvas_config.ratio, "900:383");
+    assert_eq!(project.background.background_type, BackgroundType::Gradient);
+    assert_eq!(project.background.overlay_type, "none");
+    // 缺失的 zIndex 按图层顺序补齐
+    assert_eq!(project.layers[1].z_index, 2.0);
+  }
+
+  #[test]
+  fn upgrades_schema_v2() {
+    let loaded = parse(include_str!("../tests/fixtures/migrate/v2.cfj")).unwrap();
+    assert!(loaded.migrated);
+    assert_eq!(loaded.from_schema, 2);
+    let project = loaded.project;
+    assert_eq!(project.background.overlay_type, "dots");
+    assert_eq!(project.background.overlay_scale, 12.0);
+    assert!(project.layers[0].text_gradient.as_ref().unwrap().enabled);
+    assert!(!project.layers[0].extra.contains_key("gradient"));
+    assert!(serde_json::to_value(&project.background).unwrap().get("overlay").is_none());
+  }
+
+  #[test]
+  fn current_schema_is_not_migrated() {
+    let loaded = parse(include_str!("../tests/fixtures/migrate/v3.cfj")).unwrap();
+    assert!(!loaded.migrated);
+    assert_eq!(loaded.from_schema, SCHEMA_VERSION);
+    assert!(matches!(parse("[1]"), Err(CfjError::Schema(_))));
+  }
+
+  #[test]
+  fn hash_ignores_key_order_and_whitespace() {
+    let compact = r##"{"id":"p1","title":"T","layers":[],"background":{"type":"color","value":"#fff"},"canvasConfig":{"width":100,"height":50,"ratio":"2:1"},"custom":{"b":1,"a":[1,2]},"updatedAt":1}"##;
+    let reordered = r##"{
+      "canvasConfig": { "ratio": "2:1", "height": 50, "width": 100 },
+      "custom": { "a": [1, 2], "b": 1 },
+      "background": { "value": "#fff", "type": "color" },
+      "layers": [], "title": "T", "id": "p1", "updatedAt": 99, "schemaVersion": 3
+    }"##;
+    let first = parse(compact).unwrap();
+    assert_eq!(first.hash, parse(reordered).unwrap().hash);
+    assert_eq!(first.hash.len(), 64);
+
+    let mut edited = first.project.clone();
+    edited.title = "U".to_string();
+    assert_ne!(content_hash(&edited), first.hash);
+    // 数组顺序属于内容
+    let mut reordered_array = first.project.clone();
+    reordered_array.extra.insert("custom".to_string(), serde_json::json!({ "a": [2, 1], "b": 1 }));
+    assert_ne!(content_hash(&reordered_array), first.hash);
+  }
+
+  #[test]
+  fn compressed_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfjz");
+    let original = parse(VALID).unwrap().project;
+    save(&path, &original, is_compressed_path(&path)).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap()[..2], GZIP_MAGIC);
+
+    let mut loaded = load(&path).unwrap();
+    assert_eq!(loaded.schema_version, Some(SCHEMA_VERSION));
+    loaded.schema_version = original.schema_version;
+    assert_eq!(loaded, original);
+    // 扩展名写错也按文件头解压
+    let renamed = dir.path().join("renamed.cfj");
+    std::fs::copy(&path, &renamed).unwrap();
+    assert_eq!(content_hash(&load(&renamed).unwrap()), content_hash(&original));
+  }
+
+  #[test]
+  fn decompression_is_capped() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&vec![b' '; 1 << 20]).unwrap();
+    let bomb = encoder.finish().unwrap();
+    assert!(matches!(decompress(&bomb, 1000), Err(CfjError::TooLarge(1000))));
+    assert_eq!(decompress(&bomb, 1 << 20).unwrap().len(), 1 << 20);
+  }
+
+  fn denied(path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("directory not writable: {}", path.display())))
+  }
+
+  fn allowed(_: &Path) -> std::io::Result<()> {
+    Ok(())
+  }
+
+  #[test]
+  fn read_only_follows_writability_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    let project = parse(VALID).unwrap().project;
+    save(&path, &project, false).unwrap();
+    // 预览等普通加载不探测
+    assert!(!load_report(&path).unwrap().read_only);
+    for reopen in [false, true] {
+      assert!(open_for_edit(&path, reopen, denied).unwrap().read_only);
+      assert!(!open_for_edit(&path, reopen, allowed).unwrap().read_only);
+    }
+
+    let mut edited = project.clone();
+    edited.title = "Edited".to_string();
+    let saved = save_with(&path, &edited, false, denied);
+    assert!(matches!(saved, Err(CfjError::ReadOnly(_))), "{:?}", saved);
+    assert_eq!(load(&path).unwrap(), project);
+  }
+
+  // root 不受目录权限限制，需以普通用户运行：cargo test -- --ignored
+  #[cfg(unix)]
+  #[test]
+  #[ignore = "directory permissions do not apply to root"]
+  fn read_only_directory_refuses_save() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    let project = parse(VALID).unwrap().project;
+    save(&path, &project, false).unwrap();
+
+    std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+    let opened = open_for_edit(&path, false, atomic::check_writable);
+    let saved = save(&path, &project, false);
+    std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(opened.unwrap().read_only);
+    assert!(matches!(saved, Err(CfjError::ReadOnly(_))), "{:?}", saved);
+    assert_eq!(load(&path).unwrap(), project);
+  }
+
+  #[test]
+  fn reload_picks_up_edits_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    let project = parse(VALID).unwrap().project;
+    save(&path, &project, false).unwrap();
+    let opened = load_report(&path).unwrap();
+    // 监视器在打开时记下的内容哈希
+    let known = std::sync::Mutex::new(None);
+    watcher::set_baseline(&known, &path);
+
+    // 其他程序改写了文件，监视器据此提示一次
+    let mut edited = project.clone();
+    edited.title = "Edited elsewhere".to_string();
+    std::fs::write(&path, serde_json::to_string(&edited).unwrap()).unwrap();
+    assert!(watcher::take_change(&known, &path));
+    // 提示之后又改了一次，防抖尚未处理就重新加载
+    edited.title = "Edited again".to_string();
+    std::fs::write(&path, serde_json::to_string(&edited).unwrap()).unwrap();
+
+    let reloaded = reload(&path).unwrap();
+    // reload_cfj 随后经 reset_baseline 以磁盘内容为基准，之后不会再提示文件已被修改
+    watcher::set_baseline(&known, &path);
+    assert!(!watcher::take_change(&known, &path));
+    assert_eq!(reloaded.project.title, "Edited again");
+    assert!(!reloaded.migrated);
+    // 前端以新哈希为未保存状态的基准
+    assert_ne!(reloaded.hash, opened.hash);
+    assert_eq!(reloaded.hash, content_hash(&reloaded.project));
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(reload(&path), Err(CfjError::SourceMissing(_))));
+  }
+}
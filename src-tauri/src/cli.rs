@@ -0,0 +1,239 @@
+// 模块：命令行无界面批量导出（coverflow --export a.cfj b.cfj --out dir [--format png|jpeg|webp|avif] [--scale 2]）
+use crate::cfj::{self, CfjError};
+use crate::export::{self, ExportError, ExportFormat};
+use std::path::PathBuf;
+
+// 退出码
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_INVALID_PROJECT: i32 = 4;
+pub const EXIT_WRITE: i32 = 5;
+pub const EXIT_RENDER: i32 = 6;
+
+const USAGE: &str = "usage: coverflow --export <file.cfj>... --out <dir> [--format png|jpeg|webp|avif] [--scale <n>]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportArgs {
+  pub inputs: Vec<PathBuf>,
+  pub out_dir: PathBuf,
+  pub format: ExportFormat,
+  pub scale: f32,
+}
+
+// 没有 --export 时返回 None，按正常界面启动
+pub fn parse_args(args: &[String]) -> Option<Result<ExportArgs, String>> {
+  let args: Vec<&str> = args.iter().skip(1).map(String::as_str).collect();
+  if !args.contains(&"--export") {
+    return None;
+  }
+  Some(parse_export(&args))
+}
+
+fn parse_export(args: &[&str]) -> Result<ExportArgs, String> {
+  let mut inputs = Vec::new();
+  let mut out_dir = None;
+  let mut format = ExportFormat::Png;
+  let mut scale = 1.0;
+  let mut iter = args.iter();
+  while let Some(&arg) = iter.next() {
+    match arg {
+      "--export" => {}
+      "--out" => out_dir = Some(PathBuf::from(*iter.next().ok_or("--out needs a directory")?)),
+      "--format" => {
+        format = match iter.next().map(|value| value.to_ascii_lowercase()).as_deref() {
+          Some("png") => ExportFormat::Png,
+          Some("jpeg") | Some("jpg") => ExportFormat::Jpeg,
+          Some("webp") => ExportFormat::Webp,
+          Some("avif") => ExportFormat::Avif,
+          Some(other) => return Err(format!("unsupported format: {}", other)),
+          None => return Err("--format needs a value".to_string()),
+        }
+      }
+      "--scale" => {
+        let value = iter.next().ok_or("--scale needs a value")?;
+        scale = value
+          .parse::<f32>()
+          .ok()
+          .filter(|scale| scale.is_finite() && *scale > 0.0)
+          .ok_or_else(|| format!("invalid scale: {}", value))?;
+      }
+      flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+      input => inputs.push(PathBuf::from(input)),
+    }
+  }
+  if inputs.is_empty() {
+    return Err("no project files given".to_string());
+  }
+  let out_dir = out_dir.ok_or("--out is required")?;
+  Ok(ExportArgs { inputs, out_dir, format, scale })
+}
+
+fn exit_code_for_load(err: &CfjError) -> i32 {
+  match err {
+    CfjError::NotFound(_) => EXIT_NOT_FOUND,
+    _ => EXIT_INVALID_PROJECT,
+  }
+}
+
+fn exit_code_for_export(err: &ExportError) -> i32 {
+  match err {
+    // 命令行导出不会被取消
+    ExportError::Write(_) | ExportError::Cancelled => EXIT_WRITE,
+    ExportError::Render(_) | ExportError::Encode(_) | ExportError::MissingFont(_) | ExportError::InvalidQuality(_) => {
+      EXIT_RENDER
+    }
+  }
+}
+
+// 与 export_all 相同的文件名处理，不同目录下的同名输入不会互相覆盖
+fn output_names(inputs: &[PathBuf], format: ExportFormat) -> Vec<String> {
+  let stems = inputs
+    .iter()
+    .enumerate()
+    .map(|(index, input)| {
+      let stem = input.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+      export::file_stem(&stem, index)
+    })
+    .collect();
+  export::unique_names(stems, format)
+}
+
+// 逐个导出，全部完成后返回遇到的第一个错误对应的退出码
+pub fn run(args: &ExportArgs) -> i32 {
+  if let Err(err) = std::fs::create_dir_all(&args.out_dir) {
+    eprintln!("error: {}: {}", args.out_dir.display(), err);
+    return EXIT_WRITE;
+  }
+  let mut code = EXIT_OK;
+  let names = output_names(&args.inputs, args.format);
+  for (input, name) in args.inputs.iter().zip(&names) {
+    let result = cfj::load(input)
+      .map_err(|err| (exit_code_for_load(&err), err.to_string()))
+      .and_then(|project| {
+        let out = args.out_dir.join(name);
+        export::export_to(&project, args.format, args.scale, &out)
+          .map_err(|err| (exit_code_for_export(&err), err.to_string()))
+      });
+    match result {
+      Ok(image) => {
+        println!("{} -> {} ({}x{})", input.display(), image.path, image.width, image.height);
+        for warning in image.warnings {
+          println!("  warning: {}", warning);
+        }
+      }
+      Err((err_code, message)) => {
+        eprintln!("error: {}: {}", input.display(), message);
+        if code == EXIT_OK {
+          code = err_code;
+        }
+      }
+    }
+  }
+  code
+}
+
+// 发行版是 GUI 子系统程序，需挂到父进程的控制台才能输出
+#[cfg(windows)]
+fn attach_console() {
+  use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+  unsafe {
+    AttachConsole(ATTACH_PARENT_PROCESS);
+  }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+pub fn main(parsed: Result<ExportArgs, String>) -> i32 {
+  attach_console();
+  match parsed {
+    Ok(args) => run(&args),
+    Err(message) => {
+      eprintln!("error: {}\n{}", message, USAGE);
+      EXIT_USAGE
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALID: &str = include_str!("../tests/fixtures/valid.cfj");
+
+  fn args(list: &[&str]) -> Vec<String> {
+    std::iter::once("coverflow").chain(list.iter().copied()).map(String::from).collect()
+  }
+
+  #[test]
+  fn parses_export_arguments() {
+    assert_eq!(parse_args(&args(&["cover.cfj"])), None);
+    let parsed = parse_args(&args(&["--export", "a.cfj", "b.cfj", "--out", "dist", "--format", "JPG", "--scale", "2"]))
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      parsed,
+      ExportArgs {
+        inputs: vec![PathBuf::from("a.cfj"), PathBuf::from("b.cfj")],
+        out_dir: PathBuf::from("dist"),
+        format: ExportFormat::Jpeg,
+        scale: 2.0,
+      }
+    );
+  }
+
+  #[test]
+  fn rejects_bad_arguments() {
+    for bad in [
+      &["--export", "a.cfj"][..],
+      &["--export", "--out", "dist"],
+      &["--export", "a.cfj", "--out"],
+      &["--export", "a.cfj", "--out", "dist", "--format", "bmp"],
+      &["--export", "a.cfj", "--out", "dist", "--scale", "0"],
+      &["--export", "a.cfj", "--out", "dist", "--scale", "NaN"],
+      &["--export", "a.cfj", "--out", "dist", "--verbose"],
+    ] {
+      assert!(parse_args(&args(bad)).unwrap().is_err(), "{:?}", bad);
+    }
+    assert_eq!(main(Err("bad".to_string())), EXIT_USAGE);
+  }
+
+  #[test]
+  fn same_stems_get_unique_names() {
+    let inputs = [PathBuf::from("a/cover.cfj"), PathBuf::from("b/cover.cfj"), PathBuf::from("b/COVER.cfjz")];
+    assert_eq!(output_names(&inputs, ExportFormat::Png), ["cover.png", "cover_2.png", "COVER_3.png"]);
+  }
+
+  #[test]
+  fn exit_codes() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("a").join("cover.cfj");
+    let second = dir.path().join("b").join("cover.cfj");
+    for path in [&first, &second] {
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, VALID).unwrap();
+    }
+    let out_dir = dir.path().join("out");
+    let export = |inputs: Vec<PathBuf>, out_dir: &std::path::Path| {
+      run(&ExportArgs {
+        inputs,
+        out_dir: out_dir.to_path_buf(),
+        format: ExportFormat::Png,
+        scale: 0.25,
+      })
+    };
+
+    assert_eq!(export(vec![first.clone(), second.clone()], &out_dir), EXIT_OK);
+    assert!(out_dir.join("cover.png").is_file());
+    assert!(out_dir.join("cover_2.png").is_file());
+
+    let broken = dir.path().join("broken.cfj");
+    std::fs::write(&broken, "{").unwrap();
+    // 返回第一个错误对应的退出码，其余文件照常导出
+    assert_eq!(export(vec![dir.path().join("missing.cfj"), broken.clone()], &out_dir), EXIT_NOT_FOUND);
+    assert_eq!(export(vec![broken, first.clone()], &out_dir), EXIT_INVALID_PROJECT);
+    // 输出目录是一个文件
+    assert_eq!(export(vec![first.clone()], &first), EXIT_WRITE);
+  }
+}
@@ -0,0 +1,333 @@
+// 模块：把所有封面缩成缩略图排成网格，导出为一张 PNG（联系表，用于预览与分享整套封面）
+use crate::export::{self, ExportError, ExportFormat};
+use crate::{atomic, fonts, icc, limits, render};
+use crate::cfj::CfjProject;
+use resvg::tiny_skia::{Pixmap, PixmapPaint, Transform};
+use resvg::usvg;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+const MIN_THUMB_PX: u32 = 16;
+const MAX_THUMB_PX: u32 = 2048;
+const MAX_COLS: u32 = 100;
+const MAX_PADDING: u32 = 512;
+// 与单张导出的上限一致
+const MAX_SIDE: u64 = 16384;
+const MAX_PIXELS: u64 = 64_000_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContactSheetOptions {
+  // 缩略图之间及四周的留白（像素）
+  pub padding: u32,
+  // 任意 SVG 颜色，如 #ffffff、transparent
+  pub background: String,
+  // 在缩略图下方写上封面标题
+  pub labels: bool,
+  pub label_color: String,
+}
+
+impl Default for ContactSheetOptions {
+  fn default() -> Self {
+    ContactSheetOptions {
+      padding: 16,
+      background: "#ffffff".to_string(),
+      labels: false,
+      label_color: "#1f2937".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSheet {
+  pub path: String,
+  pub width: u32,
+  pub height: u32,
+  // 各封面缩略图的渲染警告（缺字体、无法还原的效果、读不到的图片等）与渲染失败而留空的格子
+  pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SheetLayout {
+  pub cols: u32,
+  pub rows: u32,
+  // 每格为 thumb_px 见方，开启标题时下方再加 label_px
+  pub thumb_px: u32,
+  pub label_px: u32,
+  pub padding: u32,
+  pub width: u32,
+  pub height: u32,
+}
+
+impl SheetLayout {
+  // 第 index 张缩略图所在格子的左上角
+  pub fn cell_origin(&self, index: usize) -> (u32, u32) {
+    let col = index as u32 % self.cols;
+    let row = index as u32 / self.cols;
+    (
+      self.padding + col * (self.thumb_px + self.padding),
+      self.padding + row * (self.thumb_px + self.label_px + self.padding),
+    )
+  }
+}
+
+fn label_px(thumb_px: u32, labels: bool) -> u32 {
+  if labels {
+    (thumb_px / 8).clamp(12, 48)
+  } else {
+    0
+  }
+}
+
+fn sheet_size(cols: u32, rows: u32, thumb_px: u32, label_px: u32, padding: u32) -> (u64, u64) {
+  let (cols, rows, thumb, label, padding) =
+    (cols as u64, rows as u64, thumb_px as u64, label_px as u64, padding as u64);
+  (
+    cols * thumb + (cols + 1) * padding,
+    rows * (thumb + label) + (rows + 1) * padding,
+  )
+}
+
+// 列数不超过封面数，最后一行可以不满；画布超出上限时逐步缩小缩略图
+pub fn layout(count: usize, cols: u32, thumb_px: u32, padding: u32, labels: bool) -> Result<SheetLayout, ExportError> {
+  if count == 0 {
+    return Err(ExportError::Render("no covers to lay out".to_string()));
+  }
+  let cols = cols.clamp(1, MAX_COLS).min(count as u32);
+  let rows = (count as u32).div_ceil(cols);
+  let padding = padding.min(MAX_PADDING);
+  let mut thumb_px = thumb_px.clamp(MIN_THUMB_PX, MAX_THUMB_PX);
+  loop {
+    let label = label_px(thumb_px, labels);
+    let (width, height) = sheet_size(cols, rows, thumb_px, label, padding);
+    if width <= MAX_SIDE && height <= MAX_SIDE && width * height <= MAX_PIXELS {
+      return Ok(SheetLayout {
+        cols,
+        rows,
+        thumb_px,
+        label_px: label,
+        padding,
+        width: width as u32,
+        height: height as u32,
+      });
+    }
+    if thumb_px == MIN_THUMB_PX {
+      return Err(ExportError::Render(format!(
+        "contact sheet too large: {} covers in {} columns exceed {}x{} even at {} px",
+        count, cols, MAX_SIDE, MAX_SIDE, MIN_THUMB_PX
+      )));
+    }
+    thumb_px = (thumb_px * 9 / 10).max(MIN_THUMB_PX);
+  }
+}
+
+// 背景与标题交给 usvg 绘制，颜色写法与文字排版和封面渲染一致
+fn backdrop(projects: &[CfjProject], layout: &SheetLayout, options: &ContactSheetOptions) -> String {
+  let mut markup = format!(
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="{fill}"/>"#,
+    w = layout.width,
+    h = layout.height,
+    fill = render::escape(&options.background),
+  );
+  if layout.label_px > 0 {
+    let font_size = layout.label_px as f32 * 0.7;
+    markup.push_str("<defs>");
+    for index in 0..projects.len() {
+      let (x, y) = layout.cell_origin(index);
+      markup.push_str(&format!(
+        r#"<clipPath id="label{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+        index,
+        x,
+        y + layout.thumb_px,
+        layout.thumb_px,
+        layout.label_px
+      ));
+    }
+    markup.push_str("</defs>");
+    for (index, project) in projects.iter().enumerate() {
+      let (x, y) = layout.cell_origin(index);
+      // 标题过长时裁掉超出格子的部分
+      markup.push_str(&format!(
+        r#"<text clip-path="url(#label{})" x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+        index,
+        x as f32 + layout.thumb_px as f32 / 2.0,
+        (y + layout.thumb_px) as f32 + layout.label_px as f32 / 2.0,
+        font_size,
+        render::escape(&options.label_color),
+        render::escape(&project.title)
+      ));
+    }
+  }
+  markup.push_str("</svg>");
+  markup
+}
+
+// 单张封面渲染失败时留空该格并记入警告，其余照常合成
+pub fn compose(
+  projects: &[CfjProject],
+  cols: u32,
+  thumb_px: u32,
+  options: &ContactSheetOptions,
+) -> Result<(Pixmap, Vec<String>), ExportError> {
+  let layout = layout(projects.len(), cols, thumb_px, options.padding, options.labels)?;
+  let tree_options = usvg::Options {
+    fontdb: fonts::database(),
+    ..usvg::Options::default()
+  };
+  let tree = usvg::Tree::from_str(&backdrop(projects, &layout, options), &tree_options)
+    .map_err(|err| ExportError::Render(format!("contact sheet background: {}", err)))?;
+  let mut sheet = Pixmap::new(layout.width, layout.height)
+    .ok_or_else(|| ExportError::Render(format!("cannot allocate {}x{}", layout.width, layout.height)))?;
+  resvg::render(&tree, Transform::identity(), &mut sheet.as_mut());
+
+  let mut warnings = Vec::new();
+  for (index, project) in projects.iter().enumerate() {
+    let thumb = match export::rasterize_fit(project, layout.thumb_px) {
+      Ok((thumb, thumb_warnings)) => {
+        warnings.extend(
+          thumb_warnings
+            .into_iter()
+            .map(|warning| format!("cover {} \"{}\": {}", index, project.title, warning)),
+        );
+        thumb
+      }
+      Err(err) => {
+        warnings.push(format!("cover {} \"{}\": {}", index, project.title, err));
+        continue;
+      }
+    };
+    // 按比例缩放后较短的一边在格子内居中
+    let (x, y) = layout.cell_origin(index);
+    let dx = x + (layout.thumb_px - thumb.width().min(layout.thumb_px)) / 2;
+    let dy = y + (layout.thumb_px - thumb.height().min(layout.thumb_px)) / 2;
+    sheet.draw_pixmap(
+      dx as i32,
+      dy as i32,
+      thumb.as_ref(),
+      &PixmapPaint::default(),
+      Transform::identity(),
+      None,
+    );
+  }
+  Ok((sheet, warnings))
+}
+
+pub fn export_to(
+  projects: &[CfjProject],
+  cols: u32,
+  thumb_px: u32,
+  options: &ContactSheetOptions,
+  out_path: &Path,
+) -> Result<ExportedSheet, ExportError> {
+  let (sheet, warnings) = compose(projects, cols, thumb_px, options)?;
+  let mut bytes = export::encode(&sheet, ExportFormat::Png)?;
+  if let Some(tagged) = icc::embed_srgb(&bytes, ExportFormat::Png, sheet.width(), sheet.height()) {
+    bytes = tagged;
+  }
+  atomic::write(out_path, &bytes).map_err(|err| ExportError::Write(err.to_string()))?;
+  for warning in &warnings {
+    log::warn!("contact sheet: {}", warning);
+  }
+  log::info!(
+    "exported contact sheet {}x{} ({} covers): {}",
+    sheet.width(),
+    sheet.height(),
+    projects.len(),
+    out_path.display()
+  );
+  Ok(ExportedSheet {
+    path: out_path.to_string_lossy().into_owned(),
+    width: sheet.width(),
+    height: sheet.height(),
+    warnings,
+  })
+}
+
+// 与 export_pdf 一致接收整套封面列表
+#[tauri::command]
+pub async fn export_contact_sheet(
+  app: AppHandle,
+  projects: Vec<CfjProject>,
+  cols: u32,
+  thumb_px: u32,
+  out_path: String,
+  options: Option<ContactSheetOptions>,
+) -> Result<ExportedSheet, String> {
+  limits::check(&app, "export_contact_sheet", &projects)?;
+  let options = options.unwrap_or_default();
+  tauri::async_runtime::spawn_blocking(move || {
+    export_to(&projects, cols, thumb_px, &options, Path::new(&out_path))
+  })
+  .await
+  .map_err(|err| err.to_string())?
+  .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+
+  #[test]
+  fn lays_out_rows_and_cells() {
+    let grid = layout(5, 3, 100, 10, true).unwrap();
+    assert_eq!((grid.cols, grid.rows, grid.label_px), (3, 2, 12));
+    assert_eq!((grid.width, grid.height), (340, 254));
+    assert_eq!(grid.cell_origin(0), (10, 10));
+    assert_eq!(grid.cell_origin(2), (230, 10));
+    // 第二行从第一行缩略图与标题下方开始
+    assert_eq!(grid.cell_origin(4), (120, 132));
+
+    // 列数不超过封面数，不开标题时没有标题行
+    let single = layout(2, 5, 100, 10, false).unwrap();
+    assert_eq!((single.cols, single.rows, single.label_px), (2, 1, 0));
+    assert_eq!((single.width, single.height), (230, 120));
+    assert!(layout(0, 3, 100, 10, false).is_err());
+  }
+
+  #[test]
+  fn oversized_sheets_shrink_thumbnails() {
+    let grid = layout(400, 20, 2048, 0, false).unwrap();
+    assert!(grid.thumb_px < 2048);
+    assert!(grid.width as u64 <= MAX_SIDE && grid.height as u64 <= MAX_SIDE);
+    assert!(grid.width as u64 * grid.height as u64 <= MAX_PIXELS);
+  }
+
+  #[test]
+  fn compose_matches_layout() {
+    let project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    let options = ContactSheetOptions {
+      padding: 10,
+      labels: true,
+      ..ContactSheetOptions::default()
+    };
+    let (sheet, warnings) = compose(&vec![project; 5], 3, 100, &options).unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!((sheet.width(), sheet.height()), (340, 254));
+    // 留白为背景色
+    let corner = sheet.pixel(0, 0).unwrap();
+    assert_eq!((corner.red(), corner.green(), corner.blue(), corner.alpha()), (255, 255, 255, 255));
+  }
+
+  #[test]
+  fn export_reports_per_cover_warnings() {
+    let plain = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    let mut blended = plain.clone();
+    let mut layer = blended.layers[0].clone();
+    layer.id = "fx".to_string();
+    layer.layer_type = cfj::LayerType::Decoration;
+    layer.content = "background: #ff0000; mix-blend-mode: multiply".to_string();
+    blended.layers.push(layer);
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("sheet.png");
+    let sheet = export_to(&[plain, blended], 2, 64, &ContactSheetOptions::default(), &out).unwrap();
+    assert!(out.is_file());
+    assert_eq!(sheet.path, out.to_string_lossy());
+    assert!(!sheet.warnings.is_empty());
+    // 警告注明来自哪一张封面
+    assert!(sheet.warnings.iter().all(|warning| warning.starts_with("cover 1 ")), "{:?}", sheet.warnings);
+  }
+}
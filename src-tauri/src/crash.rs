@@ -0,0 +1,269 @@
+// 模块：崩溃报告（panic 时写入日志目录；主线程崩溃时提示用户并可重启）
+use crate::{atomic, autosave};
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+// 重启后的进程通过该变量拿到上一次的报告路径
+const REPORT_ENV: &str = "COVERFLOW_CRASH_REPORT";
+// 新进程先等旧进程退出，否则单实例插件会把启动转交给正在退出的旧进程
+const RESTART_DELAY: Duration = Duration::from_millis(1500);
+
+struct CrashContext {
+  log_dir: Option<PathBuf>,
+  recovery_dir: Option<PathBuf>,
+}
+
+static CONTEXT: OnceLock<CrashContext> = OnceLock::new();
+// 同一毫秒内多次 panic 时区分报告文件名
+static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+  // 钩子内的代码不调用会 panic 的接口；万一报告过程中再次 panic，只走默认输出，避免递归
+  static REPORTING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn now_ms() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or(0)
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+  let payload = info.payload();
+  let message = payload
+    .downcast_ref::<&str>()
+    .map(|text| text.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+    .unwrap_or_else(|| "unknown panic payload".to_string());
+  match info.location() {
+    Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+    None => message,
+  }
+}
+
+// 列出未清理的自动保存快照，下次启动时会提示恢复
+fn recovery_snapshots(dir: Option<&Path>) -> Vec<PathBuf> {
+  let Some(dir) = dir else {
+    return Vec::new();
+  };
+  let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+    .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+    .unwrap_or_default();
+  files.retain(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"));
+  files.sort();
+  files
+}
+
+pub fn report_text(message: &str, thread: &str, backtrace: &str, snapshots: &[PathBuf]) -> String {
+  let mut text = String::new();
+  let _ = writeln!(text, "CoverFlow crash report");
+  let _ = writeln!(text, "version: {}", env!("CARGO_PKG_VERSION"));
+  let _ = writeln!(text, "os: {} {} ({})", std::env::consts::OS, std::env::consts::ARCH, std::env::consts::FAMILY);
+  let _ = writeln!(text, "time: {}", now_ms());
+  let _ = writeln!(text, "thread: {}", thread);
+  let _ = writeln!(text, "panic: {}", message);
+  let _ = writeln!(text);
+  if snapshots.is_empty() {
+    let _ = writeln!(text, "recovery snapshots: none");
+  } else {
+    let _ = writeln!(text, "recovery snapshots (offered on next launch):");
+    for snapshot in snapshots {
+      let _ = writeln!(text, "  {}", snapshot.display());
+    }
+  }
+  let _ = writeln!(text);
+  let _ = writeln!(text, "backtrace:");
+  let _ = writeln!(text, "{}", backtrace);
+  text
+}
+
+fn write_report(context: &CrashContext, info: &PanicHookInfo<'_>) -> Option<PathBuf> {
+  let thread = std::thread::current();
+  let text = report_text(
+    &panic_message(info),
+    thread.name().unwrap_or("unnamed"),
+    &Backtrace::force_capture().to_string(),
+    &recovery_snapshots(context.recovery_dir.as_deref()),
+  );
+  let dir = context.log_dir.as_ref()?;
+  std::fs::create_dir_all(dir).ok()?;
+  let path = dir.join(format!("crash-{}-{}.txt", now_ms(), SEQUENCE.fetch_add(1, Ordering::SeqCst)));
+  atomic::write(&path, text.as_bytes()).ok()?;
+  Some(path)
+}
+
+fn relaunch(report: Option<&Path>) -> bool {
+  let Ok(exe) = std::env::current_exe() else {
+    return false;
+  };
+  let mut command = std::process::Command::new(exe);
+  if let Some(report) = report {
+    command.env(REPORT_ENV, report);
+  }
+  command.spawn().is_ok()
+}
+
+fn is_main_thread() -> bool {
+  std::thread::current().name() == Some("main")
+}
+
+// 主线程 panic 后事件循环随之退出，插件的异步对话框无法再显示，这里直接用同步的原生对话框
+fn ask_restart(detail: &str) -> bool {
+  let result = rfd::MessageDialog::new()
+    .set_level(rfd::MessageLevel::Error)
+    .set_title("CoverFlow")
+    .set_description(detail)
+    .set_buttons(rfd::MessageButtons::OkCancelCustom("Restart".to_string(), "Quit".to_string()))
+    .show();
+  // Linux 上自定义按钮返回 Ok/Cancel
+  match result {
+    rfd::MessageDialogResult::Custom(label) => label == "Restart",
+    rfd::MessageDialogResult::Ok | rfd::MessageDialogResult::Yes => true,
+    _ => false,
+  }
+}
+
+// 每次 panic 都写报告；其他线程的 panic 只结束该线程（或任务），应用继续运行，不打扰用户
+fn on_panic(context: &CrashContext, info: &PanicHookInfo<'_>) {
+  let report = write_report(context, info);
+  log::error!(
+    "panic: {} (report: {})",
+    panic_message(info),
+    report.as_ref().map(|path| path.display().to_string()).unwrap_or_else(|| "not written".to_string())
+  );
+  if !is_main_thread() {
+    return;
+  }
+  let detail = report
+    .as_ref()
+    .map(|path| format!("CoverFlow ran into an error and has to close.\n\nA crash report was saved to:\n{}", path.display()))
+    .unwrap_or_else(|| "CoverFlow ran into an error and has to close.".to_string());
+  if ask_restart(&detail) && !relaunch(report.as_deref()) {
+    log::error!("relaunch after crash failed");
+  }
+}
+
+fn handle(context: &CrashContext, info: &PanicHookInfo<'_>) {
+  if REPORTING.with(|reporting| reporting.replace(true)) {
+    return;
+  }
+  on_panic(context, info);
+  REPORTING.with(|reporting| reporting.set(false));
+}
+
+pub fn install(app: &AppHandle) {
+  let paths = app.path();
+  let context = CrashContext {
+    log_dir: paths.app_log_dir().ok(),
+    recovery_dir: autosave::recovery_dir(app).ok(),
+  };
+  if CONTEXT.set(context).is_err() {
+    return;
+  }
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    default_hook(info);
+    if let Some(context) = CONTEXT.get() {
+      handle(context, info);
+    }
+  }));
+}
+
+// 在初始化单实例插件之前调用
+pub fn wait_if_restarted() {
+  if std::env::var_os(REPORT_ENV).is_some() {
+    std::thread::sleep(RESTART_DELAY);
+  }
+}
+
+// 由崩溃后重启的进程调用，告知用户报告位置
+pub fn announce_previous(app: &AppHandle) {
+  let Some(report) = std::env::var_os(REPORT_ENV) else {
+    return;
+  };
+  let report = PathBuf::from(report);
+  log::info!("restarted after crash, report: {}", report.display());
+  app
+    .dialog()
+    .message(format!(
+      "CoverFlow restarted after a crash.\n\nThe crash report was saved to:\n{}",
+      report.display()
+    ))
+    .kind(MessageDialogKind::Warning)
+    .title("CoverFlow")
+    .show(|_| {});
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn reports(dir: &Path) -> Vec<String> {
+    let mut texts: Vec<String> = std::fs::read_dir(dir)
+      .unwrap()
+      .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+      .collect();
+    texts.sort();
+    texts
+  }
+
+  #[test]
+  fn report_names_version_thread_and_snapshots() {
+    let snapshots = [PathBuf::from("recovery").join("p1.json")];
+    let text = report_text("boom at src/main.rs:1", "worker", "0: main", &snapshots);
+    assert!(text.contains(&format!("version: {}", env!("CARGO_PKG_VERSION"))));
+    assert!(text.contains(&format!("os: {}", std::env::consts::OS)));
+    assert!(text.contains("thread: worker"));
+    assert!(text.contains("panic: boom at src/main.rs:1"));
+    assert!(text.contains(&format!("  {}", snapshots[0].display())));
+    assert!(report_text("boom", "main", "", &[]).contains("recovery snapshots: none"));
+  }
+
+  #[test]
+  fn only_json_snapshots_are_listed() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["b.json", "a.json", "notes.txt"] {
+      std::fs::write(dir.path().join(name), "{}").unwrap();
+    }
+    assert_eq!(
+      recovery_snapshots(Some(dir.path())),
+      vec![dir.path().join("a.json"), dir.path().join("b.json")]
+    );
+    assert!(recovery_snapshots(None).is_empty());
+  }
+
+  #[test]
+  fn every_worker_panic_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let context = std::sync::Arc::new(CrashContext {
+      log_dir: Some(dir.path().to_path_buf()),
+      recovery_dir: None,
+    });
+    let previous = std::panic::take_hook();
+    let hook_context = context.clone();
+    std::panic::set_hook(Box::new(move |info| handle(&hook_context, info)));
+    for index in 0..2 {
+      let worker = std::thread::Builder::new()
+        .name(format!("worker-{}", index))
+        .spawn(move || panic!("worker {} failed", index))
+        .unwrap();
+      // 非主线程不弹对话框，也不结束进程
+      assert!(worker.join().is_err());
+    }
+    std::panic::set_hook(previous);
+
+    let texts = reports(dir.path());
+    assert_eq!(texts.len(), 2);
+    assert!(texts[0].contains("thread: worker-0") && texts[0].contains("panic: worker 0 failed"));
+    assert!(texts[1].contains("thread: worker-1") && texts[1].contains("panic: worker 1 failed"));
+  }
+}
@@ -0,0 +1,170 @@
+// 模块：coverflow:// 深度链接（coverflow://open?path=...），与文件关联共用待打开队列
+use crate::{cfj, pending, window_state};
+use std::fmt;
+use std::path::{Component, PathBuf};
+use tauri::{AppHandle, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "coverflow";
+
+#[derive(Debug, PartialEq)]
+pub enum DeepLinkError {
+  UnsupportedScheme(String),
+  UnknownAction(String),
+  MissingPath,
+  // 同一链接带了多个 path 参数
+  AmbiguousPath,
+  NotLocal(String),
+  NotProject(String),
+}
+
+impl fmt::Display for DeepLinkError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DeepLinkError::UnsupportedScheme(scheme) => write!(f, "unsupported scheme: {}", scheme),
+      DeepLinkError::UnknownAction(action) => write!(f, "unknown action: {}", action),
+      DeepLinkError::MissingPath => write!(f, "missing path parameter"),
+      DeepLinkError::AmbiguousPath => write!(f, "more than one path parameter"),
+      DeepLinkError::NotLocal(path) => write!(f, "not a local absolute path: {}", path),
+      DeepLinkError::NotProject(path) => write!(f, "not a .cfj or .cfjz file: {}", path),
+    }
+  }
+}
+
+// 拒绝网络地址、UNC 共享与设备路径（\\ 或 // 开头）、相对路径与 ..，只接受本机绝对路径
+fn local_path(raw: &str) -> Result<PathBuf, DeepLinkError> {
+  let not_local = || DeepLinkError::NotLocal(raw.to_string());
+  if raw.contains("://") || raw.starts_with("\\\\") || raw.starts_with("//") || raw.contains('\0') {
+    return Err(not_local());
+  }
+  let path = PathBuf::from(raw);
+  if !path.is_absolute() {
+    return Err(not_local());
+  }
+  if path.components().any(|component| component == Component::ParentDir) {
+    return Err(not_local());
+  }
+  if !cfj::is_project_path(&path) {
+    return Err(DeepLinkError::NotProject(raw.to_string()));
+  }
+  Ok(path)
+}
+
+// 只做格式校验，文件是否存在由 pending::enqueue 检查
+pub fn parse(url: &Url) -> Result<PathBuf, DeepLinkError> {
+  if !url.scheme().eq_ignore_ascii_case(SCHEME) {
+    return Err(DeepLinkError::UnsupportedScheme(url.scheme().to_string()));
+  }
+  // coverflow://open?... 的动作在 host 位置，coverflow:open?... 则在 path 位置
+  let action = match url.host_str().filter(|host| !host.is_empty()) {
+    Some(host) if !url.path().trim_matches('/').is_empty() => {
+      return Err(DeepLinkError::UnknownAction(format!("{}{}", host, url.path())));
+    }
+    Some(host) => host,
+    None => url.path().trim_matches('/'),
+  };
+  if !action.eq_ignore_ascii_case("open") {
+    return Err(DeepLinkError::UnknownAction(action.to_string()));
+  }
+  let mut paths = url.query_pairs().filter(|(key, _)| key == "path").map(|(_, value)| value.into_owned());
+  let path = paths.next().filter(|path| !path.trim().is_empty()).ok_or(DeepLinkError::MissingPath)?;
+  if paths.next().is_some() {
+    return Err(DeepLinkError::AmbiguousPath);
+  }
+  local_path(&path)
+}
+
+fn handle(app: &AppHandle, urls: Vec<Url>) {
+  let mut paths = Vec::new();
+  for url in urls {
+    match parse(&url) {
+      Ok(path) => paths.push(path.to_string_lossy().into_owned()),
+      // macOS 打开文件也会走这个事件，由 pending::open_urls 处理
+      Err(DeepLinkError::UnsupportedScheme(scheme)) if scheme == "file" => {}
+      Err(err) => log::warn!("ignored deep link {}: {}", url, err),
+    }
+  }
+  if paths.is_empty() {
+    return;
+  }
+  log::info!("deep link open: {:?}", paths);
+  window_state::bring_to_front(app);
+  pending::enqueue(app, paths);
+}
+
+// 在 setup 中调用：开发版没有经过安装包注册协议，运行时补注册；
+// 冷启动的链接同样进入待打开队列，前端就绪后再发出
+pub fn init(app: &AppHandle) {
+  let deep_link = app.deep_link();
+  #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+  if let Err(err) = deep_link.register_all() {
+    log::warn!("deep link scheme not registered: {}", err);
+  }
+  let handle_app = app.clone();
+  deep_link.on_open_url(move |event| handle(&handle_app, event.urls()));
+  match deep_link.get_current() {
+    Ok(Some(urls)) => handle(app, urls),
+    Ok(None) => {}
+    Err(err) => log::warn!("deep link lookup failed: {}", err),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(windows)]
+  const ROOT: &str = "C:\\Users\\me\\";
+  #[cfg(not(windows))]
+  const ROOT: &str = "/home/me/";
+
+  fn open_url(path: &str) -> Url {
+    Url::parse_with_params("coverflow://open", &[("path", path)]).unwrap()
+  }
+
+  fn parse_str(url: &str) -> Result<PathBuf, DeepLinkError> {
+    parse(&Url::parse(url).unwrap())
+  }
+
+  #[test]
+  fn accepts_open_links_with_local_project() {
+    let path = format!("{}封面 1.cfj", ROOT);
+    assert_eq!(parse(&open_url(&path)), Ok(PathBuf::from(&path)));
+    let compressed = format!("{}cover.cfjz", ROOT);
+    assert_eq!(parse(&open_url(&compressed)), Ok(PathBuf::from(&compressed)));
+    // coverflow:open?... 与大小写不同的动作名
+    let query = open_url(&path).query().unwrap().to_string();
+    assert_eq!(parse_str(&format!("coverflow:open?{}", query)), Ok(PathBuf::from(&path)));
+    assert_eq!(parse_str(&format!("COVERFLOW://OPEN/?{}", query)), Ok(PathBuf::from(&path)));
+  }
+
+  #[test]
+  fn rejects_missing_or_repeated_path() {
+    assert_eq!(parse_str("coverflow://open"), Err(DeepLinkError::MissingPath));
+    assert_eq!(parse_str("coverflow://open?path="), Err(DeepLinkError::MissingPath));
+    assert_eq!(parse_str("coverflow://open?file=/a.cfj"), Err(DeepLinkError::MissingPath));
+    assert_eq!(
+      parse_str("coverflow://open?path=/a.cfj&path=/b.cfj"),
+      Err(DeepLinkError::AmbiguousPath)
+    );
+  }
+
+  #[test]
+  fn rejects_malicious_links() {
+    assert!(matches!(parse_str("https://open?path=/a.cfj"), Err(DeepLinkError::UnsupportedScheme(_))));
+    assert!(matches!(parse_str("coverflow://delete?path=/a.cfj"), Err(DeepLinkError::UnknownAction(_))));
+    assert!(matches!(parse_str("coverflow://open/extra?path=/a.cfj"), Err(DeepLinkError::UnknownAction(_))));
+    for path in [
+      "https://evil.example/a.cfj".to_string(),
+      "\\\\server\\share\\a.cfj".to_string(),
+      "//server/share/a.cfj".to_string(),
+      "relative/a.cfj".to_string(),
+      format!("{}..{}secret.cfj", ROOT, std::path::MAIN_SEPARATOR),
+      format!("{}a\0.cfj", ROOT),
+    ] {
+      assert_eq!(parse(&open_url(&path)), Err(DeepLinkError::NotLocal(path.clone())), "{:?}", path);
+    }
+    let script = format!("{}payload.exe", ROOT);
+    assert_eq!(parse(&open_url(&script)), Err(DeepLinkError::NotProject(script.clone())));
+  }
+}
@@ -0,0 +1,75 @@
+// 模块：拖放到窗口的文件（项目文件打开，图片交给前端添加为图层）
+use crate::{cfj, pending, scope};
+use std::path::PathBuf;
+use tauri::{DragDropEvent, Emitter, Manager, Window, WindowEvent};
+
+pub const ADD_IMAGE_EVENT: &str = "cover://add-image";
+pub const REJECTED_EVENT: &str = "drop://rejected";
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DroppedFiles {
+  pub projects: Vec<String>,
+  pub images: Vec<String>,
+  pub rejected: Vec<String>,
+}
+
+fn extension(path: &std::path::Path) -> String {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .map(|ext| ext.to_ascii_lowercase())
+    .unwrap_or_default()
+}
+
+// 按扩展名分类；图片需解析为规范路径且是普通文件
+pub fn partition(paths: &[PathBuf]) -> DroppedFiles {
+  let mut dropped = DroppedFiles::default();
+  for path in paths {
+    let display = path.to_string_lossy().into_owned();
+    let ext = extension(path);
+    if cfj::EXTENSIONS.contains(&ext.as_str()) {
+      dropped.projects.push(display);
+      continue;
+    }
+    if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+      dropped.rejected.push(display);
+      continue;
+    }
+    match std::fs::canonicalize(path) {
+      Ok(canonical) if canonical.is_file() && IMAGE_EXTENSIONS.contains(&extension(&canonical).as_str()) => {
+        let canonical = canonical.to_string_lossy().into_owned();
+        if !dropped.images.contains(&canonical) {
+          dropped.images.push(canonical);
+        }
+      }
+      _ => dropped.rejected.push(display),
+    }
+  }
+  dropped
+}
+
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+  let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event else {
+    return;
+  };
+  let dropped = partition(paths);
+  log::info!(
+    "dropped {} project(s), {} image(s), {} rejected",
+    dropped.projects.len(),
+    dropped.images.len(),
+    dropped.rejected.len()
+  );
+  // 项目文件走与启动参数相同的校验与缓冲流程
+  pending::enqueue(window.app_handle(), dropped.projects);
+  if !dropped.images.is_empty() {
+    for image in &dropped.images {
+      scope::grant_file(window.app_handle(), std::path::Path::new(image));
+    }
+    let _ = window.emit(ADD_IMAGE_EVENT, &dropped.images);
+  }
+  if !dropped.rejected.is_empty() {
+    let _ = window.emit(REJECTED_EVENT, &dropped.rejected);
+  }
+}
@@ -0,0 +1,196 @@
+// 模块：另存副本（复制项目文件，并把引用的本地图片与字体复制到副本旁的资源目录）
+use crate::cfj::{self, CfjProject};
+use crate::{assets, limits, recent, recycle};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// 副本 cover.cfj 的资源目录为 cover_assets
+fn assets_dir_for(path: &Path) -> PathBuf {
+  let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+  path.with_file_name(format!("{}_assets", stem))
+}
+
+fn copy_into(source: &Path, dir: &Path) -> Result<PathBuf, String> {
+  let stem = source.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_else(|| "asset".to_string());
+  let extension = source.extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_default();
+  std::fs::read(source)
+    .and_then(|bytes| assets::write_unique(dir, &stem, &extension, &bytes))
+    .map_err(|err| format!("copy {} failed: {}", source.display(), err))
+}
+
+// 保持原引用的写法：file:// 地址仍写成地址，路径仍写成路径
+fn rewrite_href(original: &str, path: &Path) -> String {
+  if original.trim().to_ascii_lowercase().starts_with("file:") {
+    if let Ok(url) = tauri::Url::from_file_path(path) {
+      return url.to_string();
+    }
+  }
+  path.to_string_lossy().into_owned()
+}
+
+struct Copier<'a> {
+  base_dir: Option<&'a Path>,
+  assets_dir: PathBuf,
+  // 同一文件被多处引用时只复制一次
+  copied: HashMap<PathBuf, PathBuf>,
+}
+
+impl Copier<'_> {
+  // 非本地引用或文件不存在时返回 None，保留原值
+  fn relocate(&mut self, href: &str) -> Result<Option<String>, String> {
+    let Some(path) = assets::local_path(href) else {
+      return Ok(None);
+    };
+    let source = assets::resolve(&path, self.base_dir);
+    if !source.is_file() {
+      log::warn!("duplicate_project: asset not found, kept as is: {}", source.display());
+      return Ok(None);
+    }
+    let key = std::fs::canonicalize(&source).unwrap_or(source);
+    let target = match self.copied.get(&key) {
+      Some(target) => target.clone(),
+      None => {
+        let target = copy_into(&key, &self.assets_dir)?;
+        self.copied.insert(key, target.clone());
+        target
+      }
+    };
+    Ok(Some(rewrite_href(href, &target)))
+  }
+}
+
+// 先复制资源再写项目文件，副本不会引用尚未复制的文件
+pub fn duplicate(
+  project: &CfjProject,
+  source_path: Option<&Path>,
+  new_path: &Path,
+  overwrite: bool,
+) -> Result<CfjProject, String> {
+  if new_path.exists() && !overwrite {
+    return Err(format!("file already exists: {}", new_path.display()));
+  }
+  let mut copy = project.clone();
+  // 新的项目 id，避免与原项目共用自动保存记录
+  copy.id = cfj::new_id();
+  let mut copier = Copier {
+    base_dir: source_path.and_then(Path::parent),
+    assets_dir: assets_dir_for(new_path),
+    copied: HashMap::new(),
+  };
+  for asset in assets::references(project) {
+    let Some(href) = copier.relocate(&asset.href)? else {
+      continue;
+    };
+    assets::replace(&mut copy, &asset, href);
+  }
+  for font in copy.fonts.iter_mut().filter(|font| font.data.is_none()) {
+    let Some(path) = font.path.clone() else {
+      continue;
+    };
+    if let Some(href) = copier.relocate(&path)? {
+      font.path = Some(href);
+    }
+  }
+  cfj::save(new_path, &copy, cfj::is_compressed_path(new_path)).map_err(|err| err.to_string())?;
+  log::info!(
+    "duplicated project to {} ({} assets copied)",
+    new_path.display(),
+    copier.copied.len()
+  );
+  Ok(copy)
+}
+
+// source_path 为原项目文件路径，用于解析相对路径引用
+#[tauri::command]
+pub async fn duplicate_project(
+  app: AppHandle,
+  project: CfjProject,
+  new_path: String,
+  source_path: Option<String>,
+  overwrite: Option<bool>,
+) -> Result<CfjProject, String> {
+  limits::check(&app, "duplicate_project", std::slice::from_ref(&project))?;
+  let target = PathBuf::from(&new_path);
+  let overwrite = overwrite.unwrap_or(false);
+  let displaced = if overwrite {
+    recycle::before_overwrite(&app, &target)?
+  } else {
+    None
+  };
+  let restore = displaced.clone();
+  let copy = tauri::async_runtime::spawn_blocking(move || {
+    duplicate(&project, source_path.as_deref().map(Path::new), &target, overwrite)
+  })
+  .await
+  .map_err(|err| err.to_string())?
+  .map_err(|err| {
+    log::error!("duplicate_project failed: {}: {}", new_path, err);
+    if let Some(displaced) = &restore {
+      recycle::restore(displaced);
+    }
+    err
+  })?;
+  recycle::report(&app, displaced);
+  recent::push(&app, &new_path);
+  Ok(copy)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj::{BackgroundType, LayerType};
+
+  // 图片图层用相对路径，背景用 file:// 地址，两者指向同一文件
+  fn project_with_assets(dir: &Path) -> CfjProject {
+    std::fs::create_dir_all(dir.join("img")).unwrap();
+    std::fs::write(dir.join("img").join("photo.png"), b"photo").unwrap();
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.background.background_type = BackgroundType::Image;
+    project.background.value = tauri::Url::from_file_path(dir.join("img").join("photo.png")).unwrap().to_string();
+    let mut layer = project.layers[0].clone();
+    layer.id = "img".to_string();
+    layer.layer_type = LayerType::Image;
+    layer.content = "img/photo.png".to_string();
+    project.layers.push(layer);
+    project
+  }
+
+  #[test]
+  fn copy_loads_after_original_is_deleted() {
+    let original_dir = tempfile::tempdir().unwrap();
+    let target_dir = tempfile::tempdir().unwrap();
+    let source = original_dir.path().join("cover.cfj");
+    let project = project_with_assets(original_dir.path());
+    cfj::save(&source, &project, false).unwrap();
+    let new_path = target_dir.path().join("copy.cfj");
+
+    let copy = duplicate(&project, Some(&source), &new_path, false).unwrap();
+    assert_ne!(copy.id, project.id);
+    drop(original_dir);
+
+    let loaded = cfj::load(&new_path).unwrap();
+    assert_eq!(loaded, copy);
+    let refs = assets::references(&loaded);
+    assert_eq!(refs.len(), 2);
+    for asset in &refs {
+      let path = assets::local_path(&asset.href).unwrap();
+      assert!(path.starts_with(target_dir.path().join("copy_assets")), "{}", path.display());
+      assert_eq!(std::fs::read(&path).unwrap(), b"photo");
+    }
+    // 同一文件只复制一次，背景仍写成 file:// 地址
+    assert_eq!(std::fs::read_dir(target_dir.path().join("copy_assets")).unwrap().count(), 1);
+    assert!(loaded.background.value.starts_with("file:"));
+  }
+
+  #[test]
+  fn existing_target_needs_overwrite() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = project_with_assets(dir.path());
+    let new_path = dir.path().join("copy.cfj");
+    std::fs::write(&new_path, "{}").unwrap();
+    assert!(duplicate(&project, None, &new_path, false).is_err());
+    assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "{}");
+    assert!(duplicate(&project, None, &new_path, true).is_ok());
+  }
+}
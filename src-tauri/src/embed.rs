@@ -0,0 +1,287 @@
+// 模块：把项目引用的本地图片内嵌为 data: 地址以便分享，或反向导出为文件
+// 内嵌后的项目建议保存为 .cfjz，base64 在压缩后体积接近原图
+use crate::{assets, limits};
+use crate::cfj::CfjProject;
+use crate::fetch;
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// 单次内嵌的原始字节总量上限，调用方传入的上限再大也不超过这个值
+const DEFAULT_MAX_EMBED_BYTES: usize = 64 * 1024 * 1024;
+const HARD_MAX_EMBED_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransfer {
+  pub project: CfjProject,
+  // 改写的引用数
+  pub changed: usize,
+  // 内嵌或写出的原始字节数（同一文件只计一次）
+  pub bytes: usize,
+  // 导出时写出的文件
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub files: Vec<String>,
+  pub warnings: Vec<String>,
+}
+
+fn mime_for(bytes: &[u8], path: &Path) -> Option<&'static str> {
+  if let Some(format) = fetch::sniff(bytes) {
+    return Some(format.to_mime_type());
+  }
+  let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+  match extension.as_str() {
+    "svg" => Some("image/svg+xml"),
+    "avif" => Some("image/avif"),
+    _ => None,
+  }
+}
+
+fn extension_for(mime: &str) -> &'static str {
+  match mime {
+    "image/png" => "png",
+    "image/jpeg" | "image/jpg" => "jpg",
+    "image/gif" => "gif",
+    "image/webp" => "webp",
+    "image/svg+xml" => "svg",
+    "image/avif" => "avif",
+    _ => "bin",
+  }
+}
+
+fn data_url_body(href: &str) -> Option<&str> {
+  let href = href.trim();
+  href.get(..5).filter(|prefix| prefix.eq_ignore_ascii_case("data:")).map(|_| &href[5..])
+}
+
+// 只处理 base64 形式，返回 (mime, 字节)
+fn decode_data_url(href: &str) -> Option<(String, Vec<u8>)> {
+  let (meta, data) = data_url_body(href)?.split_once(',')?;
+  let meta = meta.strip_suffix(";base64")?;
+  let mime = meta.split(';').next().unwrap_or_default().to_ascii_lowercase();
+  let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim()).ok()?;
+  Some((mime, bytes))
+}
+
+// 文件名只保留安全字符
+fn file_stem_for(layer_id: Option<&str>) -> String {
+  let stem: String = layer_id
+    .unwrap_or("background")
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+    .take(64)
+    .collect();
+  if stem.is_empty() { "image".to_string() } else { stem }
+}
+
+// 超出上限的图片保留原引用并给出警告
+pub fn embed(project: &CfjProject, base_dir: Option<&Path>, max_bytes: usize) -> AssetTransfer {
+  let limit = max_bytes.clamp(1, HARD_MAX_EMBED_BYTES);
+  let mut result = AssetTransfer {
+    project: project.clone(),
+    changed: 0,
+    bytes: 0,
+    files: Vec::new(),
+    warnings: Vec::new(),
+  };
+  let mut encoded: HashMap<PathBuf, String> = HashMap::new();
+  for asset in assets::references(project) {
+    let Some(path) = assets::local_path(&asset.href) else {
+      continue;
+    };
+    let path = assets::resolve(&path, base_dir);
+    let key = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    let href = match encoded.get(&key) {
+      Some(href) => href.clone(),
+      None => {
+        let bytes = match std::fs::read(&key) {
+          Ok(bytes) => bytes,
+          Err(err) => {
+            result.warnings.push(format!("{}: cannot read {}: {}", asset.label, path.display(), err));
+            continue;
+          }
+        };
+        let Some(mime) = mime_for(&bytes, &key) else {
+          result.warnings.push(format!("{}: {} is not a supported image", asset.label, path.display()));
+          continue;
+        };
+        if result.bytes + bytes.len() > limit {
+          result.warnings.push(format!(
+            "{}: not embedded, total size would exceed {} bytes",
+            asset.label, limit
+          ));
+          continue;
+        }
+        result.bytes += bytes.len();
+        let href = format!(
+          "data:{};base64,{}",
+          mime,
+          base64::engine::general_purpose::STANDARD.encode(&bytes)
+        );
+        encoded.insert(key, href.clone());
+        href
+      }
+    };
+    assets::replace(&mut result.project, &asset, href);
+    result.changed += 1;
+  }
+  result
+}
+
+// 相同内容只写一个文件，引用改为绝对路径
+pub fn extract(project: &CfjProject, dir: &Path) -> Result<AssetTransfer, String> {
+  let mut result = AssetTransfer {
+    project: project.clone(),
+    changed: 0,
+    bytes: 0,
+    files: Vec::new(),
+    warnings: Vec::new(),
+  };
+  let mut written: HashMap<String, PathBuf> = HashMap::new();
+  for asset in assets::references(project) {
+    let href = asset.href.trim();
+    if data_url_body(href).is_none() {
+      continue;
+    }
+    let path = match written.get(href) {
+      Some(path) => path.clone(),
+      None => {
+        let Some((mime, bytes)) = decode_data_url(href) else {
+          result.warnings.push(format!("{}: only base64 data URLs can be extracted", asset.label));
+          continue;
+        };
+        let stem = file_stem_for(asset.layer_id.as_deref());
+        let path = assets::write_unique(dir, &stem, extension_for(&mime), &bytes)
+          .map_err(|err| format!("write {} failed: {}", dir.display(), err))?;
+        result.bytes += bytes.len();
+        result.files.push(path.to_string_lossy().into_owned());
+        written.insert(href.to_string(), path.clone());
+        path
+      }
+    };
+    assets::replace(&mut result.project, &asset, path.to_string_lossy().into_owned());
+    result.changed += 1;
+  }
+  Ok(result)
+}
+
+// source_path 为项目文件路径，用于解析相对路径引用
+#[tauri::command]
+pub async fn embed_assets(
+  app: AppHandle,
+  project: CfjProject,
+  source_path: Option<String>,
+  max_bytes: Option<usize>,
+) -> Result<AssetTransfer, String> {
+  limits::check(&app, "embed_assets", std::slice::from_ref(&project))?;
+  let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_EMBED_BYTES);
+  let result = tauri::async_runtime::spawn_blocking(move || {
+    let base_dir = source_path.as_deref().map(Path::new).and_then(Path::parent);
+    embed(&project, base_dir, max_bytes)
+  })
+  .await
+  .map_err(|err| err.to_string())?;
+  log::info!(
+    "embed_assets: {} references, {} bytes, {} warnings",
+    result.changed,
+    result.bytes,
+    result.warnings.len()
+  );
+  Ok(result)
+}
+
+#[tauri::command]
+pub async fn extract_assets(app: AppHandle, project: CfjProject, dir: String) -> Result<AssetTransfer, String> {
+  limits::check(&app, "extract_assets", std::slice::from_ref(&project))?;
+  let result = tauri::async_runtime::spawn_blocking(move || extract(&project, Path::new(&dir)))
+    .await
+    .map_err(|err| err.to_string())??;
+  log::info!("extract_assets: {} files, {} bytes", result.files.len(), result.bytes);
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj::{self, BackgroundType, LayerType};
+
+  const PNG: &[u8] = include_bytes!("../tests/fixtures/validate/img/a.png");
+
+  fn project_with_image(dir: &Path) -> CfjProject {
+    std::fs::write(dir.join("a.png"), PNG).unwrap();
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.background.background_type = BackgroundType::Image;
+    project.background.value = "a.png".to_string();
+    let mut layer = project.layers[0].clone();
+    layer.id = "img".to_string();
+    layer.layer_type = LayerType::Image;
+    layer.content = dir.join("a.png").to_string_lossy().into_owned();
+    project.layers.push(layer);
+    project
+  }
+
+  #[test]
+  fn embedded_images_survive_moving_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = project_with_image(dir.path());
+    let result = embed(&project, Some(dir.path()), DEFAULT_MAX_EMBED_BYTES);
+    assert_eq!(result.changed, 2);
+    // 同一文件只计一次
+    assert_eq!(result.bytes, PNG.len());
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+    let shared = tempfile::tempdir().unwrap();
+    let path = shared.path().join("shared.cfjz");
+    cfj::save(&path, &result.project, true).unwrap();
+    std::fs::remove_file(dir.path().join("a.png")).unwrap();
+
+    let loaded = cfj::load(&path).unwrap();
+    let refs = assets::references(&loaded);
+    assert_eq!(refs.len(), 2);
+    for asset in refs {
+      assert!(assets::local_path(&asset.href).is_none());
+      assert_eq!(decode_data_url(&asset.href), Some(("image/png".to_string(), PNG.to_vec())));
+    }
+  }
+
+  #[test]
+  fn oversized_images_keep_their_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = project_with_image(dir.path());
+    let result = embed(&project, Some(dir.path()), PNG.len() - 1);
+    assert_eq!(result.changed, 0);
+    assert_eq!(result.warnings.len(), 2);
+    assert_eq!(result.project, project);
+  }
+
+  #[test]
+  fn extract_writes_each_image_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let embedded = embed(&project_with_image(dir.path()), Some(dir.path()), DEFAULT_MAX_EMBED_BYTES).project;
+    let out = dir.path().join("out");
+    let result = extract(&embedded, &out).unwrap();
+    assert_eq!(result.changed, 2);
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(std::fs::read(&result.files[0]).unwrap(), PNG);
+    assert_eq!(Path::new(&result.files[0]).file_name().unwrap(), "background.png");
+  }
+
+  #[test]
+  fn decodes_base64_data_urls_only() {
+    assert_eq!(
+      decode_data_url("data:image/PNG;base64,aGk="),
+      Some(("image/png".to_string(), b"hi".to_vec()))
+    );
+    assert_eq!(
+      decode_data_url("  DATA:image/svg+xml;charset=utf-8;base64,aGk=  "),
+      Some(("image/svg+xml".to_string(), b"hi".to_vec()))
+    );
+    assert_eq!(decode_data_url("data:,hi"), None);
+    assert_eq!(decode_data_url("data:image/svg+xml,<svg/>"), None);
+    assert_eq!(decode_data_url("data:image/png;base64,***"), None);
+    assert_eq!(decode_data_url("https://example.com/a.png"), None);
+    assert_eq!(decode_data_url("data:image/png;base64"), None);
+  }
+}
@@ -0,0 +1,1009 @@
+// 模块：在 Rust 端栅格化导出封面
+use crate::{atomic, limits};
+use crate::cfj::{self, CfjProject, FontAsset};
+use crate::icc;
+use crate::naming::{self, NamingTemplate};
+use crate::optimize::{self, OptimizeLevel};
+use crate::{fonts, render};
+use base64::Engine;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, fontdb};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Semaphore;
+
+const MIN_SCALE: f32 = 0.1;
+const MAX_SCALE: f32 = 16.0;
+const MAX_SIDE: f32 = 16384.0;
+const MAX_PIXELS: f32 = 64_000_000.0;
+const JPEG_QUALITY: u8 = 90;
+const WEBP_QUALITY: u8 = 80;
+const AVIF_QUALITY: u8 = 70;
+// 1 最慢、压缩率最高，10 最快
+const AVIF_SPEED: u8 = 6;
+// 颜色数不超过该值视为平面图形，WebP 默认无损
+const FLAT_ART_COLORS: usize = 256;
+
+pub const PROGRESS_EVENT: &str = "export://progress";
+pub const CANCELLED_EVENT: &str = "export://cancelled";
+
+#[derive(Debug)]
+pub enum ExportError {
+  Render(String),
+  Encode(String),
+  Write(String),
+  MissingFont(String),
+  InvalidQuality(u8),
+  Cancelled,
+}
+
+impl fmt::Display for ExportError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ExportError::Render(detail) => write!(f, "render failed: {}", detail),
+      ExportError::Encode(detail) => write!(f, "encode failed: {}", detail),
+      ExportError::Write(detail) => write!(f, "write failed: {}", detail),
+      ExportError::MissingFont(font) => write!(f, "missing font: {}", font),
+      ExportError::InvalidQuality(quality) => write!(f, "quality {} outside 1-100", quality),
+      ExportError::Cancelled => write!(f, "export cancelled"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+  Png,
+  Jpeg,
+  Webp,
+  Avif,
+}
+
+impl ExportFormat {
+  pub fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Png => "png",
+      ExportFormat::Jpeg => "jpg",
+      ExportFormat::Webp => "webp",
+      ExportFormat::Avif => "avif",
+    }
+  }
+
+  // AVIF 编码器不支持写入 ICC
+  pub fn supports_icc(self) -> bool {
+    self != ExportFormat::Avif
+  }
+}
+
+// 编码参数；缺省时按格式取默认值（WebP 对平面图形用无损）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodeOptions {
+  #[serde(default)]
+  pub quality: Option<u8>,
+  // 只对 WebP 生效
+  #[serde(default)]
+  pub lossless: Option<bool>,
+  // 只对 PNG 生效
+  #[serde(default)]
+  pub optimize: OptimizeLevel,
+  // 像素按 sRGB 渲染，嵌入 sRGB 配置文件供其他软件识别；AVIF 不支持
+  #[serde(default = "default_true")]
+  pub embed_srgb: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+impl Default for EncodeOptions {
+  fn default() -> Self {
+    EncodeOptions {
+      quality: None,
+      lossless: None,
+      optimize: OptimizeLevel::None,
+      embed_srgb: true,
+    }
+  }
+}
+
+impl EncodeOptions {
+  pub fn check(&self) -> Result<(), ExportError> {
+    match self.quality {
+      Some(quality) if !(1..=100).contains(&quality) => Err(ExportError::InvalidQuality(quality)),
+      _ => Ok(()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedImage {
+  pub path: String,
+  pub width: u32,
+  pub height: u32,
+  // 写入的文件大小
+  pub bytes: usize,
+  // 开启优化时优化前的大小
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub unoptimized_bytes: Option<usize>,
+  pub warnings: Vec<String>,
+}
+
+pub fn describe_font(font: &FontAsset) -> String {
+  let mut name = font.family.clone();
+  if let Some(weight) = font.weight {
+    name.push_str(&format!(" {}", weight));
+  }
+  if font.italic {
+    name.push_str(" italic");
+  }
+  name
+}
+
+pub fn font_bytes(font: &FontAsset) -> Option<Vec<u8>> {
+  if let Some(data) = &font.data {
+    let encoded = data.split_once("base64,").map_or(data.as_str(), |(_, rest)| rest);
+    return base64::engine::general_purpose::STANDARD.decode(encoded.trim()).ok();
+  }
+  std::fs::read(font.path.as_deref()?).ok()
+}
+
+// 字体文件需真的提供所声明的字族与字重/斜体，否则渲染会悄悄回退
+fn provides(db: &fontdb::Database, ids: &[fontdb::ID], font: &FontAsset) -> bool {
+  ids.iter().filter_map(|id| db.face(*id)).any(|face| {
+    face.families.iter().any(|(name, _)| name.eq_ignore_ascii_case(&font.family))
+      && font.weight.is_none_or(|weight| face.weight.0 == weight)
+      && font.italic == (face.style != fontdb::Style::Normal)
+  })
+}
+
+// 项目自带字体加载到系统字体库的副本中，无法解析时报 missing font
+fn font_database_for(project: &CfjProject) -> Result<Arc<fontdb::Database>, ExportError> {
+  let base = fonts::database();
+  if project.fonts.is_empty() {
+    return Ok(base);
+  }
+  let mut db = (*base).clone();
+  for font in &project.fonts {
+    let bytes = font_bytes(font).ok_or_else(|| ExportError::MissingFont(describe_font(font)))?;
+    let ids = db.load_font_source(fontdb::Source::Binary(Arc::new(bytes)));
+    if !provides(&db, &ids, font) {
+      return Err(ExportError::MissingFont(describe_font(font)));
+    }
+  }
+  Ok(Arc::new(db))
+}
+
+// 限制缩放倍数与总像素，避免超大画布耗尽内存
+pub fn output_size(width: f32, height: f32, scale: f32) -> (u32, u32) {
+  let scale = if scale.is_finite() { scale.clamp(MIN_SCALE, MAX_SCALE) } else { 1.0 };
+  let limit = (MAX_SIDE / width)
+    .min(MAX_SIDE / height)
+    .min((MAX_PIXELS / (width * height)).sqrt());
+  let scale = scale.min(limit);
+  (
+    ((width * scale).round() as u32).max(1),
+    ((height * scale).round() as u32).max(1),
+  )
+}
+
+fn parse_tree(project: &CfjProject) -> Result<(usvg::Tree, Vec<String>), ExportError> {
+  let composed = render::compose(project);
+  let options = usvg::Options {
+    fontdb: font_database_for(project)?,
+    ..usvg::Options::default()
+  };
+  let tree = usvg::Tree::from_str(&composed.markup, &options)
+    .map_err(|err| ExportError::Render(err.to_string()))?;
+  Ok((tree, composed.warnings))
+}
+
+fn render_tree(tree: &usvg::Tree, width: u32, height: u32) -> Result<Pixmap, ExportError> {
+  let size = tree.size();
+  let mut pixmap = Pixmap::new(width, height)
+    .ok_or_else(|| ExportError::Render(format!("cannot allocate {}x{}", width, height)))?;
+  let transform = Transform::from_scale(width as f32 / size.width(), height as f32 / size.height());
+  resvg::render(tree, transform, &mut pixmap.as_mut());
+  Ok(pixmap)
+}
+
+// 返回位图与合成时无法还原的效果列表
+pub fn rasterize(project: &CfjProject, scale: f32) -> Result<(Pixmap, Vec<String>), ExportError> {
+  let (tree, warnings) = parse_tree(project)?;
+  let size = tree.size();
+  let (width, height) = output_size(size.width(), size.height(), scale);
+  Ok((render_tree(&tree, width, height)?, warnings))
+}
+
+// 按最长边缩放到 max_side 像素（用于缩略图，不受最小倍率限制），警告同 rasterize
+pub fn rasterize_fit(project: &CfjProject, max_side: u32) -> Result<(Pixmap, Vec<String>), ExportError> {
+  let (tree, warnings) = parse_tree(project)?;
+  let size = tree.size();
+  let scale = max_side as f32 / size.width().max(size.height());
+  let width = ((size.width() * scale).round() as u32).max(1);
+  let height = ((size.height() * scale).round() as u32).max(1);
+  Ok((render_tree(&tree, width, height)?, warnings))
+}
+
+// 按白底合成为 RGB（像素为预乘 alpha）
+pub fn flatten_rgb(pixmap: &Pixmap) -> Vec<u8> {
+  let mut rgb = Vec::with_capacity((pixmap.width() * pixmap.height() * 3) as usize);
+  for pixel in pixmap.pixels() {
+    let white = 255 - pixel.alpha();
+    rgb.extend_from_slice(&[
+      pixel.red().saturating_add(white),
+      pixel.green().saturating_add(white),
+      pixel.blue().saturating_add(white),
+    ]);
+  }
+  rgb
+}
+
+// 还原为非预乘的 RGBA
+pub fn unpremultiplied_rgba(pixmap: &Pixmap) -> Vec<u8> {
+  let mut rgba = Vec::with_capacity(pixmap.data().len());
+  for pixel in pixmap.pixels() {
+    let color = pixel.demultiply();
+    rgba.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+  }
+  rgba
+}
+
+// 颜色数少的画面（纯色、图标、文字）无损压缩更小
+pub fn is_flat_art(pixmap: &Pixmap) -> bool {
+  let mut colors = HashSet::new();
+  pixmap.pixels().iter().all(|pixel| {
+    colors.insert((pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()));
+    colors.len() <= FLAT_ART_COLORS
+  })
+}
+
+// JPEG 不支持透明
+fn encode_jpeg(pixmap: &Pixmap, quality: u8) -> Result<Vec<u8>, ExportError> {
+  let rgb = flatten_rgb(pixmap);
+  let mut bytes = Vec::new();
+  JpegEncoder::new_with_quality(&mut bytes, quality)
+    .encode(&rgb, pixmap.width(), pixmap.height(), ExtendedColorType::Rgb8)
+    .map_err(|err| ExportError::Encode(err.to_string()))?;
+  Ok(bytes)
+}
+
+fn encode_webp(pixmap: &Pixmap, options: &EncodeOptions) -> Result<Vec<u8>, ExportError> {
+  let rgba = unpremultiplied_rgba(pixmap);
+  let lossless = options.lossless.unwrap_or_else(|| is_flat_art(pixmap));
+  let quality = options.quality.unwrap_or(WEBP_QUALITY);
+  let encoded = webp::Encoder::from_rgba(&rgba, pixmap.width(), pixmap.height())
+    .encode_simple(lossless, quality as f32)
+    .map_err(|err| ExportError::Encode(format!("webp: {:?}", err)))?;
+  Ok(encoded.to_vec())
+}
+
+fn encode_avif(pixmap: &Pixmap, quality: u8) -> Result<Vec<u8>, ExportError> {
+  let rgba = unpremultiplied_rgba(pixmap);
+  let mut bytes = Vec::new();
+  AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, quality)
+    .write_image(&rgba, pixmap.width(), pixmap.height(), ExtendedColorType::Rgba8)
+    .map_err(|err| ExportError::Encode(err.to_string()))?;
+  Ok(bytes)
+}
+
+pub fn encode(pixmap: &Pixmap, format: ExportFormat) -> Result<Vec<u8>, ExportError> {
+  encode_with(pixmap, format, &EncodeOptions::default())
+}
+
+pub fn encode_with(pixmap: &Pixmap, format: ExportFormat, options: &EncodeOptions) -> Result<Vec<u8>, ExportError> {
+  options.check()?;
+  match format {
+    ExportFormat::Png => pixmap.encode_png().map_err(|err| ExportError::Encode(err.to_string())),
+    ExportFormat::Jpeg => encode_jpeg(pixmap, options.quality.unwrap_or(JPEG_QUALITY)),
+    ExportFormat::Webp => encode_webp(pixmap, options),
+    ExportFormat::Avif => encode_avif(pixmap, options.quality.unwrap_or(AVIF_QUALITY)),
+  }
+}
+
+pub fn export_to(
+  project: &CfjProject,
+  format: ExportFormat,
+  scale: f32,
+  out_path: &Path,
+) -> Result<ExportedImage, ExportError> {
+  export_with(project, format, &EncodeOptions::default(), scale, out_path)
+}
+
+pub fn export_with(
+  project: &CfjProject,
+  format: ExportFormat,
+  options: &EncodeOptions,
+  scale: f32,
+  out_path: &Path,
+) -> Result<ExportedImage, ExportError> {
+  export_cancellable(project, format, options, scale, out_path, &AtomicBool::new(false))
+}
+
+fn check_cancelled(cancel: &AtomicBool) -> Result<(), ExportError> {
+  if cancel.load(Ordering::SeqCst) {
+    Err(ExportError::Cancelled)
+  } else {
+    Ok(())
+  }
+}
+
+// 在渲染、编码、写入之间检查取消标记；写入途中取消会删除临时文件，目标文件不受影响
+pub fn export_cancellable(
+  project: &CfjProject,
+  format: ExportFormat,
+  options: &EncodeOptions,
+  scale: f32,
+  out_path: &Path,
+  cancel: &AtomicBool,
+) -> Result<ExportedImage, ExportError> {
+  // 参数错误时不必先渲染
+  options.check()?;
+  check_cancelled(cancel)?;
+  let (pixmap, mut warnings) = rasterize(project, scale)?;
+  check_cancelled(cancel)?;
+  let mut bytes = encode_with(&pixmap, format, options)?;
+  let mut unoptimized_bytes = None;
+  if options.optimize != OptimizeLevel::None {
+    if format == ExportFormat::Png {
+      unoptimized_bytes = Some(bytes.len());
+      // 优化失败时保留原始编码结果
+      match optimize::optimize_png(&bytes, options.optimize) {
+        Ok(Some(optimized)) => bytes = optimized,
+        Ok(None) => {}
+        Err(err) => warnings.push(format!("PNG optimization skipped: {}", err)),
+      }
+    } else {
+      warnings.push(format!("optimization does not apply to {}", format.extension()));
+    }
+  }
+  // 在优化之后写入，oxipng 不会再剥离
+  if options.embed_srgb && format.supports_icc() {
+    match icc::embed_srgb(&bytes, format, pixmap.width(), pixmap.height()) {
+      Some(tagged) => {
+        // 未优化大小同样计入配置文件，两者可直接比较
+        let added = tagged.len().saturating_sub(bytes.len());
+        unoptimized_bytes = unoptimized_bytes.map(|size| size + added);
+        bytes = tagged;
+      }
+      None => warnings.push(format!("sRGB profile not embedded in {}", format.extension())),
+    }
+  }
+  check_cancelled(cancel)?;
+  atomic::write_with(out_path, |file| {
+    file.write_all(&bytes)?;
+    // 重命名前最后检查一次，出错时临时文件由 write_with 删除
+    if cancel.load(Ordering::SeqCst) {
+      return Err(io::Error::new(io::ErrorKind::Interrupted, "export cancelled"));
+    }
+    Ok(())
+  })
+  .map_err(|err| match err.kind() {
+    io::ErrorKind::Interrupted if cancel.load(Ordering::SeqCst) => ExportError::Cancelled,
+    _ => ExportError::Write(err.to_string()),
+  })?;
+  log::info!(
+    "exported {}x{} {} ({} bytes): {}",
+    pixmap.width(),
+    pixmap.height(),
+    format.extension(),
+    bytes.len(),
+    out_path.display()
+  );
+  Ok(ExportedImage {
+    path: out_path.to_string_lossy().into_owned(),
+    width: pixmap.width(),
+    height: pixmap.height(),
+    bytes: bytes.len(),
+    unoptimized_bytes,
+    warnings,
+  })
+}
+
+// format 缺省为 PNG，保持旧调用方式可用
+#[tauri::command]
+pub async fn export_png(
+  app: AppHandle,
+  project: CfjProject,
+  scale: f32,
+  out_path: String,
+  format: Option<ExportFormat>,
+  options: Option<EncodeOptions>,
+) -> Result<ExportedImage, String> {
+  limits::check(&app, "export_png", std::slice::from_ref(&project))?;
+  let format = format.unwrap_or(ExportFormat::Png);
+  let options = options.unwrap_or_default();
+  tauri::async_runtime::spawn_blocking(move || {
+    export_with(&project, format, &options, scale, Path::new(&out_path))
+  })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFailure {
+  pub index: usize,
+  pub title: String,
+  pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExport {
+  pub job_id: String,
+  pub written: Vec<String>,
+  pub failed: Vec<ExportFailure>,
+  // 被取消时未导出的封面既不在 written 也不在 failed 中
+  pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress {
+  job_id: String,
+  completed: usize,
+  total: usize,
+  // 刚处理完（成功或失败）的文件名，开始时为空；多个封面并行导出，没有“当前文件”
+  completed_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportCancelled {
+  job_id: String,
+  written: usize,
+  total: usize,
+}
+
+// 进行中的批量导出，按任务 id 保存取消标记
+#[derive(Default)]
+pub struct ExportJobs(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+impl ExportJobs {
+  fn start(&self, id: &str) -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    if let Ok(mut jobs) = self.0.lock() {
+      jobs.insert(id.to_string(), cancel.clone());
+    }
+    cancel
+  }
+
+  fn finish(&self, id: &str) {
+    if let Ok(mut jobs) = self.0.lock() {
+      jobs.remove(id);
+    }
+  }
+
+  fn cancel(&self, id: &str) -> bool {
+    let Ok(jobs) = self.0.lock() else {
+      return false;
+    };
+    match jobs.get(id) {
+      Some(cancel) => {
+        cancel.store(true, Ordering::SeqCst);
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+// 已开始的封面会在下一个检查点停下；返回任务是否存在
+#[tauri::command]
+pub fn cancel_export(app: AppHandle, job_id: String) -> bool {
+  let cancelled = app.state::<ExportJobs>().cancel(&job_id);
+  if cancelled {
+    log::info!("export cancel requested: {}", job_id);
+  }
+  cancelled
+}
+
+// 文件名主体的字节上限，为重名后缀与扩展名留出余量（多数文件系统限制 255 字节）
+const MAX_STEM_BYTES: usize = 200;
+
+const WINDOWS_RESERVED: [&str; 22] = [
+  "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+  "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+fn truncate_bytes(text: &str, max: usize) -> &str {
+  if text.len() <= max {
+    return text;
+  }
+  let mut end = max;
+  while !text.is_char_boundary(end) {
+    end -= 1;
+  }
+  &text[..end]
+}
+
+// windows 为 true 时按 Windows 规则处理：更多非法字符、去掉结尾的点和空格、避开设备名
+pub fn sanitize_filename_for(title: &str, windows: bool) -> String {
+  let illegal: &[char] = if windows { &['\\', '/', ':', '*', '?', '"', '<', '>', '|'] } else { &['/'] };
+  let cleaned: String = title
+    .chars()
+    .map(|ch| if ch.is_control() || illegal.contains(&ch) { '_' } else { ch })
+    .collect();
+  let trimmed = cleaned.trim();
+  let trimmed = if windows { trimmed.trim_end_matches(['.', ' ']) } else { trimmed };
+  let mut name = truncate_bytes(trimmed, MAX_STEM_BYTES).trim_end().to_string();
+  if windows {
+    name = name.trim_end_matches(['.', ' ']).to_string();
+    let base = name.split('.').next().unwrap_or_default().trim_end();
+    if WINDOWS_RESERVED.iter().any(|reserved| reserved.eq_ignore_ascii_case(base)) {
+      name.insert(0, '_');
+    }
+  }
+  if name == "." || name == ".." {
+    return String::new();
+  }
+  name
+}
+
+pub fn sanitize_filename(title: &str) -> String {
+  sanitize_filename_for(title, cfg!(windows))
+}
+
+// index 从 0 开始；清理后为空时回退为 cover_<序号>，序号与命名模板的 {index} 一样从 1 开始
+pub(crate) fn file_stem(title: &str, index: usize) -> String {
+  let name = sanitize_filename(title);
+  if name.is_empty() {
+    format!("cover_{}", index + 1)
+  } else {
+    name
+  }
+}
+
+// 同名封面追加数字后缀
+pub(crate) fn unique_names(stems: Vec<String>, format: ExportFormat) -> Vec<String> {
+  let mut used = HashSet::new();
+  stems
+    .into_iter()
+    .map(|stem| {
+      let mut name = format!("{}.{}", stem, format.extension());
+      let mut suffix = 2;
+      while !used.insert(name.to_lowercase()) {
+        name = format!("{}_{}.{}", stem, suffix, format.extension());
+        suffix += 1;
+      }
+      name
+    })
+    .collect()
+}
+
+// 参数与前端调用一一对应，不合并成结构体
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_all(
+  app: AppHandle,
+  projects: Vec<CfjProject>,
+  out_dir: String,
+  format: ExportFormat,
+  scale: f32,
+  options: Option<EncodeOptions>,
+  naming_template: Option<String>,
+  job_id: Option<String>,
+) -> Result<BatchExport, String> {
+  limits::check(&app, "export_all", &projects)?;
+  let options = options.unwrap_or_default();
+  options.check().map_err(|err| err.to_string())?;
+  // 模板有误时在写入任何文件前报错
+  let template = naming_template
+    .as_deref()
+    .map(NamingTemplate::parse)
+    .transpose()
+    .map_err(|err| err.to_string())?;
+  let dir = PathBuf::from(&out_dir);
+  if !dir.is_dir() {
+    return Err(format!("directory not found: {}", out_dir));
+  }
+
+  // job_id 由前端传入时可在调用返回前取消，否则从首个进度事件中取得
+  let job_id = job_id.filter(|id| !id.is_empty()).unwrap_or_else(cfj::new_id);
+  let stems = match &template {
+    Some(template) => naming::stems(template, &projects, format, scale),
+    None => projects.iter().enumerate().map(|(index, project)| file_stem(&project.title, index)).collect(),
+  };
+  let names = unique_names(stems, format);
+  let job = BatchJob {
+    job_id,
+    dir,
+    format,
+    scale,
+    options,
+    workers: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2),
+  };
+  let progress_app = app.clone();
+  let emit: ProgressSink = Arc::new(move |progress| {
+    let _ = progress_app.emit(PROGRESS_EVENT, progress);
+  });
+  let total = projects.len();
+  let batch = run_batch(&app.state::<ExportJobs>(), job, projects, names, emit).await;
+  if batch.cancelled {
+    log::info!("export {} cancelled after {} of {} covers", batch.job_id, batch.written.len(), total);
+    let _ = app.emit(
+      CANCELLED_EVENT,
+      ExportCancelled { job_id: batch.job_id.clone(), written: batch.written.len(), total },
+    );
+  }
+  Ok(batch)
+}
+
+struct BatchJob {
+  job_id: String,
+  dir: PathBuf,
+  format: ExportFormat,
+  scale: f32,
+  options: EncodeOptions,
+  // 同时导出的封面数
+  workers: usize,
+}
+
+type ProgressSink = Arc<dyn Fn(ExportProgress) + Send + Sync>;
+
+// export_all 的主体，进度经 emit 发出；names 与 projects 一一对应
+async fn run_batch(
+  jobs: &ExportJobs,
+  job: BatchJob,
+  projects: Vec<CfjProject>,
+  names: Vec<String>,
+  emit: ProgressSink,
+) -> BatchExport {
+  let BatchJob { job_id, dir, format, scale, options, workers } = job;
+  let total = projects.len();
+  let semaphore = Arc::new(Semaphore::new(workers.max(1)));
+  // 计数与发出进度在同一把锁内，并行完成时完成数也按顺序到达前端
+  let completed = Arc::new(Mutex::new(0usize));
+  let mut tasks = Vec::with_capacity(total);
+  let cancel = jobs.start(&job_id);
+  emit(ExportProgress { job_id: job_id.clone(), completed: 0, total, completed_file: None });
+
+  for (index, (project, name)) in projects.into_iter().zip(names).enumerate() {
+    let Ok(permit) = semaphore.clone().acquire_owned().await else {
+      break;
+    };
+    // 取消后不再启动新的封面
+    if cancel.load(Ordering::SeqCst) {
+      break;
+    }
+    let path = dir.join(&name);
+    let completed = completed.clone();
+    let cancel = cancel.clone();
+    let job_id = job_id.clone();
+    let title = project.title.clone();
+    let emit = emit.clone();
+    let task = tauri::async_runtime::spawn_blocking(move || {
+      // 进度发出后才释放名额，收到进度时取消可保证不再启动新的封面
+      let _permit = permit;
+      let result = export_cancellable(&project, format, &options, scale, &path, &cancel);
+      match &result {
+        Err(ExportError::Cancelled) => return result,
+        Err(err) => log::warn!("export failed: {}: {}", path.display(), err),
+        Ok(_) => {}
+      }
+      if let Ok(mut done) = completed.lock() {
+        *done += 1;
+        emit(ExportProgress { job_id, completed: *done, total, completed_file: Some(name) });
+      }
+      result
+    });
+    tasks.push((index, title, task));
+  }
+
+  // 单个封面失败不影响其余封面
+  let mut batch = BatchExport { job_id: job_id.clone(), ..BatchExport::default() };
+  for (index, title, task) in tasks {
+    match task.await {
+      Ok(Ok(image)) => batch.written.push(image.path),
+      Ok(Err(ExportError::Cancelled)) => {}
+      Ok(Err(err)) => batch.failed.push(ExportFailure { index, title, error: err.to_string() }),
+      Err(err) => batch.failed.push(ExportFailure { index, title, error: err.to_string() }),
+    }
+  }
+  jobs.finish(&job_id);
+  // 全部封面都已处理完才取消的不算
+  batch.cancelled = cancel.load(Ordering::SeqCst) && batch.written.len() + batch.failed.len() < total;
+  batch
+}
+
+// 在文件管理器中定位文件；走系统接口而非拼接命令行，路径无需转义
+#[tauri::command]
+pub fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), String> {
+  let target = std::fs::canonicalize(&path).map_err(|err| format!("file not found: {}: {}", path, err))?;
+  match app.opener().reveal_item_in_dir(&target) {
+    Ok(()) => Ok(()),
+    // 部分 Linux 桌面没有 FileManager1 接口，退而打开所在目录
+    Err(err) if cfg!(target_os = "linux") => {
+      log::warn!("reveal failed, opening parent: {}: {}", target.display(), err);
+      let dir = if target.is_dir() { target.as_path() } else { target.parent().unwrap_or(&target) };
+      app
+        .opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|err| err.to_string())
+    }
+    Err(err) => Err(err.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALID: &str = include_str!("../tests/fixtures/valid.cfj");
+
+  #[test]
+  fn exports_png_at_canvas_size() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.png");
+    let exported = export_to(&project, ExportFormat::Png, 1.0, &path).unwrap();
+    assert_eq!((exported.width, exported.height), (900, 383));
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes.len(), exported.bytes);
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    // IHDR 紧跟在签名之后
+    assert_eq!(&bytes[12..16], b"IHDR");
+    let width = u32::from_be_bytes(bytes[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(bytes[20..24].try_into().unwrap());
+    assert_eq!((width, height), (900, 383));
+  }
+
+  #[test]
+  fn scale_multiplies_output_size() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let exported = export_to(&project, ExportFormat::Png, 2.0, &dir.path().join("cover@2x.png")).unwrap();
+    assert_eq!((exported.width, exported.height), (1800, 766));
+  }
+
+  #[test]
+  fn sanitizes_windows_names() {
+    for (title, expected) in [
+      ("a/b:c*d", "a_b_c_d"),
+      ("ok?<>|\"", "ok_____"),
+      ("  title.. ", "title"),
+      ("a. . .", "a"),
+      ("CON", "_CON"),
+      ("con.txt", "_con.txt"),
+      ("Lpt9 ", "_Lpt9"),
+      ("COM10", "COM10"),
+      ("a\u{0}b\tc", "a_b_c"),
+      ("...", ""),
+      ("封面：标题", "封面：标题"),
+    ] {
+      assert_eq!(sanitize_filename_for(title, true), expected, "{:?}", title);
+    }
+  }
+
+  #[test]
+  fn sanitizes_posix_names() {
+    for (title, expected) in [
+      ("a/b:c*d", "a_b:c*d"),
+      ("  title.. ", "title.."),
+      ("CON", "CON"),
+      ("a\nb", "a_b"),
+      (".hidden", ".hidden"),
+      (".", ""),
+      ("..", ""),
+    ] {
+      assert_eq!(sanitize_filename_for(title, false), expected, "{:?}", title);
+    }
+  }
+
+  #[test]
+  fn truncates_long_names_on_char_boundary() {
+    let name = sanitize_filename_for(&"字".repeat(100), true);
+    assert!(name.len() <= MAX_STEM_BYTES);
+    assert!(!name.is_empty() && name.chars().all(|ch| ch == '字'));
+  }
+
+  #[test]
+  fn fallback_stems_count_from_one_with_or_without_template() {
+    assert_eq!(file_stem("", 0), "cover_1");
+    assert_eq!(file_stem("  ", 4), "cover_5");
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.title = String::new();
+    let projects = [project.clone(), project];
+    let plain: Vec<String> = projects.iter().enumerate().map(|(index, project)| file_stem(&project.title, index)).collect();
+    let template = NamingTemplate::parse("{title}").unwrap();
+    assert_eq!(naming::stems(&template, &projects, ExportFormat::Png, 1.0), plain);
+  }
+
+  fn text_project(family: &str, fonts: Vec<FontAsset>) -> CfjProject {
+    let text = format!(
+      r##"{{"id":"f","title":"F","layers":[{{"id":"t","name":"t","type":"text","content":"Hamburgefonstiv","x":0,"y":0,"width":400,"height":100,"fontSize":48,"fontFamily":"{}","fontWeight":400,"color":"#000000"}}],"background":{{"type":"color","value":"#ffffff"}},"canvasConfig":{{"width":400,"height":100}}}}"##,
+      family
+    );
+    let mut project = cfj::parse(&text).unwrap().project;
+    project.fonts = fonts;
+    project
+  }
+
+  fn tuffy(path: Option<String>, data: Option<String>, italic: bool) -> FontAsset {
+    FontAsset { family: "Tuffy".to_string(), weight: None, italic, path, data }
+  }
+
+  // Tuffy 为公有领域字体，系统中通常没有，便于与回退字体区分
+  const TUFFY: &[u8] = include_bytes!("../tests/fixtures/fonts/Tuffy.ttf");
+
+  #[test]
+  fn project_fonts_replace_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    let font_path = dir.path().join("Tuffy.ttf");
+    std::fs::write(&font_path, TUFFY).unwrap();
+    let font_path = font_path.to_string_lossy().into_owned();
+
+    let (fallback, _) = rasterize(&text_project("Tuffy, sans-serif", vec![]), 1.0).unwrap();
+    let (custom, _) = rasterize(&text_project("Tuffy, sans-serif", vec![tuffy(Some(font_path), None, false)]), 1.0).unwrap();
+    let differing = fallback.pixels().iter().zip(custom.pixels()).filter(|(a, b)| a != b).count();
+    assert!(differing > 500, "only {} pixels differ", differing);
+
+    // 内嵌的 base64 字体与按路径加载结果一致
+    let data = format!("data:font/ttf;base64,{}", base64::engine::general_purpose::STANDARD.encode(TUFFY));
+    let (embedded, _) = rasterize(&text_project("Tuffy, sans-serif", vec![tuffy(None, Some(data), false)]), 1.0).unwrap();
+    assert_eq!(embedded.data(), custom.data());
+  }
+
+  #[test]
+  fn missing_project_font_is_an_error() {
+    let missing = FontAsset {
+      family: "Nope".to_string(),
+      weight: Some(700),
+      italic: true,
+      path: Some("/no/such/font.ttf".to_string()),
+      data: None,
+    };
+    let err = rasterize(&text_project("Nope", vec![missing]), 1.0).unwrap_err();
+    assert_eq!(err.to_string(), "missing font: Nope 700 italic");
+    // 文件存在但没有所需的斜体
+    let data = format!("data:font/ttf;base64,{}", base64::engine::general_purpose::STANDARD.encode(TUFFY));
+    let wrong_style = tuffy(None, Some(data), true);
+    assert!(matches!(rasterize(&text_project("Tuffy", vec![wrong_style]), 1.0), Err(ExportError::MissingFont(_))));
+  }
+
+  #[test]
+  fn webp_decodes_to_canvas_size() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    for lossless in [true, false] {
+      let options = EncodeOptions { lossless: Some(lossless), ..EncodeOptions::default() };
+      let path = dir.path().join(format!("cover-{}.webp", lossless));
+      let exported = export_with(&project, ExportFormat::Webp, &options, 1.0, &path).unwrap();
+      let bytes = std::fs::read(&path).unwrap();
+      assert_eq!(bytes.len(), exported.bytes);
+      let decoded = image::load_from_memory_with_format(&bytes, image::ImageFormat::WebP).unwrap();
+      assert_eq!((decoded.width(), decoded.height()), (900, 383));
+    }
+  }
+
+  #[test]
+  fn avif_records_canvas_size() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.avif");
+    export_to(&project, ExportFormat::Avif, 1.0, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[4..12], b"ftypavif");
+    // 未启用 AVIF 解码，按 ispe 属性（版本与标志 4 字节后跟宽高）读取尺寸
+    let ispe = bytes.windows(4).position(|window| window == b"ispe").unwrap() + 8;
+    let width = u32::from_be_bytes(bytes[ispe..ispe + 4].try_into().unwrap());
+    let height = u32::from_be_bytes(bytes[ispe + 4..ispe + 8].try_into().unwrap());
+    assert_eq!((width, height), (900, 383));
+  }
+
+  #[test]
+  fn quality_out_of_range_is_rejected() {
+    let pixmap = Pixmap::new(4, 4).unwrap();
+    for quality in [0, 101] {
+      let options = EncodeOptions { quality: Some(quality), ..EncodeOptions::default() };
+      assert!(matches!(
+        encode_with(&pixmap, ExportFormat::Webp, &options),
+        Err(ExportError::InvalidQuality(value)) if value == quality
+      ));
+    }
+  }
+
+  // 按顺序记下 run_batch 发出的进度
+  fn recorder() -> (ProgressSink, Arc<Mutex<Vec<ExportProgress>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    (Arc::new(move |progress| sink.lock().unwrap().push(progress)), events)
+  }
+
+  fn batch_job(dir: &Path, workers: usize) -> BatchJob {
+    BatchJob {
+      job_id: "job".to_string(),
+      dir: dir.to_path_buf(),
+      format: ExportFormat::Png,
+      scale: 0.25,
+      options: EncodeOptions::default(),
+      workers,
+    }
+  }
+
+  #[tokio::test]
+  async fn progress_names_each_completed_file() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let names = unique_names(vec!["cover".to_string(); 3], ExportFormat::Png);
+    let (emit, events) = recorder();
+    let jobs = ExportJobs::default();
+    let batch = run_batch(&jobs, batch_job(dir.path(), 2), vec![project; 3], names.clone(), emit).await;
+    assert_eq!(batch.written.len(), 3);
+    assert!(batch.failed.is_empty() && !batch.cancelled);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], ExportProgress { job_id: "job".to_string(), completed: 0, total: 3, completed_file: None });
+    // 完成数依次递增，每个文件恰好报告一次且已写入
+    assert_eq!(events.iter().map(|event| event.completed).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    let mut reported: Vec<String> = events.iter().filter_map(|event| event.completed_file.clone()).collect();
+    reported.sort();
+    assert_eq!(reported, names);
+    for event in events.iter().skip(1) {
+      assert!(dir.path().join(event.completed_file.as_ref().unwrap()).is_file());
+    }
+    // 任务结束后不能再取消
+    assert!(!jobs.cancel("job"));
+  }
+
+  #[tokio::test]
+  async fn cancel_after_first_cover_writes_nothing_more() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let names = unique_names(vec!["cover".to_string(); 3], ExportFormat::Png);
+    // 第三个目标已存在，取消后应保持原样
+    std::fs::write(dir.path().join(&names[2]), b"old").unwrap();
+    let jobs = Arc::new(ExportJobs::default());
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (sink, canceller) = (events.clone(), jobs.clone());
+    // 收到第一个封面完成的进度时取消，如同用户在进度条上点取消
+    let emit: ProgressSink = Arc::new(move |progress: ExportProgress| {
+      if progress.completed == 1 {
+        assert!(canceller.cancel(&progress.job_id));
+      }
+      sink.lock().unwrap().push(progress);
+    });
+    let batch = run_batch(&jobs, batch_job(dir.path(), 1), vec![project; 3], names.clone(), emit).await;
+    assert!(batch.cancelled);
+    assert_eq!(batch.written, vec![dir.path().join(&names[0]).to_string_lossy().into_owned()]);
+    assert!(batch.failed.is_empty());
+    let completed: Vec<usize> = events.lock().unwrap().iter().map(|event| event.completed).collect();
+    assert_eq!(completed, [0, 1]);
+
+    let mut files: Vec<String> = std::fs::read_dir(dir.path())
+      .unwrap()
+      .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+      .collect();
+    files.sort();
+    // 没有残留的临时文件
+    assert_eq!(files, vec![names[0].clone(), names[2].clone()]);
+    assert_eq!(std::fs::read(dir.path().join(&names[2])).unwrap(), b"old");
+  }
+
+  #[test]
+  fn cancel_between_stages_leaves_target_alone() {
+    let project = cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.png");
+    std::fs::write(&path, b"old").unwrap();
+    let cancel = AtomicBool::new(true);
+    let result = export_cancellable(&project, ExportFormat::Png, &EncodeOptions::default(), 0.25, &path, &cancel);
+    assert!(matches!(result, Err(ExportError::Cancelled)));
+    assert_eq!(std::fs::read(&path).unwrap(), b"old");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+  }
+}
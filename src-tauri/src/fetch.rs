@@ -0,0 +1,219 @@
+// 模块：从网址导入封面图片（仅 HTTP(S)，边下载边限制大小，按文件头识别格式）
+use base64::Engine;
+use serde::Serialize;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::Once;
+use std::time::Duration;
+
+// 调用方传入的上限再大也不超过这个值
+const HARD_MAX_BYTES: usize = 50 * 1024 * 1024;
+const MAX_SIDE: u32 = 16384;
+const TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static TLS_PROVIDER: Once = Once::new();
+
+#[derive(Debug)]
+pub enum FetchError {
+  InvalidUrl(String),
+  UnsupportedScheme(String),
+  Network(String),
+  Status(u16),
+  TooLarge(usize),
+  NotImage,
+  ImageTooLarge(u32, u32),
+}
+
+impl fmt::Display for FetchError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FetchError::InvalidUrl(detail) => write!(f, "invalid url: {}", detail),
+      FetchError::UnsupportedScheme(scheme) => write!(f, "unsupported scheme: {} (only http and https)", scheme),
+      FetchError::Network(detail) => write!(f, "download failed: {}", detail),
+      FetchError::Status(code) => write!(f, "server responded with status {}", code),
+      FetchError::TooLarge(limit) => write!(f, "image exceeds {} bytes", limit),
+      FetchError::NotImage => write!(f, "content is not a supported image"),
+      FetchError::ImageTooLarge(width, height) => {
+        write!(f, "image is {}x{}, larger than {}px per side", width, height, MAX_SIDE)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedImage {
+  pub mime: String,
+  pub width: u32,
+  pub height: u32,
+  pub bytes: usize,
+  pub data_url: String,
+}
+
+pub fn check_url(url: &str) -> Result<reqwest::Url, FetchError> {
+  let parsed = reqwest::Url::parse(url.trim()).map_err(|err| FetchError::InvalidUrl(err.to_string()))?;
+  match parsed.scheme() {
+    "http" | "https" => Ok(parsed),
+    other => Err(FetchError::UnsupportedScheme(other.to_string())),
+  }
+}
+
+// 只认文件头，不信任服务器给的 Content-Type
+pub fn sniff(bytes: &[u8]) -> Option<image::ImageFormat> {
+  if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+    Some(image::ImageFormat::Png)
+  } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+    Some(image::ImageFormat::Jpeg)
+  } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+    Some(image::ImageFormat::Gif)
+  } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+    Some(image::ImageFormat::WebP)
+  } else {
+    None
+  }
+}
+
+pub fn inspect(bytes: Vec<u8>) -> Result<ImportedImage, FetchError> {
+  let format = sniff(&bytes).ok_or(FetchError::NotImage)?;
+  let (width, height) = image::ImageReader::with_format(Cursor::new(&bytes), format)
+    .into_dimensions()
+    .map_err(|_| FetchError::NotImage)?;
+  if width > MAX_SIDE || height > MAX_SIDE {
+    return Err(FetchError::ImageTooLarge(width, height));
+  }
+  let mime = format.to_mime_type().to_string();
+  let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+  Ok(ImportedImage {
+    data_url: format!("data:{};base64,{}", mime, encoded),
+    mime,
+    width,
+    height,
+    bytes: bytes.len(),
+  })
+}
+
+fn client() -> Result<reqwest::Client, FetchError> {
+  // reqwest 与更新插件共用 ring 作为 TLS 实现
+  TLS_PROVIDER.call_once(|| {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+  });
+  reqwest::Client::builder()
+    .timeout(TIMEOUT)
+    .connect_timeout(CONNECT_TIMEOUT)
+    .user_agent(concat!("CoverFlow/", env!("CARGO_PKG_VERSION")))
+    .build()
+    .map_err(|err| FetchError::Network(err.to_string()))
+}
+
+// 先看 Content-Length，再在接收过程中累计字节数，超出立即中止
+pub async fn fetch(url: &str, max_bytes: usize) -> Result<ImportedImage, FetchError> {
+  let url = check_url(url)?;
+  let limit = max_bytes.clamp(1, HARD_MAX_BYTES);
+  let mut response = client()?
+    .get(url.clone())
+    .send()
+    .await
+    .map_err(|err| FetchError::Network(err.to_string()))?;
+  let status = response.status();
+  if !status.is_success() {
+    return Err(FetchError::Status(status.as_u16()));
+  }
+  if response.content_length().is_some_and(|length| length > limit as u64) {
+    return Err(FetchError::TooLarge(limit));
+  }
+  let mut bytes = Vec::new();
+  while let Some(chunk) = response
+    .chunk()
+    .await
+    .map_err(|err| FetchError::Network(err.to_string()))?
+  {
+    if bytes.len() + chunk.len() > limit {
+      return Err(FetchError::TooLarge(limit));
+    }
+    bytes.extend_from_slice(&chunk);
+  }
+  let image = inspect(bytes)?;
+  log::info!("fetch_image: {} ({}x{}, {} bytes)", url, image.width, image.height, image.bytes);
+  Ok(image)
+}
+
+#[tauri::command]
+pub async fn fetch_image(url: String, max_bytes: usize) -> Result<ImportedImage, String> {
+  fetch(&url, max_bytes).await.map_err(|err| {
+    log::warn!("fetch_image failed: {}: {}", url, err);
+    err.to_string()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Read, Write};
+  use std::net::TcpListener;
+
+  fn png_bytes() -> Vec<u8> {
+    let image = image::RgbaImage::from_pixel(3, 2, image::Rgba([1, 2, 3, 255]));
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, image::ImageFormat::Png).unwrap();
+    out.into_inner()
+  }
+
+  // 本地单次应答的 HTTP 服务，headers 为额外的响应头
+  fn serve(status: &'static str, headers: String, body: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = [0u8; 2048];
+      let _ = stream.read(&mut request);
+      let head = format!("HTTP/1.1 {}\r\n{}Connection: close\r\n\r\n", status, headers);
+      let _ = stream.write_all(head.as_bytes());
+      let _ = stream.write_all(&body);
+    });
+    format!("http://{}/cover.png", addr)
+  }
+
+  fn serve_body(status: &'static str, body: Vec<u8>) -> String {
+    serve(status, format!("Content-Length: {}\r\n", body.len()), body)
+  }
+
+  #[tokio::test]
+  async fn downloads_image() {
+    let url = serve_body("200 OK", png_bytes());
+    let image = fetch(&url, 1 << 20).await.unwrap();
+    assert_eq!((image.width, image.height), (3, 2));
+    assert_eq!(image.mime, "image/png");
+    assert!(image.data_url.starts_with("data:image/png;base64,"));
+  }
+
+  #[tokio::test]
+  async fn follows_redirects() {
+    let target = serve_body("200 OK", png_bytes());
+    let url = serve("302 Found", format!("Location: {}\r\nContent-Length: 0\r\n", target), Vec::new());
+    assert_eq!(fetch(&url, 1 << 20).await.unwrap().width, 3);
+  }
+
+  #[tokio::test]
+  async fn reports_http_status() {
+    let url = serve_body("404 Not Found", b"missing".to_vec());
+    assert!(matches!(fetch(&url, 1 << 20).await, Err(FetchError::Status(404))));
+  }
+
+  #[tokio::test]
+  async fn stops_oversized_downloads() {
+    // 声明了 Content-Length 时提前拒绝，未声明时边收边累计
+    let url = serve_body("200 OK", vec![0; 4096]);
+    assert!(matches!(fetch(&url, 1000).await, Err(FetchError::TooLarge(1000))));
+    let url = serve("200 OK", String::new(), vec![0; 4096]);
+    assert!(matches!(fetch(&url, 1000).await, Err(FetchError::TooLarge(1000))));
+  }
+
+  #[tokio::test]
+  async fn rejects_non_images_and_other_schemes() {
+    let url = serve_body("200 OK", b"<html></html>".to_vec());
+    assert!(matches!(fetch(&url, 1 << 20).await, Err(FetchError::NotImage)));
+    assert!(matches!(fetch("file:///etc/passwd", 10).await, Err(FetchError::UnsupportedScheme(_))));
+    assert!(matches!(fetch("not a url", 10).await, Err(FetchError::InvalidUrl(_))));
+  }
+}
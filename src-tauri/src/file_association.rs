@@ -0,0 +1,249 @@
+// 模块：运行时注册 .cfj/.cfjz 文件关联（Windows 便携版，写入当前用户的 HKCU，无需管理员权限）
+use serde::Serialize;
+
+#[cfg(windows)]
+const PROG_ID: &str = "CoverFlow.Project";
+#[cfg(windows)]
+const PROG_NAME: &str = "CoverFlow Project";
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssociationStatus {
+  // 非 Windows 平台由安装包或系统负责关联
+  pub supported: bool,
+  // 扩展名当前关联的打开命令，未关联时为 None
+  pub command: Option<String>,
+  // 关联指向当前运行的程序
+  pub points_to_current: bool,
+}
+
+// 路径整体加引号，带空格的安装目录也能正确传参
+#[cfg(windows)]
+pub fn open_command(exe: &str) -> String {
+  format!("\"{}\" \"%1\"", exe)
+}
+
+#[cfg(windows)]
+mod registry {
+  use std::ptr::null;
+  use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS, WIN32_ERROR};
+  use windows_sys::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegDeleteKeyValueW, RegDeleteTreeW, RegGetValueW, RegSetValueExW, HKEY,
+    HKEY_CURRENT_USER, KEY_WRITE, REG_OPTION_NON_VOLATILE, REG_SZ, RRF_RT_REG_SZ,
+  };
+  use windows_sys::Win32::UI::Shell::{SHChangeNotify, SHCNE_ASSOCCHANGED, SHCNF_IDLIST};
+
+  fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+  }
+
+  fn check(status: WIN32_ERROR, what: &str) -> Result<(), String> {
+    if status == ERROR_SUCCESS {
+      Ok(())
+    } else {
+      Err(format!("{} failed: {}", what, std::io::Error::from_raw_os_error(status as i32)))
+    }
+  }
+
+  // 在 HKCU\Software\Classes 下写入字符串值，name 为空时写默认值
+  pub fn set_string(subkey: &str, name: &str, value: &str) -> Result<(), String> {
+    let path = wide(&format!("Software\\Classes\\{}", subkey));
+    let mut key: HKEY = std::ptr::null_mut();
+    // SAFETY: 参数均为以 0 结尾的 UTF-16 字符串，key 仅在创建成功后使用并关闭
+    unsafe {
+      check(
+        RegCreateKeyExW(
+          HKEY_CURRENT_USER,
+          path.as_ptr(),
+          0,
+          null(),
+          REG_OPTION_NON_VOLATILE,
+          KEY_WRITE,
+          null(),
+          &mut key,
+          std::ptr::null_mut(),
+        ),
+        "create registry key",
+      )?;
+      let name = wide(name);
+      let data = wide(value);
+      let status = RegSetValueExW(
+        key,
+        if name.len() > 1 { name.as_ptr() } else { null() },
+        0,
+        REG_SZ,
+        data.as_ptr().cast(),
+        (data.len() * 2) as u32,
+      );
+      RegCloseKey(key);
+      check(status, "write registry value")
+    }
+  }
+
+  pub fn get_string(subkey: &str, name: &str) -> Option<String> {
+    let path = wide(&format!("Software\\Classes\\{}", subkey));
+    let name = wide(name);
+    let name_ptr = if name.len() > 1 { name.as_ptr() } else { null() };
+    let mut size: u32 = 0;
+    // SAFETY: 先取长度再按长度分配缓冲区
+    unsafe {
+      let status = RegGetValueW(
+        HKEY_CURRENT_USER,
+        path.as_ptr(),
+        name_ptr,
+        RRF_RT_REG_SZ,
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        &mut size,
+      );
+      if status != ERROR_SUCCESS || size == 0 {
+        return None;
+      }
+      let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+      let status = RegGetValueW(
+        HKEY_CURRENT_USER,
+        path.as_ptr(),
+        name_ptr,
+        RRF_RT_REG_SZ,
+        std::ptr::null_mut(),
+        buffer.as_mut_ptr().cast(),
+        &mut size,
+      );
+      if status != ERROR_SUCCESS {
+        return None;
+      }
+      let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+      Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+  }
+
+  // 不存在视为成功
+  pub fn delete_tree(subkey: &str) -> Result<(), String> {
+    let path = wide(&format!("Software\\Classes\\{}", subkey));
+    // SAFETY: path 为以 0 结尾的 UTF-16 字符串
+    let status = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, path.as_ptr()) };
+    if status == ERROR_FILE_NOT_FOUND {
+      return Ok(());
+    }
+    check(status, "delete registry key")
+  }
+
+  pub fn delete_value(subkey: &str, name: &str) -> Result<(), String> {
+    let path = wide(&format!("Software\\Classes\\{}", subkey));
+    let name = wide(name);
+    // SAFETY: 两个参数均为以 0 结尾的 UTF-16 字符串
+    let status = unsafe {
+      RegDeleteKeyValueW(
+        HKEY_CURRENT_USER,
+        path.as_ptr(),
+        if name.len() > 1 { name.as_ptr() } else { null() },
+      )
+    };
+    if status == ERROR_FILE_NOT_FOUND {
+      return Ok(());
+    }
+    check(status, "delete registry value")
+  }
+
+  // 通知资源管理器刷新图标与默认程序
+  pub fn notify_shell() {
+    // SAFETY: SHCNE_ASSOCCHANGED 不使用两个条目参数
+    unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED as i32, SHCNF_IDLIST, null(), null()) };
+  }
+}
+
+#[cfg(windows)]
+fn current_exe() -> Result<String, String> {
+  let exe = std::env::current_exe()
+    .and_then(std::fs::canonicalize)
+    .map_err(|err| err.to_string())?;
+  let exe = exe.to_string_lossy();
+  // canonicalize 在 Windows 上返回 \\?\ 前缀，资源管理器不认
+  Ok(exe.strip_prefix(r"\\?\").unwrap_or(&exe).to_string())
+}
+
+#[cfg(windows)]
+fn extension_key(extension: &str) -> String {
+  format!(".{}", extension)
+}
+
+#[cfg(windows)]
+pub fn query() -> AssociationStatus {
+  let command = registry::get_string(&extension_key(crate::cfj::EXTENSIONS[0]), "")
+    .filter(|prog_id| prog_id == PROG_ID)
+    .and_then(|_| registry::get_string(&format!("{}\\shell\\open\\command", PROG_ID), ""));
+  let points_to_current = match (&command, current_exe()) {
+    (Some(command), Ok(exe)) => command.eq_ignore_ascii_case(&open_command(&exe)),
+    _ => false,
+  };
+  AssociationStatus {
+    supported: true,
+    command,
+    points_to_current,
+  }
+}
+
+#[cfg(not(windows))]
+pub fn query() -> AssociationStatus {
+  AssociationStatus::default()
+}
+
+#[cfg(windows)]
+pub fn register() -> Result<(), String> {
+  let exe = current_exe()?;
+  registry::set_string(PROG_ID, "", PROG_NAME)?;
+  registry::set_string(&format!("{}\\DefaultIcon", PROG_ID), "", &format!("\"{}\",0", exe))?;
+  registry::set_string(&format!("{}\\shell\\open\\command", PROG_ID), "", &open_command(&exe))?;
+  for extension in crate::cfj::EXTENSIONS {
+    let key = extension_key(extension);
+    registry::set_string(&key, "", PROG_ID)?;
+    registry::set_string(&format!("{}\\OpenWithProgids", key), PROG_ID, "")?;
+  }
+  registry::notify_shell();
+  log::info!("registered file association: {}", exe);
+  Ok(())
+}
+
+// 只移除本程序写入的内容，扩展名已改关联到其他程序时保留
+#[cfg(windows)]
+pub fn unregister() -> Result<(), String> {
+  for extension in crate::cfj::EXTENSIONS {
+    let key = extension_key(extension);
+    if registry::get_string(&key, "").as_deref() == Some(PROG_ID) {
+      registry::delete_value(&key, "")?;
+    }
+    registry::delete_value(&format!("{}\\OpenWithProgids", key), PROG_ID)?;
+  }
+  registry::delete_tree(PROG_ID)?;
+  registry::notify_shell();
+  log::info!("unregistered file association");
+  Ok(())
+}
+
+#[cfg(not(windows))]
+const UNSUPPORTED: &str = "file associations are only registered at runtime on Windows; they are set up by the installer on this platform";
+
+#[cfg(not(windows))]
+pub fn register() -> Result<(), String> {
+  Err(UNSUPPORTED.to_string())
+}
+
+#[cfg(not(windows))]
+pub fn unregister() -> Result<(), String> {
+  Err(UNSUPPORTED.to_string())
+}
+
+#[tauri::command]
+pub fn register_file_association() -> Result<(), String> {
+  register()
+}
+
+#[tauri::command]
+pub fn unregister_file_association() -> Result<(), String> {
+  unregister()
+}
+
+#[tauri::command]
+pub fn query_file_association() -> AssociationStatus {
+  query()
+}
@@ -0,0 +1,180 @@
+// 模块：系统字体（导出渲染与前端字体选择共用同一份字体库）
+use resvg::usvg::fontdb;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+// 系统字体加载较慢，加载一次后缓存，refresh 时重建
+static SYSTEM_FONTS: RwLock<Option<Arc<fontdb::Database>>> = RwLock::new(None);
+static FAMILIES: Mutex<Option<Vec<FontFamily>>> = Mutex::new(None);
+
+const SANS_SERIF_CANDIDATES: [&str; 6] = ["Inter", "Arial", "Helvetica", "Noto Sans", "DejaVu Sans", "Liberation Sans"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FontStyle {
+  Regular,
+  Bold,
+  Italic,
+  BoldItalic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontFamily {
+  pub family: String,
+  pub styles: Vec<FontStyle>,
+  // 实际安装的字重，升序
+  pub weights: Vec<u16>,
+}
+
+fn style_of(face: &fontdb::FaceInfo) -> FontStyle {
+  let bold = face.weight.0 >= fontdb::Weight::SEMIBOLD.0;
+  let italic = face.style != fontdb::Style::Normal;
+  match (bold, italic) {
+    (false, false) => FontStyle::Regular,
+    (true, false) => FontStyle::Bold,
+    (false, true) => FontStyle::Italic,
+    (true, true) => FontStyle::BoldItalic,
+  }
+}
+
+// 读不到数据的字体（权限、已删除的文件）直接移除，避免列出后导出时悄悄回退
+fn build() -> fontdb::Database {
+  let mut db = fontdb::Database::new();
+  db.load_system_fonts();
+  let unreadable: Vec<fontdb::ID> = db
+    .faces()
+    .map(|face| face.id)
+    .filter(|id| db.with_face_data(*id, |data, _| !data.is_empty()) != Some(true))
+    .collect();
+  if !unreadable.is_empty() {
+    log::warn!("skipped {} unreadable font face(s)", unreadable.len());
+  }
+  for id in unreadable {
+    db.remove_face(id);
+  }
+  let sans = SANS_SERIF_CANDIDATES
+    .into_iter()
+    .find(|family| db.faces().any(|face| face.families.iter().any(|(name, _)| name == family)));
+  if let Some(family) = sans {
+    db.set_sans_serif_family(family);
+  }
+  db
+}
+
+pub fn database() -> Arc<fontdb::Database> {
+  if let Some(db) = SYSTEM_FONTS.read().ok().and_then(|db| db.clone()) {
+    return db;
+  }
+  let mut slot = match SYSTEM_FONTS.write() {
+    Ok(slot) => slot,
+    Err(_) => return Arc::new(build()),
+  };
+  slot.get_or_insert_with(|| Arc::new(build())).clone()
+}
+
+// 重新扫描系统字体（用户安装新字体后）
+pub fn reload() -> Arc<fontdb::Database> {
+  let db = Arc::new(build());
+  if let Ok(mut slot) = SYSTEM_FONTS.write() {
+    *slot = Some(db.clone());
+  }
+  if let Ok(mut families) = FAMILIES.lock() {
+    *families = None;
+  }
+  db
+}
+
+// 按字族名去重（忽略大小写），每个字族取第一个（英文）名称
+pub fn families(db: &fontdb::Database) -> Vec<FontFamily> {
+  let mut grouped: BTreeMap<String, FontFamily> = BTreeMap::new();
+  for face in db.faces() {
+    let Some((name, _)) = face.families.first() else {
+      continue;
+    };
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') {
+      continue;
+    }
+    let entry = grouped.entry(name.to_lowercase()).or_insert_with(|| FontFamily {
+      family: name.to_string(),
+      styles: Vec::new(),
+      weights: Vec::new(),
+    });
+    let style = style_of(face);
+    if !entry.styles.contains(&style) {
+      entry.styles.push(style);
+    }
+    if !entry.weights.contains(&face.weight.0) {
+      entry.weights.push(face.weight.0);
+    }
+  }
+  grouped
+    .into_values()
+    .map(|mut family| {
+      family.styles.sort();
+      family.weights.sort();
+      family
+    })
+    .collect()
+}
+
+#[tauri::command]
+pub async fn list_system_fonts(refresh: Option<bool>) -> Vec<FontFamily> {
+  let refresh = refresh.unwrap_or(false);
+  if !refresh {
+    if let Some(cached) = FAMILIES.lock().ok().and_then(|families| families.clone()) {
+      return cached;
+    }
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let db = if refresh { reload() } else { database() };
+    let list = families(&db);
+    log::info!("list_system_fonts: {} families", list.len());
+    if let Ok(mut families) = FAMILIES.lock() {
+      *families = Some(list.clone());
+    }
+    list
+  })
+  .await
+  .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TUFFY: &[u8] = include_bytes!("../tests/fixtures/fonts/Tuffy.ttf");
+
+  #[test]
+  fn duplicate_faces_collapse_into_one_family() {
+    let mut db = fontdb::Database::new();
+    db.load_font_data(TUFFY.to_vec());
+    db.load_font_data(TUFFY.to_vec());
+    assert_eq!(
+      families(&db),
+      vec![FontFamily {
+        family: "Tuffy".to_string(),
+        styles: vec![FontStyle::Regular],
+        weights: vec![500],
+      }]
+    );
+  }
+
+  #[test]
+  fn default_system_fonts_appear() {
+    let list = families(&database());
+    let names: Vec<String> = list.iter().map(|family| family.family.to_lowercase()).collect();
+    // 按名称排序且不重复
+    assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(list.iter().all(|family| !family.styles.is_empty() && !family.weights.is_empty()));
+    #[cfg(target_os = "windows")]
+    assert!(names.iter().any(|name| name == "arial"));
+    #[cfg(target_os = "macos")]
+    assert!(names.iter().any(|name| name == "helvetica"));
+    // Linux 的默认字体随发行版而定，只要求装了字体时能列出来
+    #[cfg(target_os = "linux")]
+    assert_eq!(list.is_empty(), database().is_empty());
+  }
+}
@@ -0,0 +1,312 @@
+// 模块：撤销 / 重做快照（按文档保存，超过条数或总字节数时丢弃最早的记录）
+// 内嵌图片等大字符串在快照间共享，只存一份，相邻快照通常只有少量差异
+use crate::cfj::{self, CfjProject};
+use crate::limits;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const MAX_ENTRIES: usize = 100;
+const MAX_BYTES: usize = 256 * 1024 * 1024;
+// 超过该长度的字符串单独存放并在快照间共享
+const SHARED_MIN_LEN: usize = 4096;
+// 快照中指向共享字符串的占位前缀，正常内容不会以 NUL 开头
+const SHARED_MARKER: &str = "\u{0}shared:";
+
+struct Snapshot {
+  hash: String,
+  json: String,
+  shared: Vec<String>,
+}
+
+struct SharedText {
+  text: Arc<str>,
+  refs: usize,
+}
+
+pub struct History {
+  entries: VecDeque<Snapshot>,
+  // 当前状态在 entries 中的位置
+  cursor: usize,
+  shared: HashMap<String, SharedText>,
+  bytes: usize,
+  max_entries: usize,
+  max_bytes: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatus {
+  pub can_undo: bool,
+  pub can_redo: bool,
+  pub entries: usize,
+  pub bytes: usize,
+}
+
+impl Default for History {
+  fn default() -> Self {
+    History::with_limits(MAX_ENTRIES, MAX_BYTES)
+  }
+}
+
+impl History {
+  pub fn with_limits(max_entries: usize, max_bytes: usize) -> Self {
+    History {
+      entries: VecDeque::new(),
+      cursor: 0,
+      shared: HashMap::new(),
+      bytes: 0,
+      max_entries: max_entries.max(1),
+      max_bytes,
+    }
+  }
+
+  fn share(&mut self, value: &mut Value, keys: &mut Vec<String>) {
+    match value {
+      Value::String(text) if text.len() >= SHARED_MIN_LEN => {
+        let key = blake3::hash(text.as_bytes()).to_hex().to_string();
+        match self.shared.get_mut(&key) {
+          Some(entry) => entry.refs += 1,
+          None => {
+            self.bytes += text.len();
+            let text = Arc::from(std::mem::take(text).as_str());
+            self.shared.insert(key.clone(), SharedText { text, refs: 1 });
+          }
+        }
+        *value = Value::String(format!("{}{}", SHARED_MARKER, key));
+        keys.push(key);
+      }
+      Value::Array(items) => items.iter_mut().for_each(|item| self.share(item, keys)),
+      Value::Object(map) => map.values_mut().for_each(|item| self.share(item, keys)),
+      _ => {}
+    }
+  }
+
+  fn unshare(&self, value: &mut Value) {
+    match value {
+      Value::String(text) => {
+        if let Some(entry) = text.strip_prefix(SHARED_MARKER).and_then(|key| self.shared.get(key)) {
+          *text = entry.text.to_string();
+        }
+      }
+      Value::Array(items) => items.iter_mut().for_each(|item| self.unshare(item)),
+      Value::Object(map) => map.values_mut().for_each(|item| self.unshare(item)),
+      _ => {}
+    }
+  }
+
+  fn release(&mut self, snapshot: Snapshot) {
+    self.bytes -= snapshot.json.len();
+    for key in snapshot.shared {
+      if let Some(entry) = self.shared.get_mut(&key) {
+        entry.refs -= 1;
+        if entry.refs == 0 {
+          self.bytes -= entry.text.len();
+          self.shared.remove(&key);
+        }
+      }
+    }
+  }
+
+  fn restore(&self, snapshot: &Snapshot) -> Option<CfjProject> {
+    let mut value: Value = serde_json::from_str(&snapshot.json).ok()?;
+    self.unshare(&mut value);
+    serde_json::from_value(value).ok()
+  }
+
+  // 与当前状态内容相同（仅选中图层或时间戳不同）时不记录
+  pub fn push(&mut self, project: &CfjProject) -> Result<(), String> {
+    let hash = cfj::content_hash(project);
+    if self.entries.get(self.cursor).is_some_and(|current| current.hash == hash) {
+      return Ok(());
+    }
+    let mut value = serde_json::to_value(project).map_err(|err| err.to_string())?;
+    // 撤销后再记录新状态，丢弃可重做的部分
+    while self.entries.len() > self.cursor + 1 {
+      if let Some(snapshot) = self.entries.pop_back() {
+        self.release(snapshot);
+      }
+    }
+    let mut keys = Vec::new();
+    self.share(&mut value, &mut keys);
+    let json = serde_json::to_string(&value).map_err(|err| err.to_string())?;
+    self.bytes += json.len();
+    self.entries.push_back(Snapshot { hash, json, shared: keys });
+    self.cursor = self.entries.len() - 1;
+    // 至少保留当前状态
+    while self.entries.len() > 1 && (self.entries.len() > self.max_entries || self.bytes > self.max_bytes) {
+      if let Some(snapshot) = self.entries.pop_front() {
+        self.release(snapshot);
+        self.cursor -= 1;
+      }
+    }
+    Ok(())
+  }
+
+  pub fn undo(&mut self) -> Option<CfjProject> {
+    if self.cursor == 0 {
+      return None;
+    }
+    self.cursor -= 1;
+    self.restore(&self.entries[self.cursor])
+  }
+
+  pub fn redo(&mut self) -> Option<CfjProject> {
+    if self.cursor + 1 >= self.entries.len() {
+      return None;
+    }
+    self.cursor += 1;
+    self.restore(&self.entries[self.cursor])
+  }
+
+  pub fn status(&self) -> HistoryStatus {
+    HistoryStatus {
+      can_undo: self.cursor > 0,
+      can_redo: self.cursor + 1 < self.entries.len(),
+      entries: self.entries.len(),
+      bytes: self.bytes,
+    }
+  }
+}
+
+// 以项目 id 区分文档
+#[derive(Default)]
+pub struct ProjectHistory(Mutex<HashMap<String, History>>);
+
+impl ProjectHistory {
+  // 没有记录过的文档返回 None
+  fn with<T>(&self, id: &str, f: impl FnOnce(&mut History) -> T) -> Option<T> {
+    self.0.lock().ok()?.get_mut(id).map(f)
+  }
+}
+
+#[tauri::command]
+pub fn history_push(app: AppHandle, project: CfjProject) -> Result<HistoryStatus, String> {
+  limits::check(&app, "history_push", std::slice::from_ref(&project))?;
+  let state = app.state::<ProjectHistory>();
+  let mut documents = state.0.lock().map_err(|err| err.to_string())?;
+  let history = documents.entry(project.id.clone()).or_default();
+  history.push(&project)?;
+  Ok(history.status())
+}
+
+#[tauri::command]
+pub fn history_undo(app: AppHandle, id: String) -> Option<CfjProject> {
+  app.state::<ProjectHistory>().with(&id, History::undo).flatten()
+}
+
+#[tauri::command]
+pub fn history_redo(app: AppHandle, id: String) -> Option<CfjProject> {
+  app.state::<ProjectHistory>().with(&id, History::redo).flatten()
+}
+
+#[tauri::command]
+pub fn history_status(app: AppHandle, id: String) -> HistoryStatus {
+  app.state::<ProjectHistory>().with(&id, |history| history.status()).unwrap_or_default()
+}
+
+// 关闭文档时释放其历史
+#[tauri::command]
+pub fn history_clear(app: AppHandle, id: String) {
+  if let Ok(mut documents) = app.state::<ProjectHistory>().0.lock() {
+    documents.remove(&id);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn version(index: usize) -> CfjProject {
+    let mut project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.title = format!("v{}", index);
+    project
+  }
+
+  fn title(project: Option<CfjProject>) -> Option<String> {
+    project.map(|project| project.title)
+  }
+
+  #[test]
+  fn undo_and_redo_walk_in_order() {
+    let mut history = History::default();
+    for index in 0..3 {
+      history.push(&version(index)).unwrap();
+    }
+    assert_eq!(title(history.undo()), Some("v1".to_string()));
+    assert_eq!(title(history.undo()), Some("v0".to_string()));
+    assert_eq!(history.undo(), None);
+    assert_eq!(title(history.redo()), Some("v1".to_string()));
+    assert_eq!(title(history.redo()), Some("v2".to_string()));
+    assert_eq!(history.redo(), None);
+
+    // 撤销后记录新状态，原来可重做的部分被丢弃
+    history.undo();
+    history.push(&version(9)).unwrap();
+    assert_eq!(history.redo(), None);
+    assert_eq!(title(history.undo()), Some("v1".to_string()));
+    assert_eq!(history.status().entries, 3);
+  }
+
+  #[test]
+  fn unchanged_content_is_not_recorded() {
+    let mut history = History::default();
+    let mut project = version(0);
+    history.push(&project).unwrap();
+    project.selected_layer_id = Some("other".to_string());
+    project.updated_at += 1000.0;
+    history.push(&project).unwrap();
+    assert_eq!(history.status().entries, 1);
+    assert!(!history.status().can_undo);
+  }
+
+  #[test]
+  fn evicts_oldest_at_entry_limit() {
+    let mut history = History::with_limits(3, MAX_BYTES);
+    for index in 0..3 {
+      history.push(&version(index)).unwrap();
+    }
+    // 刚好达到上限时全部保留
+    assert_eq!(history.status().entries, 3);
+    history.push(&version(3)).unwrap();
+    assert_eq!(history.status().entries, 3);
+    assert_eq!(title(history.undo()), Some("v2".to_string()));
+    assert_eq!(title(history.undo()), Some("v1".to_string()));
+    assert_eq!(history.undo(), None);
+  }
+
+  #[test]
+  fn evicts_by_bytes_but_keeps_current() {
+    let mut single = History::default();
+    single.push(&version(0)).unwrap();
+    let one = single.status().bytes;
+
+    let mut history = History::with_limits(MAX_ENTRIES, one * 2);
+    for index in 0..3 {
+      history.push(&version(index)).unwrap();
+    }
+    assert_eq!(history.status().entries, 2);
+    assert!(history.status().bytes <= one * 2);
+    // 上限小于一份快照时仍保留当前状态
+    let mut tiny = History::with_limits(MAX_ENTRIES, 1);
+    tiny.push(&version(0)).unwrap();
+    tiny.push(&version(1)).unwrap();
+    assert_eq!(tiny.status().entries, 1);
+  }
+
+  #[test]
+  fn large_strings_are_stored_once() {
+    let image = format!("data:image/png;base64,{}", "A".repeat(SHARED_MIN_LEN * 4));
+    let mut history = History::default();
+    let mut project = version(0);
+    project.layers[0].content = image.clone();
+    history.push(&project).unwrap();
+    let first = history.status().bytes;
+    project.title = "v1".to_string();
+    history.push(&project).unwrap();
+    assert!(history.status().bytes - first < image.len());
+    assert_eq!(history.undo().unwrap().layers[0].content, image);
+  }
+}
@@ -0,0 +1,208 @@
+// 模块：在导出文件中嵌入 sRGB ICC 配置文件
+// 渲染结果本身就是 sRGB，嵌入的配置文件只是把这一点写明，不改变像素
+use crate::export::ExportFormat;
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use std::io::Write;
+use std::sync::OnceLock;
+
+static SRGB_PROFILE: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+const JPEG_ICC_MARKER: &[u8] = b"ICC_PROFILE\0";
+// 单个 APP2 段最多容纳的配置文件字节数（65535 - 长度字段 - 标识 - 序号）
+const JPEG_ICC_CHUNK: usize = 65535 - 2 - 12 - 2;
+const WEBP_ICC_FLAG: u8 = 0x20;
+
+pub fn srgb_profile() -> Option<&'static [u8]> {
+  SRGB_PROFILE
+    .get_or_init(|| moxcms::ColorProfile::new_srgb().encode().ok())
+    .as_deref()
+}
+
+fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+  let mut crc = Crc::new();
+  crc.update(kind);
+  crc.update(data);
+  let mut chunk = Vec::with_capacity(data.len() + 12);
+  chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  chunk.extend_from_slice(kind);
+  chunk.extend_from_slice(data);
+  chunk.extend_from_slice(&crc.sum().to_be_bytes());
+  chunk
+}
+
+// iCCP 紧跟在 IHDR 之后；已有 iCCP/sRGB 时不重复写入
+fn embed_png(png: &[u8], profile: &[u8]) -> Option<Vec<u8>> {
+  if !png.starts_with(&PNG_SIGNATURE) {
+    return None;
+  }
+  let mut offset = PNG_SIGNATURE.len();
+  let mut ihdr_end = None;
+  while offset + 8 <= png.len() {
+    let length = u32::from_be_bytes(png[offset..offset + 4].try_into().ok()?) as usize;
+    let kind = &png[offset + 4..offset + 8];
+    if kind == b"iCCP" || kind == b"sRGB" {
+      return Some(png.to_vec());
+    }
+    let end = offset.checked_add(length)?.checked_add(12)?;
+    if kind == b"IHDR" {
+      ihdr_end = Some(end);
+    }
+    if kind == b"IDAT" || kind == b"IEND" {
+      break;
+    }
+    offset = end;
+  }
+  let ihdr_end = ihdr_end.filter(|end| *end <= png.len())?;
+  let mut data = b"sRGB\0\0".to_vec();
+  let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(profile).ok()?;
+  data.extend_from_slice(&encoder.finish().ok()?);
+  let mut out = Vec::with_capacity(png.len() + data.len() + 12);
+  out.extend_from_slice(&png[..ihdr_end]);
+  out.extend_from_slice(&png_chunk(b"iCCP", &data));
+  out.extend_from_slice(&png[ihdr_end..]);
+  Some(out)
+}
+
+// APP2 段放在 SOI 与 JFIF APP0 之后，按 64KB 分段
+fn embed_jpeg(jpeg: &[u8], profile: &[u8]) -> Option<Vec<u8>> {
+  if jpeg.get(..2) != Some(&[0xff, 0xd8]) {
+    return None;
+  }
+  let mut insert_at = 2;
+  if jpeg.get(2..4) == Some(&[0xff, 0xe0]) {
+    let length = u16::from_be_bytes(jpeg.get(4..6)?.try_into().ok()?) as usize;
+    insert_at = 4 + length;
+  }
+  if insert_at > jpeg.len() {
+    return None;
+  }
+  let chunks: Vec<&[u8]> = profile.chunks(JPEG_ICC_CHUNK).collect();
+  let count = u8::try_from(chunks.len()).ok()?;
+  let mut out = Vec::with_capacity(jpeg.len() + profile.len() + chunks.len() * 18);
+  out.extend_from_slice(&jpeg[..insert_at]);
+  for (index, chunk) in chunks.iter().enumerate() {
+    let length = (2 + JPEG_ICC_MARKER.len() + 2 + chunk.len()) as u16;
+    out.extend_from_slice(&[0xff, 0xe2]);
+    out.extend_from_slice(&length.to_be_bytes());
+    out.extend_from_slice(JPEG_ICC_MARKER);
+    out.extend_from_slice(&[index as u8 + 1, count]);
+    out.extend_from_slice(chunk);
+  }
+  out.extend_from_slice(&jpeg[insert_at..]);
+  Some(out)
+}
+
+fn riff_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+  let mut chunk = Vec::with_capacity(data.len() + 9);
+  chunk.extend_from_slice(kind);
+  chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+  chunk.extend_from_slice(data);
+  if data.len() % 2 == 1 {
+    chunk.push(0);
+  }
+  chunk
+}
+
+fn u24_le(value: u32) -> [u8; 3] {
+  let bytes = value.to_le_bytes();
+  [bytes[0], bytes[1], bytes[2]]
+}
+
+// 简单格式（VP8/VP8L）需改为扩展格式 VP8X 才能携带 ICCP
+fn embed_webp(webp: &[u8], profile: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+  if webp.len() < 20 || &webp[..4] != b"RIFF" || &webp[8..12] != b"WEBP" {
+    return None;
+  }
+  let body = &webp[12..];
+  let first = &body[..4];
+  let mut payload = Vec::with_capacity(body.len() + profile.len() + 32);
+  match first {
+    b"VP8X" => {
+      let mut extended = body.to_vec();
+      if extended[8] & WEBP_ICC_FLAG != 0 {
+        return Some(webp.to_vec());
+      }
+      extended[8] |= WEBP_ICC_FLAG;
+      // VP8X 块固定 18 字节，ICCP 必须紧随其后
+      payload.extend_from_slice(&extended[..18]);
+      payload.extend_from_slice(&riff_chunk(b"ICCP", profile));
+      payload.extend_from_slice(&extended[18..]);
+    }
+    b"VP8 " | b"VP8L" => {
+      let mut header = vec![WEBP_ICC_FLAG, 0, 0, 0];
+      if first == b"VP8L" {
+        // VP8L 可能包含透明度，按有 alpha 标记
+        header[0] |= 0x10;
+      }
+      header.extend_from_slice(&u24_le(width.checked_sub(1)?));
+      header.extend_from_slice(&u24_le(height.checked_sub(1)?));
+      payload.extend_from_slice(&riff_chunk(b"VP8X", &header));
+      payload.extend_from_slice(&riff_chunk(b"ICCP", profile));
+      payload.extend_from_slice(body);
+    }
+    _ => return None,
+  }
+  let mut out = Vec::with_capacity(payload.len() + 12);
+  out.extend_from_slice(b"RIFF");
+  out.extend_from_slice(&((payload.len() + 4) as u32).to_le_bytes());
+  out.extend_from_slice(b"WEBP");
+  out.extend_from_slice(&payload);
+  Some(out)
+}
+
+// 数据无法解析或格式不支持时返回 None
+pub fn embed_srgb(bytes: &[u8], format: ExportFormat, width: u32, height: u32) -> Option<Vec<u8>> {
+  let profile = srgb_profile()?;
+  match format {
+    ExportFormat::Png => embed_png(bytes, profile),
+    ExportFormat::Jpeg => embed_jpeg(bytes, profile),
+    ExportFormat::Webp => embed_webp(bytes, profile, width, height),
+    ExportFormat::Avif => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+  use crate::export::{self, EncodeOptions};
+
+  fn has_profile(bytes: &[u8], format: ExportFormat) -> bool {
+    let marker: &[u8] = match format {
+      ExportFormat::Png => b"iCCP",
+      ExportFormat::Jpeg => JPEG_ICC_MARKER,
+      ExportFormat::Webp => b"ICCP",
+      ExportFormat::Avif => return false,
+    };
+    bytes.windows(marker.len()).any(|window| window == marker)
+  }
+
+  #[test]
+  fn profile_follows_embed_option() {
+    let project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    for format in [ExportFormat::Png, ExportFormat::Jpeg, ExportFormat::Webp] {
+      for embed_srgb in [true, false] {
+        let options = EncodeOptions { embed_srgb, ..EncodeOptions::default() };
+        let path = dir.path().join(format!("cover-{}.{:?}", embed_srgb, format));
+        export::export_with(&project, format, &options, 0.5, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(has_profile(&bytes, format), embed_srgb, "{:?} embed_srgb={}", format, embed_srgb);
+        // 嵌入后仍能正常解码
+        assert!(image::load_from_memory(&bytes).is_ok(), "{:?}", format);
+      }
+    }
+  }
+
+  #[test]
+  fn existing_profile_is_not_duplicated() {
+    let pixmap = resvg::tiny_skia::Pixmap::new(4, 4).unwrap();
+    let png = export::encode(&pixmap, ExportFormat::Png).unwrap();
+    let tagged = embed_srgb(&png, ExportFormat::Png, 4, 4).unwrap();
+    assert_eq!(embed_srgb(&tagged, ExportFormat::Png, 4, 4).unwrap(), tagged);
+    assert!(embed_srgb(b"junk", ExportFormat::Png, 4, 4).is_none());
+  }
+}
@@ -0,0 +1,345 @@
+// 模块：读取图片的尺寸、格式与分辨率，只解析文件头，不解码像素
+// 前端据此在导出前提示分辨率过低的图片，无需把整个文件读进 JS
+use crate::{fetch, scope};
+use image::{ImageDecoder, ImageFormat, ImageReader};
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use tauri::AppHandle;
+
+// EXIF 段过大时不再解析分辨率
+const MAX_EXIF_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug)]
+pub enum ImageInfoError {
+  Read(String),
+  Unsupported,
+  Corrupt(String),
+}
+
+impl fmt::Display for ImageInfoError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ImageInfoError::Read(detail) => write!(f, "cannot read image: {}", detail),
+      ImageInfoError::Unsupported => write!(f, "unsupported image format (expected PNG, JPEG, WebP or GIF)"),
+      ImageInfoError::Corrupt(detail) => write!(f, "corrupt or truncated image: {}", detail),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Dpi {
+  pub x: f64,
+  pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageInfo {
+  // png / jpeg / webp / gif
+  pub format: String,
+  pub mime: String,
+  pub width: u32,
+  pub height: u32,
+  // 解码后的像素格式，如 rgb8、rgba8、l16
+  pub color_type: String,
+  pub has_alpha: bool,
+  // 文件未记录分辨率时为空（GIF 没有该字段）
+  pub dpi: Option<Dpi>,
+  pub bytes: u64,
+}
+
+fn format_name(format: ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Png => "png",
+    ImageFormat::Jpeg => "jpeg",
+    ImageFormat::WebP => "webp",
+    ImageFormat::Gif => "gif",
+    _ => "unknown",
+  }
+}
+
+// 保留两位小数，72 dpi 的 PNG 记为 2835 像素/米，换算后约为 72.01
+fn dpi(x: f64, y: f64) -> Option<Dpi> {
+  let round = |value: f64| (value * 100.0).round() / 100.0;
+  (x.is_finite() && y.is_finite() && x > 0.0 && y > 0.0).then(|| Dpi { x: round(x), y: round(y) })
+}
+
+// pHYs 位于 IDAT 之前，逐个跳过数据块即可
+fn png_dpi<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Dpi>> {
+  reader.seek(SeekFrom::Start(8))?;
+  loop {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    match &header[4..] {
+      b"pHYs" if length >= 9 => {
+        let mut data = [0u8; 9];
+        reader.read_exact(&mut data)?;
+        // 单位 0 只表示像素宽高比
+        if data[8] != 1 {
+          return Ok(None);
+        }
+        let x = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as f64;
+        let y = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as f64;
+        return Ok(dpi(x * 0.0254, y * 0.0254));
+      }
+      b"IDAT" | b"IEND" => return Ok(None),
+      // 数据之后还有 4 字节 CRC
+      _ => reader.seek(SeekFrom::Current(length as i64 + 4))?,
+    };
+  }
+}
+
+// TIFF 结构的 IFD0 中读取 XResolution / YResolution / ResolutionUnit
+fn exif_dpi(tiff: &[u8]) -> Option<Dpi> {
+  let big_endian = match tiff.get(..4)? {
+    b"MM\0*" => true,
+    b"II*\0" => false,
+    _ => return None,
+  };
+  let u16_at = |offset: usize| {
+    let bytes: [u8; 2] = tiff.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+  };
+  let u32_at = |offset: usize| {
+    let bytes: [u8; 4] = tiff.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+  };
+  let rational_at = |entry: usize| {
+    let offset = u32_at(entry + 8)? as usize;
+    let denominator = u32_at(offset + 4)?;
+    if denominator == 0 {
+      return None;
+    }
+    Some(u32_at(offset)? as f64 / denominator as f64)
+  };
+  let ifd = u32_at(4)? as usize;
+  let (mut x, mut y, mut unit) = (None, None, 2);
+  for index in 0..u16_at(ifd)? as usize {
+    let entry = ifd + 2 + index * 12;
+    match u16_at(entry)? {
+      0x011a => x = rational_at(entry),
+      0x011b => y = rational_at(entry),
+      0x0128 => unit = u16_at(entry + 8)?,
+      _ => {}
+    }
+  }
+  // 2 为英寸，3 为厘米，1 表示没有单位
+  let scale = match unit {
+    2 => 1.0,
+    3 => 2.54,
+    _ => return None,
+  };
+  dpi(x? * scale, y? * scale)
+}
+
+fn read_segment<R: Read>(reader: &mut R, length: u64) -> io::Result<Option<Vec<u8>>> {
+  if length > MAX_EXIF_BYTES {
+    return Ok(None);
+  }
+  let mut data = vec![0u8; length as usize];
+  reader.read_exact(&mut data)?;
+  Ok(Some(data))
+}
+
+// JFIF 记录了单位时以它为准，否则看 EXIF；扫描到图像数据（SOS）为止
+fn jpeg_dpi<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Dpi>> {
+  reader.seek(SeekFrom::Start(2))?;
+  let mut exif = None;
+  loop {
+    let mut marker = [0u8; 2];
+    reader.read_exact(&mut marker)?;
+    if marker[0] != 0xff {
+      break;
+    }
+    if marker[1] == 0xda || marker[1] == 0xd9 {
+      break;
+    }
+    let mut length = [0u8; 2];
+    reader.read_exact(&mut length)?;
+    let length = u16::from_be_bytes(length).saturating_sub(2) as u64;
+    match marker[1] {
+      0xe0 | 0xe1 => {
+        let Some(data) = read_segment(reader, length)? else {
+          reader.seek(SeekFrom::Current(length as i64))?;
+          continue;
+        };
+        if marker[1] == 0xe0 && data.starts_with(b"JFIF\0") && data.len() >= 12 && data[7] != 0 {
+          let x = u16::from_be_bytes([data[8], data[9]]) as f64;
+          let y = u16::from_be_bytes([data[10], data[11]]) as f64;
+          let scale = if data[7] == 2 { 2.54 } else { 1.0 };
+          return Ok(dpi(x * scale, y * scale));
+        }
+        if marker[1] == 0xe1 && exif.is_none() {
+          if let Some(tiff) = data.strip_prefix(b"Exif\0\0") {
+            exif = exif_dpi(tiff);
+          }
+        }
+      }
+      _ => {
+        reader.seek(SeekFrom::Current(length as i64))?;
+      }
+    }
+  }
+  Ok(exif)
+}
+
+// 只有扩展格式（VP8X）的 WebP 才可能带 EXIF 数据块
+fn webp_dpi<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Dpi>> {
+  reader.seek(SeekFrom::Start(12))?;
+  let mut header = [0u8; 8];
+  reader.read_exact(&mut header)?;
+  if &header[..4] != b"VP8X" {
+    return Ok(None);
+  }
+  loop {
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+    let padded = length + (length & 1);
+    if &header[..4] == b"EXIF" {
+      let Some(data) = read_segment(reader, length)? else {
+        return Ok(None);
+      };
+      let tiff = data.strip_prefix(b"Exif\0\0").unwrap_or(&data);
+      return Ok(exif_dpi(tiff));
+    }
+    reader.seek(SeekFrom::Current(padded as i64))?;
+    if reader.read_exact(&mut header).is_err() {
+      return Ok(None);
+    }
+  }
+}
+
+// 分辨率读取失败不影响尺寸等信息
+fn read_dpi<R: Read + Seek>(reader: &mut R, format: ImageFormat) -> Option<Dpi> {
+  let result = match format {
+    ImageFormat::Png => png_dpi(reader),
+    ImageFormat::Jpeg => jpeg_dpi(reader),
+    ImageFormat::WebP => webp_dpi(reader),
+    _ => Ok(None),
+  };
+  result.ok().flatten()
+}
+
+pub fn inspect(path: &Path) -> Result<ImageInfo, ImageInfoError> {
+  let read_error = |err: io::Error| ImageInfoError::Read(format!("{}: {}", path.display(), err));
+  let file = File::open(path).map_err(read_error)?;
+  let bytes = file.metadata().map_err(read_error)?.len();
+  let mut reader = BufReader::new(file);
+  let mut magic = Vec::with_capacity(12);
+  (&mut reader).take(12).read_to_end(&mut magic).map_err(read_error)?;
+  let format = fetch::sniff(&magic).ok_or(ImageInfoError::Unsupported)?;
+  reader.seek(SeekFrom::Start(0)).map_err(read_error)?;
+
+  let decoder = ImageReader::with_format(&mut reader, format)
+    .into_decoder()
+    .map_err(|err| ImageInfoError::Corrupt(err.to_string()))?;
+  let (width, height) = decoder.dimensions();
+  let color_type = decoder.color_type();
+  drop(decoder);
+  if width == 0 || height == 0 {
+    return Err(ImageInfoError::Corrupt(format!("invalid size {}x{}", width, height)));
+  }
+  Ok(ImageInfo {
+    format: format_name(format).to_string(),
+    mime: format.to_mime_type().to_string(),
+    width,
+    height,
+    color_type: format!("{:?}", color_type).to_ascii_lowercase(),
+    has_alpha: color_type.has_alpha(),
+    dpi: read_dpi(&mut reader, format),
+    bytes,
+  })
+}
+
+// 与前端 fs 插件一致，只允许读取授权范围内的文件
+#[tauri::command]
+pub fn image_info(app: AppHandle, path: String) -> Result<ImageInfo, String> {
+  let canonical = scope::check(&app, Path::new(&path))?;
+  inspect(&canonical).map_err(|err| {
+    log::warn!("image_info: {}: {}", path, err);
+    err.to_string()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/images").join(name)
+  }
+
+  #[test]
+  fn reads_each_format() {
+    let cases = [
+      ("300dpi.png", "png", "image/png", (3, 2), true, Some(Dpi { x: 300.0, y: 300.0 })),
+      ("72dpi.jpg", "jpeg", "image/jpeg", (4, 3), false, Some(Dpi { x: 72.0, y: 72.0 })),
+      ("plain.gif", "gif", "image/gif", (5, 4), true, None),
+      ("plain.webp", "webp", "image/webp", (6, 5), true, None),
+    ];
+    for (name, format, mime, size, has_alpha, dpi) in cases {
+      let info = inspect(&fixture(name)).unwrap();
+      assert_eq!(info.format, format, "{}", name);
+      assert_eq!(info.mime, mime, "{}", name);
+      assert_eq!((info.width, info.height), size, "{}", name);
+      assert_eq!(info.has_alpha, has_alpha, "{}", name);
+      assert_eq!(info.dpi, dpi, "{}", name);
+      assert_eq!(info.bytes, std::fs::metadata(fixture(name)).unwrap().len());
+    }
+  }
+
+  #[test]
+  fn truncated_and_foreign_files_are_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let png = std::fs::read(fixture("300dpi.png")).unwrap();
+    let truncated = dir.path().join("truncated.png");
+    std::fs::write(&truncated, &png[..20]).unwrap();
+    assert!(matches!(inspect(&truncated), Err(ImageInfoError::Corrupt(_))));
+    let text = dir.path().join("notes.png");
+    std::fs::write(&text, "not an image").unwrap();
+    assert!(matches!(inspect(&text), Err(ImageInfoError::Unsupported)));
+    assert!(matches!(inspect(&dir.path().join("missing.png")), Err(ImageInfoError::Read(_))));
+  }
+
+  #[test]
+  fn png_dpi_needs_metre_unit() {
+    let png = std::fs::read(fixture("300dpi.png")).unwrap();
+    // 72 dpi 记为 2835 像素/米
+    let with = |value: u32, unit: u8| {
+      let mut bytes = png.clone();
+      let offset = bytes.windows(4).position(|window| window == b"pHYs").unwrap() + 4;
+      bytes[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+      bytes[offset + 4..offset + 8].copy_from_slice(&value.to_be_bytes());
+      bytes[offset + 8] = unit;
+      png_dpi(&mut Cursor::new(bytes)).unwrap()
+    };
+    assert_eq!(with(2835, 1), Some(Dpi { x: 72.01, y: 72.01 }));
+    assert_eq!(with(2835, 0), None);
+    assert_eq!(with(0, 1), None);
+  }
+
+  #[test]
+  fn exif_resolution_per_centimetre() {
+    // IFD0 含 XResolution、YResolution（有理数，偏移 50/58）与 ResolutionUnit
+    let little: Vec<u8> = [
+      &b"II*\0"[..],
+      &8u32.to_le_bytes(),
+      &3u16.to_le_bytes(),
+      &[0x1a, 0x01, 5, 0], &1u32.to_le_bytes(), &50u32.to_le_bytes(),
+      &[0x1b, 0x01, 5, 0], &1u32.to_le_bytes(), &58u32.to_le_bytes(),
+      &[0x28, 0x01, 3, 0], &1u32.to_le_bytes(), &3u16.to_le_bytes(), &[0, 0],
+      &0u32.to_le_bytes(),
+      &118u32.to_le_bytes(), &1u32.to_le_bytes(),
+      &600u32.to_le_bytes(), &5u32.to_le_bytes(),
+    ]
+    .concat();
+    // 单位 3 为每厘米
+    assert_eq!(exif_dpi(&little), Some(Dpi { x: 299.72, y: 304.8 }));
+    assert_eq!(exif_dpi(&little[..40]), None);
+    assert_eq!(exif_dpi(b"XX*\0"), None);
+  }
+}
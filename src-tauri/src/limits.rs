@@ -0,0 +1,316 @@
+// 模块：经 IPC 传入的项目大小上限（封面数、图层数、内嵌数据总量、单个字段长度）
+// 反序列化已由 Tauri 完成，这里在渲染、写盘等耗时操作之前拒绝异常输入
+// 可在应用配置目录的 limits.json 中调整，缺省的字段取默认值，启动后首次使用时读取
+use crate::cfj::{BackgroundType, CfjProject, LayerType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+const LIMITS_FILE: &str = "limits.json";
+
+static LIMITS: OnceLock<Limits> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Limits {
+  pub max_covers: usize,
+  // 单个封面
+  pub max_layers: usize,
+  // 所有 data: 地址与内嵌字体的字符数之和，默认值容得下 embed_assets 上限的 base64 结果
+  pub max_embedded_bytes: usize,
+  // 其余字符串字段，extra 中的未知字段按其中字符串的总长计算
+  pub max_field_bytes: usize,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Limits {
+      max_covers: 500,
+      max_layers: 2000,
+      max_embedded_bytes: 384 * 1024 * 1024,
+      max_field_bytes: 1024 * 1024,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitError {
+  TooManyCovers { count: usize, max: usize },
+  TooManyLayers { cover: usize, count: usize, max: usize },
+  EmbeddedTooLarge { bytes: usize, max: usize },
+  FieldTooLong { field: String, bytes: usize, max: usize },
+}
+
+// 以 "limit exceeded (<配置项>)" 开头，前端可据此识别
+impl fmt::Display for LimitError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LimitError::TooManyCovers { count, max } => {
+        write!(f, "limit exceeded (maxCovers): {} covers, at most {}", count, max)
+      }
+      LimitError::TooManyLayers { cover, count, max } => {
+        write!(f, "limit exceeded (maxLayers): cover {} has {} layers, at most {}", cover, count, max)
+      }
+      LimitError::EmbeddedTooLarge { bytes, max } => {
+        write!(f, "limit exceeded (maxEmbeddedBytes): {} bytes of embedded data, at most {}", bytes, max)
+      }
+      LimitError::FieldTooLong { field, bytes, max } => {
+        write!(f, "limit exceeded (maxFieldBytes): {} is {} bytes, at most {}", field, bytes, max)
+      }
+    }
+  }
+}
+
+fn is_data_url(value: &str) -> bool {
+  value.get(..5).is_some_and(|prefix| prefix.eq_ignore_ascii_case("data:"))
+}
+
+fn value_bytes(value: &Value) -> usize {
+  match value {
+    Value::String(text) => text.len(),
+    Value::Array(items) => items.iter().map(value_bytes).sum(),
+    Value::Object(map) => map.iter().map(|(key, item)| key.len() + value_bytes(item)).sum(),
+    _ => 0,
+  }
+}
+
+struct Checker<'a> {
+  limits: &'a Limits,
+  cover: usize,
+  embedded: usize,
+}
+
+impl Checker<'_> {
+  fn field(&self, name: &str, value: &str) -> Result<(), LimitError> {
+    self.length(name, value.len())
+  }
+
+  fn length(&self, name: &str, bytes: usize) -> Result<(), LimitError> {
+    if bytes > self.limits.max_field_bytes {
+      return Err(LimitError::FieldTooLong {
+        field: format!("cover {} {}", self.cover, name),
+        bytes,
+        max: self.limits.max_field_bytes,
+      });
+    }
+    Ok(())
+  }
+
+  fn embedded(&mut self, bytes: usize) -> Result<(), LimitError> {
+    self.embedded += bytes;
+    if self.embedded > self.limits.max_embedded_bytes {
+      return Err(LimitError::EmbeddedTooLarge { bytes: self.embedded, max: self.limits.max_embedded_bytes });
+    }
+    Ok(())
+  }
+
+  // 图片引用可以是 data: 地址，计入内嵌总量而不是字段长度
+  fn image(&mut self, name: &str, value: &str) -> Result<(), LimitError> {
+    if is_data_url(value) {
+      self.embedded(value.len())
+    } else {
+      self.field(name, value)
+    }
+  }
+
+  fn extra(&self, owner: &str, extra: &Map<String, Value>) -> Result<(), LimitError> {
+    for (key, value) in extra {
+      self.length(&format!("{}.{}", owner, key), value_bytes(value))?;
+    }
+    Ok(())
+  }
+
+  fn project(&mut self, project: &CfjProject) -> Result<(), LimitError> {
+    if project.layers.len() > self.limits.max_layers {
+      return Err(LimitError::TooManyLayers {
+        cover: self.cover,
+        count: project.layers.len(),
+        max: self.limits.max_layers,
+      });
+    }
+    self.field("id", &project.id)?;
+    self.field("title", &project.title)?;
+    let background = &project.background;
+    if background.background_type == BackgroundType::Image {
+      self.image("background", &background.value)?;
+    } else {
+      self.field("background", &background.value)?;
+    }
+    self.field("background overlay", &background.overlay_color)?;
+    self.field("canvas ratio", &project.canvas_config.ratio)?;
+    self.extra("project", &project.extra)?;
+    for layer in &project.layers {
+      let name = format!("layer {}", layer.id);
+      self.field(&name, &layer.id)?;
+      self.field(&format!("{} name", name), &layer.name)?;
+      if layer.layer_type == LayerType::Image {
+        self.image(&format!("{} content", name), &layer.content)?;
+      } else {
+        self.field(&format!("{} content", name), &layer.content)?;
+      }
+      let mut optional = vec![
+        ("color", layer.color.as_ref()),
+        ("font family", layer.font_family.as_ref()),
+        ("font style", layer.font_style.as_ref()),
+        ("text align", layer.text_align.as_ref()),
+        ("writing mode", layer.writing_mode.as_ref()),
+        ("parent", layer.parent_id.as_ref()),
+      ];
+      if let Some(gradient) = &layer.text_gradient {
+        optional.extend([("gradient", Some(&gradient.from)), ("gradient", Some(&gradient.to))]);
+      }
+      if let Some(shadow) = &layer.text_shadow {
+        optional.push(("shadow color", Some(&shadow.color)));
+      }
+      for (label, value) in optional {
+        if let Some(value) = value {
+          self.field(&format!("{} {}", name, label), value)?;
+        }
+      }
+      self.length(&format!("{} children", name), layer.children.iter().flatten().map(String::len).sum())?;
+      self.extra(&name, &layer.extra)?;
+    }
+    for font in &project.fonts {
+      let name = format!("font {}", font.family);
+      self.field(&name, &font.family)?;
+      if let Some(path) = &font.path {
+        self.field(&name, path)?;
+      }
+      if let Some(data) = &font.data {
+        self.embedded(data.len())?;
+      }
+    }
+    Ok(())
+  }
+}
+
+// 内嵌数据按整个请求累计，多张封面引用同一张图时各算一次
+pub fn check_with(projects: &[CfjProject], limits: &Limits) -> Result<(), LimitError> {
+  if projects.len() > limits.max_covers {
+    return Err(LimitError::TooManyCovers { count: projects.len(), max: limits.max_covers });
+  }
+  let mut checker = Checker { limits, cover: 0, embedded: 0 };
+  for (index, project) in projects.iter().enumerate() {
+    checker.cover = index;
+    checker.project(project)?;
+  }
+  Ok(())
+}
+
+fn load(app: &AppHandle) -> Limits {
+  let Some(file) = app.path().app_config_dir().ok().map(|dir| dir.join(LIMITS_FILE)) else {
+    return Limits::default();
+  };
+  let Ok(text) = std::fs::read_to_string(&file) else {
+    return Limits::default();
+  };
+  match serde_json::from_str(&text) {
+    Ok(limits) => {
+      log::info!("ipc limits loaded from {}: {:?}", file.display(), limits);
+      limits
+    }
+    Err(err) => {
+      log::warn!("ipc limits ignored: {}: {}", file.display(), err);
+      Limits::default()
+    }
+  }
+}
+
+pub fn current(app: &AppHandle) -> &'static Limits {
+  LIMITS.get_or_init(|| load(app))
+}
+
+// 供命令在处理前调用，command 为日志中的命令名
+pub fn check(app: &AppHandle, command: &str, projects: &[CfjProject]) -> Result<(), String> {
+  check_with(projects, current(app)).map_err(|err| {
+    log::warn!("{} rejected: {}", command, err);
+    err.to_string()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+
+  fn cover() -> CfjProject {
+    cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project
+  }
+
+  fn limits(change: impl FnOnce(&mut Limits)) -> Limits {
+    let mut limits = Limits::default();
+    change(&mut limits);
+    limits
+  }
+
+  #[test]
+  fn cover_count_boundary() {
+    let covers = vec![cover(), cover()];
+    assert_eq!(check_with(&covers, &limits(|l| l.max_covers = 2)), Ok(()));
+    assert_eq!(
+      check_with(&covers, &limits(|l| l.max_covers = 1)),
+      Err(LimitError::TooManyCovers { count: 2, max: 1 })
+    );
+  }
+
+  #[test]
+  fn layer_count_boundary() {
+    let mut project = cover();
+    let layer = project.layers[0].clone();
+    project.layers.extend(std::iter::repeat_n(layer, 4));
+    let covers = vec![cover(), project];
+    assert_eq!(check_with(&covers, &limits(|l| l.max_layers = 5)), Ok(()));
+    assert_eq!(
+      check_with(&covers, &limits(|l| l.max_layers = 4)),
+      Err(LimitError::TooManyLayers { cover: 1, count: 5, max: 4 })
+    );
+  }
+
+  #[test]
+  fn embedded_bytes_add_up_across_covers() {
+    let mut project = cover();
+    project.layers[0].layer_type = LayerType::Image;
+    project.layers[0].content = format!("data:image/png;base64,{}", "A".repeat(1000));
+    let size = project.layers[0].content.len();
+    let covers = vec![project.clone(), project];
+    // data: 地址不受单个字段长度限制
+    let fits = limits(|l| {
+      l.max_embedded_bytes = size * 2;
+      l.max_field_bytes = 100;
+    });
+    assert_eq!(check_with(&covers, &fits), Ok(()));
+    assert_eq!(
+      check_with(&covers, &limits(|l| l.max_embedded_bytes = size * 2 - 1)),
+      Err(LimitError::EmbeddedTooLarge { bytes: size * 2, max: size * 2 - 1 })
+    );
+  }
+
+  #[test]
+  fn field_length_boundary() {
+    let mut project = cover();
+    project.title = "标".repeat(100);
+    let covers = vec![project];
+    assert_eq!(check_with(&covers, &limits(|l| l.max_field_bytes = 300)), Ok(()));
+    let err = check_with(&covers, &limits(|l| l.max_field_bytes = 299)).unwrap_err();
+    assert_eq!(
+      err,
+      LimitError::FieldTooLong { field: "cover 0 title".to_string(), bytes: 300, max: 299 }
+    );
+    assert!(err.to_string().starts_with("limit exceeded (maxFieldBytes)"));
+  }
+
+  #[test]
+  fn unknown_fields_count_their_strings() {
+    let mut project = cover();
+    project.layers[0].extra.insert("note".to_string(), Value::from(vec!["x".repeat(50), "y".repeat(50)]));
+    let covers = vec![project.clone()];
+    let id = project.layers[0].id.clone();
+    assert_eq!(check_with(&covers, &limits(|l| l.max_field_bytes = 100)), Ok(()));
+    assert_eq!(
+      check_with(&covers, &limits(|l| l.max_field_bytes = 99)),
+      Err(LimitError::FieldTooLong { field: format!("cover 0 layer {}.note", id), bytes: 100, max: 99 })
+    );
+  }
+}
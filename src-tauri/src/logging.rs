@@ -0,0 +1,42 @@
+// 模块：日志（应用日志目录下滚动文件，级别可由环境变量覆盖）
+use log::LevelFilter;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+use tauri_plugin_opener::OpenerExt;
+
+const LEVEL_ENV: &str = "COVERFLOW_LOG";
+const LOG_FILE_NAME: &str = "coverflow";
+const MAX_FILE_SIZE: u128 = 2 * 1024 * 1024;
+const KEEP_FILES: usize = 5;
+
+fn level() -> LevelFilter {
+  let default = if cfg!(debug_assertions) { LevelFilter::Debug } else { LevelFilter::Info };
+  std::env::var(LEVEL_ENV)
+    .ok()
+    .and_then(|value| value.trim().parse().ok())
+    .unwrap_or(default)
+}
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+  tauri_plugin_log::Builder::new()
+    .clear_targets()
+    .target(Target::new(TargetKind::Stdout))
+    .target(Target::new(TargetKind::LogDir {
+      file_name: Some(LOG_FILE_NAME.to_string()),
+    }))
+    .rotation_strategy(RotationStrategy::KeepSome(KEEP_FILES))
+    .max_file_size(MAX_FILE_SIZE)
+    .level(level())
+    .build()
+}
+
+#[tauri::command]
+pub fn open_log_dir(app: AppHandle) -> Result<(), String> {
+  let dir = app.path().app_log_dir().map_err(|err| err.to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+  app
+    .opener()
+    .open_path(dir.to_string_lossy(), None::<&str>)
+    .map_err(|err| err.to_string())
+}
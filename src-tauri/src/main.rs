@@ -1,43 +1,161 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::sync::{Arc, Mutex};
-use tauri::Manager;
+mod assets;
+mod atomic;
+mod autosave;
+mod cfj;
+mod cli;
+mod contact_sheet;
+mod crash;
+mod deeplink;
+mod dragdrop;
+mod duplicate;
+mod embed;
+mod export;
+mod fetch;
+mod file_association;
+mod fonts;
+mod history;
+mod icc;
+mod image_info;
+mod limits;
+mod logging;
+mod menu;
+mod naming;
+mod optimize;
+mod pdf;
+mod pending;
+mod print;
+mod recent;
+mod recycle;
+mod render;
+mod scope;
+mod shortcut;
+mod svg;
+mod templates;
+mod thumbnail;
+mod tray;
+mod updater;
+mod validate;
+mod watcher;
+mod window_state;
 
-#[derive(Clone)]
-struct PendingOpen(Arc<Mutex<Vec<String>>>);
+use pending::PendingOpen;
 
 fn main() {
-  let pending_open = PendingOpen(Arc::new(Mutex::new(Vec::new())));
+  // 带 --export 时只做无界面导出，不创建窗口
+  let args: Vec<String> = std::env::args().collect();
+  if let Some(parsed) = cli::parse_args(&args) {
+    std::process::exit(cli::main(parsed));
+  }
+  crash::wait_if_restarted();
 
   tauri::Builder::default()
+    .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+      pending::on_second_instance(app, args, cwd);
+    }))
+    .plugin(tauri_plugin_deep_link::init())
+    .plugin(logging::plugin())
     .plugin(tauri_plugin_opener::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
-    .manage(pending_open.clone())
+    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+    .manage(PendingOpen::default())
+    .manage(autosave::PendingRecovery::default())
+    .manage(updater::PendingUpdate::default())
+    .manage(shortcut::ActivationShortcut::default())
+    .manage(watcher::ProjectWatcher::default())
+    .manage(scope::ProjectScope::default())
+    .manage(history::ProjectHistory::default())
+    .manage(export::ExportJobs::default())
+    .invoke_handler(tauri::generate_handler![
+      cfj::load_cfj,
+      cfj::preview_cfj,
+      cfj::save_cfj,
+      cfj::reload_cfj,
+      duplicate::duplicate_project,
+      embed::embed_assets,
+      embed::extract_assets,
+      cfj::project_hash,
+      cfj::is_writable,
+      history::history_push,
+      history::history_undo,
+      history::history_redo,
+      history::history_status,
+      history::history_clear,
+      validate::validate_cfj,
+      scope::revoke_project_scope,
+      assets::relink_assets,
+      export::export_png,
+      export::export_all,
+      export::cancel_export,
+      export::reveal_in_file_manager,
+      fetch::fetch_image,
+      image_info::image_info,
+      pdf::export_pdf,
+      svg::export_svg,
+      contact_sheet::export_contact_sheet,
+      print::print_cover,
+      fonts::list_system_fonts,
+      templates::list_templates,
+      templates::new_from_template,
+      thumbnail::cfj_thumbnail,
+      recent::get_recent_files,
+      recent::push_recent_file,
+      pending::frontend_ready,
+      pending::ack_open,
+      autosave::autosave,
+      autosave::discard_recovery,
+      logging::open_log_dir,
+      file_association::register_file_association,
+      file_association::unregister_file_association,
+      file_association::query_file_association,
+      updater::check_for_update,
+      updater::install_update,
+      updater::get_update_settings,
+      updater::set_update_auto_check,
+      shortcut::get_activation_shortcut,
+      shortcut::set_activation_shortcut,
+      tray::get_tray_settings,
+      tray::set_minimize_to_tray,
+      recycle::move_to_trash,
+      recycle::get_trash_settings,
+      recycle::set_use_trash,
+      watcher::watch_project,
+      watcher::unwatch_project
+    ])
     .setup(|app| {
+      crash::install(app.handle());
+      window_state::restore(app.handle());
+      let app_menu = menu::build(app.handle())?;
+      app.set_menu(app_menu)?;
+      autosave::scan(app.handle());
+      updater::init(app.handle())?;
+      shortcut::init(app.handle());
+      tray::init(app.handle());
+      crash::announce_previous(app.handle());
       let args: Vec<String> = std::env::args().collect();
-      if let Some(path) = args.iter().find(|arg| arg.to_lowercase().ends_with(".cfj")) {
-        let pending_open = app.state::<PendingOpen>().0.clone();
-        if let Ok(mut pending) = pending_open.lock() {
-          pending.push(path.clone());
-        };
-      }
+      let cwd = std::env::current_dir().unwrap_or_default();
+      let paths = pending::paths_from_args(&args, &cwd);
+      log::info!("launch with {} project(s): {:?}", paths.len(), paths);
+      pending::enqueue(app.handle(), paths);
+      deeplink::init(app.handle());
       Ok(())
     })
-    .on_page_load(move |window, _| {
-      let pending_open = window.app_handle().state::<PendingOpen>().0.clone();
-      if let Ok(mut pending) = pending_open.lock() {
-        for path in pending.drain(..) {
-          if let Ok(encoded) = serde_json::to_string(&path) {
-            let script = format!(
-              "window.__CFJ_PENDING__ = window.__CFJ_PENDING__ || []; window.__CFJ_PENDING__.push({});",
-              encoded
-            );
-            let _ = window.eval(&script);
-          }
-        }
-      };
+    .on_menu_event(menu::on_menu_event)
+    .on_page_load(pending::on_page_load)
+    .on_window_event(|window, event| {
+      window_state::on_window_event(window, event);
+      dragdrop::on_window_event(window, event);
+      watcher::on_window_event(window, event);
+      tray::on_window_event(window, event);
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| match event {
+      tauri::RunEvent::Exit => tray::remove(app),
+      #[cfg(any(target_os = "macos", target_os = "ios"))]
+      tauri::RunEvent::Opened { urls } => pending::open_urls(app, urls),
+      _ => {}
+    });
 }
@@ -0,0 +1,182 @@
+// 模块：原生应用菜单（文件 / 最近打开 / 帮助）
+use crate::{cfj, logging, pending, recent, tray};
+use tauri::menu::{
+  AboutMetadata, Menu, MenuBuilder, MenuEvent, MenuItem, MenuItemBuilder, MenuItemKind,
+  PredefinedMenuItem, Submenu, SubmenuBuilder,
+};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+pub const SAVE_EVENT: &str = "menu://save";
+pub const SAVE_AS_EVENT: &str = "menu://save-as";
+pub const SAVE_COPY_EVENT: &str = "menu://save-copy";
+pub const RELOAD_EVENT: &str = "menu://reload";
+
+const OPEN_ID: &str = "file.open";
+const SAVE_ID: &str = "file.save";
+const SAVE_AS_ID: &str = "file.save_as";
+const SAVE_COPY_ID: &str = "file.save_copy";
+const RELOAD_ID: &str = "file.reload";
+const LOGS_ID: &str = "help.logs";
+const RECENT_EMPTY_ID: &str = "recent.empty";
+const RECENT_PREFIX: &str = "recent:";
+
+// 保存“最近打开”子菜单，列表变化时原地替换菜单项
+pub struct RecentMenu(Submenu<tauri::Wry>);
+
+fn recent_items(app: &AppHandle) -> tauri::Result<Vec<MenuItem<tauri::Wry>>> {
+  let entries = recent::list(app);
+  if entries.is_empty() {
+    let empty = MenuItemBuilder::with_id(RECENT_EMPTY_ID, "No Recent Files")
+      .enabled(false)
+      .build(app)?;
+    return Ok(vec![empty]);
+  }
+  entries
+    .iter()
+    .map(|entry| {
+      MenuItemBuilder::with_id(format!("{}{}", RECENT_PREFIX, entry.path), &entry.path).build(app)
+    })
+    .collect()
+}
+
+// 应用菜单和托盘菜单各有一份，菜单项 id 相同
+pub fn recent_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
+  let mut recent = SubmenuBuilder::new(app, "Recent");
+  for item in recent_items(app)? {
+    recent = recent.item(&item);
+  }
+  recent.build()
+}
+
+pub fn build(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+  let open = MenuItemBuilder::with_id(OPEN_ID, "Open…")
+    .accelerator("CmdOrCtrl+O")
+    .build(app)?;
+  let save = MenuItemBuilder::with_id(SAVE_ID, "Save")
+    .accelerator("CmdOrCtrl+S")
+    .build(app)?;
+  let save_as = MenuItemBuilder::with_id(SAVE_AS_ID, "Save As…")
+    .accelerator("CmdOrCtrl+Shift+S")
+    .build(app)?;
+  let save_copy = MenuItemBuilder::with_id(SAVE_COPY_ID, "Save a Copy…").build(app)?;
+  let reload = MenuItemBuilder::with_id(RELOAD_ID, "Reload from Disk").build(app)?;
+
+  let recent = recent_submenu(app)?;
+
+  let file = SubmenuBuilder::new(app, "File")
+    .item(&open)
+    .item(&recent)
+    .separator()
+    .item(&save)
+    .item(&save_as)
+    .item(&save_copy)
+    .item(&reload)
+    .separator()
+    .item(&PredefinedMenuItem::quit(app, None)?)
+    .build()?;
+
+  let about = AboutMetadata {
+    name: Some(app.package_info().name.clone()),
+    version: Some(app.package_info().version.to_string()),
+    ..AboutMetadata::default()
+  };
+  let logs = MenuItemBuilder::with_id(LOGS_ID, "Open Logs").build(app)?;
+  let help = SubmenuBuilder::new(app, "Help")
+    .item(&PredefinedMenuItem::about(app, None, Some(about))?)
+    .item(&logs)
+    .build()?;
+
+  // macOS 的第一个子菜单固定显示为应用菜单
+  let mut menu = MenuBuilder::new(app);
+  #[cfg(target_os = "macos")]
+  {
+    let app_menu = SubmenuBuilder::new(app, app.package_info().name.clone())
+      .hide()
+      .hide_others()
+      .show_all()
+      .separator()
+      .quit()
+      .build()?;
+    menu = menu.item(&app_menu);
+  }
+  let edit = SubmenuBuilder::new(app, "Edit")
+    .cut()
+    .copy()
+    .paste()
+    .select_all()
+    .build()?;
+  menu = menu.item(&file).item(&edit).item(&help);
+
+  app.manage(RecentMenu(recent));
+  menu.build()
+}
+
+pub fn refill_recent(app: &AppHandle, submenu: &Submenu<tauri::Wry>) {
+  let result = (|| -> tauri::Result<()> {
+    for item in submenu.items()? {
+      if let MenuItemKind::MenuItem(item) = item {
+        submenu.remove(&item)?;
+      }
+    }
+    for item in recent_items(app)? {
+      submenu.append(&item)?;
+    }
+    Ok(())
+  })();
+  if let Err(err) = result {
+    log::warn!("recent menu not refreshed: {}", err);
+  }
+}
+
+// 最近打开列表变化后调用
+pub fn refresh_recent(app: &AppHandle) {
+  if let Some(state) = app.try_state::<RecentMenu>() {
+    refill_recent(app, &state.0);
+  }
+  tray::refresh_recent(app);
+}
+
+// 与命令行、第二实例走同一条校验与缓冲流程
+fn pick_project(app: &AppHandle) {
+  let handle = app.clone();
+  app
+    .dialog()
+    .file()
+    .add_filter("CoverFlow Project", &cfj::EXTENSIONS)
+    .pick_file(move |selection| {
+      let Some(path) = selection.and_then(|path| path.into_path().ok()) else {
+        return;
+      };
+      pending::enqueue(&handle, vec![path.to_string_lossy().into_owned()]);
+    });
+}
+
+pub fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+  let id = event.id().as_ref();
+  match id {
+    OPEN_ID => pick_project(app),
+    SAVE_ID => {
+      let _ = app.emit(SAVE_EVENT, ());
+    }
+    SAVE_AS_ID => {
+      let _ = app.emit(SAVE_AS_EVENT, ());
+    }
+    SAVE_COPY_ID => {
+      let _ = app.emit(SAVE_COPY_EVENT, ());
+    }
+    RELOAD_ID => {
+      let _ = app.emit(RELOAD_EVENT, ());
+    }
+    LOGS_ID => {
+      if let Err(err) = logging::open_log_dir(app.clone()) {
+        log::warn!("open log dir failed: {}", err);
+      }
+    }
+    _ => {
+      if let Some(path) = id.strip_prefix(RECENT_PREFIX) {
+        pending::enqueue(app, vec![path.to_string()]);
+      }
+    }
+  }
+}
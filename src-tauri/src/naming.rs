@@ -0,0 +1,245 @@
+// 模块：批量导出的文件名模板，如 "{title}-{index}"
+// 支持 {title} {index} {width} {height} {date}，{{ 与 }} 表示字面的花括号
+use crate::cfj::CfjProject;
+use crate::export::{self, ExportFormat};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamingError {
+  UnknownPlaceholder(String),
+  Unclosed,
+  UnmatchedBrace,
+}
+
+impl fmt::Display for NamingError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      NamingError::UnknownPlaceholder(name) => write!(
+        f,
+        "unknown placeholder {{{}}} in naming template (supported: {{title}} {{index}} {{width}} {{height}} {{date}})",
+        name
+      ),
+      NamingError::Unclosed => write!(f, "unclosed {{ in naming template"),
+      NamingError::UnmatchedBrace => write!(f, "unmatched }} in naming template (use }}}} for a literal brace)"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+  Title,
+  Index,
+  Width,
+  Height,
+  Date,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+  Text(String),
+  Field(Field),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingTemplate(Vec<Part>);
+
+// 单个封面的取值，index 从 1 开始
+pub struct NamingContext<'a> {
+  pub title: &'a str,
+  pub index: usize,
+  pub count: usize,
+  pub width: u32,
+  pub height: u32,
+  pub date: &'a str,
+}
+
+impl NamingTemplate {
+  pub fn parse(template: &str) -> Result<Self, NamingError> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+      match ch {
+        '{' if chars.peek() == Some(&'{') => {
+          chars.next();
+          text.push('{');
+        }
+        '}' if chars.peek() == Some(&'}') => {
+          chars.next();
+          text.push('}');
+        }
+        '}' => return Err(NamingError::UnmatchedBrace),
+        '{' => {
+          let mut name = String::new();
+          loop {
+            match chars.next() {
+              Some('}') => break,
+              Some(ch) => name.push(ch),
+              None => return Err(NamingError::Unclosed),
+            }
+          }
+          let field = match name.trim() {
+            "title" => Field::Title,
+            "index" => Field::Index,
+            "width" => Field::Width,
+            "height" => Field::Height,
+            "date" => Field::Date,
+            _ => return Err(NamingError::UnknownPlaceholder(name)),
+          };
+          if !text.is_empty() {
+            parts.push(Part::Text(std::mem::take(&mut text)));
+          }
+          parts.push(Part::Field(field));
+        }
+        _ => text.push(ch),
+      }
+    }
+    if !text.is_empty() {
+      parts.push(Part::Text(text));
+    }
+    Ok(NamingTemplate(parts))
+  }
+
+  // 结果未经清理，由调用方交给 sanitize_filename
+  pub fn render(&self, context: &NamingContext<'_>) -> String {
+    let digits = context.count.max(1).to_string().len();
+    let mut name = String::new();
+    for part in &self.0 {
+      match part {
+        Part::Text(text) => name.push_str(text),
+        Part::Field(Field::Title) => name.push_str(context.title),
+        Part::Field(Field::Index) => name.push_str(&format!("{:0width$}", context.index, width = digits)),
+        Part::Field(Field::Width) => name.push_str(&context.width.to_string()),
+        Part::Field(Field::Height) => name.push_str(&context.height.to_string()),
+        Part::Field(Field::Date) => name.push_str(context.date),
+      }
+    }
+    name
+  }
+}
+
+// UTC 日期 YYYY-MM-DD（按公历由天数换算）
+pub fn date_string(time: SystemTime) -> String {
+  let days = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0) as i64;
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// 模板里已写了扩展名时不再重复追加
+fn strip_extension(name: &str, format: ExportFormat) -> &str {
+  let suffix = format!(".{}", format.extension());
+  let cut = name.len().saturating_sub(suffix.len());
+  match name.get(cut..) {
+    Some(tail) if tail.eq_ignore_ascii_case(&suffix) => &name[..cut],
+    _ => name,
+  }
+}
+
+// 每个封面的文件名主体（不含扩展名），清理后为空时回退为 cover_<序号>
+pub fn stems(template: &NamingTemplate, projects: &[CfjProject], format: ExportFormat, scale: f32) -> Vec<String> {
+  let date = date_string(SystemTime::now());
+  let count = projects.len();
+  projects
+    .iter()
+    .enumerate()
+    .map(|(index, project)| {
+      let (width, height) = export::output_size(
+        project.canvas_config.width as f32,
+        project.canvas_config.height as f32,
+        scale,
+      );
+      let name = template.render(&NamingContext {
+        title: &project.title,
+        index: index + 1,
+        count,
+        width,
+        height,
+        date: &date,
+      });
+      export::file_stem(strip_extension(&name, format), index)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn context(index: usize, count: usize) -> NamingContext<'static> {
+    NamingContext {
+      title: "封面",
+      index,
+      count,
+      width: 900,
+      height: 383,
+      date: "2024-02-29",
+    }
+  }
+
+  #[test]
+  fn substitutes_every_placeholder() {
+    let template = NamingTemplate::parse("{title}-{index}_{width}x{height}@{date}").unwrap();
+    assert_eq!(template.render(&context(1, 1)), "封面-1_900x383@2024-02-29");
+    // 占位符两侧允许空格，{{ }} 为字面花括号
+    let template = NamingTemplate::parse("{{{ title }}}").unwrap();
+    assert_eq!(template.render(&context(1, 1)), "{封面}");
+  }
+
+  #[test]
+  fn pads_index_to_cover_count() {
+    let template = NamingTemplate::parse("{index}").unwrap();
+    assert_eq!(template.render(&context(3, 9)), "3");
+    assert_eq!(template.render(&context(3, 10)), "03");
+    assert_eq!(template.render(&context(42, 100)), "042");
+    assert_eq!(template.render(&context(1, 0)), "1");
+  }
+
+  #[test]
+  fn rejects_bad_templates() {
+    assert_eq!(
+      NamingTemplate::parse("{name}"),
+      Err(NamingError::UnknownPlaceholder("name".to_string()))
+    );
+    assert_eq!(NamingTemplate::parse("{title"), Err(NamingError::Unclosed));
+    assert_eq!(NamingTemplate::parse("title}"), Err(NamingError::UnmatchedBrace));
+  }
+
+  #[test]
+  fn formats_utc_dates() {
+    let day = |days: u64| date_string(UNIX_EPOCH + Duration::from_secs(days * 86_400));
+    assert_eq!(day(0), "1970-01-01");
+    assert_eq!(day(19_782), "2024-02-29");
+    assert_eq!(day(19_783), "2024-03-01");
+    assert_eq!(day(11_016), "2000-02-29");
+    assert_eq!(date_string(UNIX_EPOCH + Duration::from_secs(86_399)), "1970-01-01");
+  }
+
+  #[test]
+  fn template_extension_is_not_repeated() {
+    let project = crate::cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    let template = NamingTemplate::parse("{title}.PNG").unwrap();
+    let names = stems(&template, std::slice::from_ref(&project), ExportFormat::Png, 2.0);
+    assert_eq!(names, vec![export::sanitize_filename(&project.title)]);
+    let sized = stems(&NamingTemplate::parse("{width}x{height}").unwrap(), &[project], ExportFormat::Png, 2.0);
+    assert_eq!(sized, vec!["1800x766".to_string()]);
+  }
+
+  #[test]
+  fn empty_names_fall_back_to_one_based_index() {
+    let mut project = crate::cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    project.title = String::new();
+    let template = NamingTemplate::parse("{title}").unwrap();
+    let names = stems(&template, &[project.clone(), project], ExportFormat::Png, 1.0);
+    assert_eq!(names, vec!["cover_1".to_string(), "cover_2".to_string()]);
+  }
+}
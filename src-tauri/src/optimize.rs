@@ -0,0 +1,117 @@
+// 模块：导出 PNG 的体积优化（oxipng 无损重压缩，可选减色为调色板）
+use color_quant::NeuQuant;
+use image::{ImageEncoder, ImageFormat};
+use serde::{Deserialize, Serialize};
+
+// NeuQuant 采样间隔，1 最精细，30 最快
+const QUANT_SAMPLE_FACTOR: i32 = 10;
+const PALETTE_COLORS: usize = 256;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptimizeLevel {
+  #[default]
+  None,
+  // 以下三级均为无损，级别越高越慢、文件越小
+  Fast,
+  Balanced,
+  Max,
+  // 有损：减为 256 色调色板后再按 Balanced 压缩，适合纯色图形
+  Palette,
+}
+
+impl OptimizeLevel {
+  fn preset(self) -> Option<u8> {
+    match self {
+      OptimizeLevel::None => None,
+      OptimizeLevel::Fast => Some(1),
+      OptimizeLevel::Balanced | OptimizeLevel::Palette => Some(2),
+      OptimizeLevel::Max => Some(6),
+    }
+  }
+
+  pub fn is_lossless(self) -> bool {
+    self != OptimizeLevel::Palette
+  }
+}
+
+// 非预乘 RGBA 像素，用于比对与减色
+fn decode_rgba(png: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+  let image = image::load_from_memory_with_format(png, ImageFormat::Png).ok()?.to_rgba8();
+  Some((image.width(), image.height(), image.into_raw()))
+}
+
+fn quantize(png: &[u8]) -> Result<Vec<u8>, String> {
+  let (width, height, mut rgba) = decode_rgba(png).ok_or("decode failed")?;
+  let quant = NeuQuant::new(QUANT_SAMPLE_FACTOR, PALETTE_COLORS, &rgba);
+  for pixel in rgba.chunks_exact_mut(4) {
+    quant.map_pixel(pixel);
+  }
+  let mut bytes = Vec::new();
+  image::codecs::png::PngEncoder::new(&mut bytes)
+    .write_image(&rgba, width, height, image::ExtendedColorType::Rgba8)
+    .map_err(|err| err.to_string())?;
+  Ok(bytes)
+}
+
+// 返回优化后的数据；失败、解码结果不一致或没有变小时返回 None，调用方沿用原数据
+pub fn optimize_png(png: &[u8], level: OptimizeLevel) -> Result<Option<Vec<u8>>, String> {
+  let Some(preset) = level.preset() else {
+    return Ok(None);
+  };
+  let input = if level.is_lossless() { png.to_vec() } else { quantize(png)? };
+  let optimized = oxipng::optimize_from_memory(&input, &oxipng::Options::from_preset(preset))
+    .map_err(|err| err.to_string())?;
+  let original = decode_rgba(png).ok_or("decode failed")?;
+  let Some(result) = decode_rgba(&optimized) else {
+    return Err("optimized PNG does not decode".to_string());
+  };
+  if (result.0, result.1) != (original.0, original.1) {
+    return Err("optimized PNG changed dimensions".to_string());
+  }
+  // 无损级别要求逐像素一致
+  if level.is_lossless() && result.2 != original.2 {
+    return Err("optimized PNG changed pixels".to_string());
+  }
+  Ok((optimized.len() < png.len()).then_some(optimized))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{cfj, export};
+
+  fn cover_png() -> Vec<u8> {
+    let project = cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project;
+    let (pixmap, _) = export::rasterize_fit(&project, 240).unwrap();
+    export::encode(&pixmap, export::ExportFormat::Png).unwrap()
+  }
+
+  #[test]
+  fn lossless_levels_keep_pixels_and_never_grow() {
+    let png = cover_png();
+    let original = decode_rgba(&png).unwrap();
+    for level in [OptimizeLevel::Fast, OptimizeLevel::Balanced, OptimizeLevel::Max] {
+      let output = optimize_png(&png, level).unwrap().unwrap_or_else(|| png.clone());
+      assert!(output.len() <= png.len(), "{:?} grew the file", level);
+      assert_eq!(decode_rgba(&output).unwrap(), original, "{:?} changed pixels", level);
+    }
+  }
+
+  #[test]
+  fn palette_keeps_dimensions() {
+    let png = cover_png();
+    let (width, height, _) = decode_rgba(&png).unwrap();
+    let output = optimize_png(&png, OptimizeLevel::Palette).unwrap().unwrap_or_else(|| png.clone());
+    assert!(output.len() <= png.len());
+    let (out_width, out_height, _) = decode_rgba(&output).unwrap();
+    assert_eq!((out_width, out_height), (width, height));
+    assert!(!OptimizeLevel::Palette.is_lossless());
+  }
+
+  #[test]
+  fn none_leaves_data_alone() {
+    assert_eq!(optimize_png(b"not a png", OptimizeLevel::None), Ok(None));
+    assert!(optimize_png(b"not a png", OptimizeLevel::Fast).is_err());
+  }
+}
@@ -0,0 +1,241 @@
+// 模块：导出多页 PDF（每页一个封面，按页边距等比居中）
+use crate::{atomic, limits};
+use crate::cfj::CfjProject;
+use crate::export::{self, ExportError, ExportFormat};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use serde::Deserialize;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tauri::AppHandle;
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+const MIN_DPI: u32 = 36;
+const MAX_DPI: u32 = 1200;
+
+fn default_margin() -> f32 {
+  10.0
+}
+
+fn default_dpi() -> u32 {
+  300
+}
+
+fn default_lossless() -> bool {
+  true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfPageSpec {
+  pub width_mm: f32,
+  pub height_mm: f32,
+  #[serde(default = "default_margin")]
+  pub margin_mm: f32,
+  #[serde(default = "default_dpi")]
+  pub dpi: u32,
+  // 关闭时以 JPEG 嵌入，文件更小
+  #[serde(default = "default_lossless")]
+  pub lossless: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+}
+
+// 在页边距内等比缩放并居中，单位为 pt；封面尺寸须为正数，由 build 事先检查
+pub fn fit(page_width: f32, page_height: f32, margin: f32, cover_width: f32, cover_height: f32) -> Placement {
+  let box_width = (page_width - margin * 2.0).max(1.0);
+  let box_height = (page_height - margin * 2.0).max(1.0);
+  let scale = (box_width / cover_width).min(box_height / cover_height);
+  let width = cover_width * scale;
+  let height = cover_height * scale;
+  Placement {
+    x: (page_width - width) / 2.0,
+    y: (page_height - height) / 2.0,
+    width,
+    height,
+  }
+}
+
+fn image_stream(project: &CfjProject, placement: Placement, spec: &PdfPageSpec) -> Result<Stream, ExportError> {
+  let dpi = spec.dpi.clamp(MIN_DPI, MAX_DPI) as f32;
+  let scale = placement.width / 72.0 * dpi / project.canvas_config.width.max(1.0) as f32;
+  let (pixmap, _) = export::rasterize(project, scale)?;
+  let mut dict = dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Image",
+    "Width" => pixmap.width() as i64,
+    "Height" => pixmap.height() as i64,
+    "ColorSpace" => "DeviceRGB",
+    "BitsPerComponent" => 8,
+  };
+  if spec.lossless {
+    let mut stream = Stream::new(dict, export::flatten_rgb(&pixmap));
+    stream.compress().map_err(|err| ExportError::Encode(err.to_string()))?;
+    Ok(stream)
+  } else {
+    dict.set("Filter", "DCTDecode");
+    Ok(Stream::new(dict, export::encode(&pixmap, ExportFormat::Jpeg)?).with_compression(false))
+  }
+}
+
+pub fn build(projects: &[CfjProject], spec: &PdfPageSpec) -> Result<Document, ExportError> {
+  if projects.is_empty() {
+    return Err(ExportError::Render("no covers to export".to_string()));
+  }
+  let page_width = spec.width_mm * POINTS_PER_MM;
+  let page_height = spec.height_mm * POINTS_PER_MM;
+  if !(page_width.is_finite() && page_height.is_finite() && page_width > 0.0 && page_height > 0.0) {
+    return Err(ExportError::Render("invalid page size".to_string()));
+  }
+  let margin = spec.margin_mm.max(0.0) * POINTS_PER_MM;
+
+  let mut doc = Document::with_version("1.5");
+  let pages_id = doc.new_object_id();
+  let mut kids: Vec<Object> = Vec::with_capacity(projects.len());
+
+  for (index, project) in projects.iter().enumerate() {
+    let canvas = &project.canvas_config;
+    // 宽或高为 0 时缩放比例为 inf/NaN，写出的 PDF 无法打开
+    if !(canvas.width.is_finite() && canvas.height.is_finite() && canvas.width > 0.0 && canvas.height > 0.0) {
+      return Err(ExportError::Render(format!(
+        "cover {} \"{}\": invalid canvas size {}x{}",
+        index, project.title, canvas.width, canvas.height
+      )));
+    }
+    let placement = fit(page_width, page_height, margin, canvas.width as f32, canvas.height as f32);
+    let image_id = doc.add_object(image_stream(project, placement, spec)?);
+    let content = Content {
+      operations: vec![
+        Operation::new("q", vec![]),
+        Operation::new(
+          "cm",
+          vec![
+            placement.width.into(),
+            0.into(),
+            0.into(),
+            placement.height.into(),
+            placement.x.into(),
+            placement.y.into(),
+          ],
+        ),
+        Operation::new("Do", vec!["Im0".into()]),
+        Operation::new("Q", vec![]),
+      ],
+    };
+    let encoded = content.encode().map_err(|err| ExportError::Encode(err.to_string()))?;
+    let content_id = doc.add_object(Stream::new(dictionary! {}, encoded));
+    let page_id = doc.add_object(dictionary! {
+      "Type" => "Page",
+      "Parent" => pages_id,
+      "MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
+      "Contents" => content_id,
+      "Resources" => dictionary! {
+        "XObject" => dictionary! { "Im0" => image_id },
+      },
+    });
+    kids.push(page_id.into());
+  }
+
+  let count = kids.len() as i64;
+  doc.objects.insert(
+    pages_id,
+    Object::Dictionary(dictionary! {
+      "Type" => "Pages",
+      "Kids" => kids,
+      "Count" => count,
+    }),
+  );
+  let catalog_id = doc.add_object(dictionary! {
+    "Type" => "Catalog",
+    "Pages" => pages_id,
+  });
+  doc.trailer.set("Root", catalog_id);
+  Ok(doc)
+}
+
+pub fn export_to(projects: &[CfjProject], spec: &PdfPageSpec, out_path: &Path) -> Result<(), ExportError> {
+  let mut doc = build(projects, spec)?;
+  atomic::write_with(out_path, |handle| {
+    let mut writer = BufWriter::new(handle);
+    doc.save_to(&mut writer).map_err(std::io::Error::other)?;
+    writer.flush()
+  })
+  .map_err(|err| ExportError::Write(err.to_string()))?;
+  log::info!("exported {} page(s): {}", projects.len(), out_path.display());
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn export_pdf(
+  app: AppHandle,
+  projects: Vec<CfjProject>,
+  out_path: String,
+  page: PdfPageSpec,
+) -> Result<(), String> {
+  limits::check(&app, "export_pdf", &projects)?;
+  tauri::async_runtime::spawn_blocking(move || export_to(&projects, &page, Path::new(&out_path)))
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALID: &str = include_str!("../tests/fixtures/valid.cfj");
+
+  fn spec(lossless: bool) -> PdfPageSpec {
+    PdfPageSpec {
+      width_mm: 210.0,
+      height_mm: 297.0,
+      margin_mm: 10.0,
+      dpi: MIN_DPI,
+      lossless,
+    }
+  }
+
+  #[test]
+  fn one_page_per_cover() {
+    let project = crate::cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("covers.pdf");
+    export_to(&[project.clone(), project.clone()], &spec(true), &path).unwrap();
+    assert_eq!(Document::load(&path).unwrap().get_pages().len(), 2);
+    export_to(&[project], &spec(false), &path).unwrap();
+    assert_eq!(Document::load(&path).unwrap().get_pages().len(), 1);
+    assert!(export_to(&[], &spec(true), &path).is_err());
+  }
+
+  #[test]
+  fn fit_centres_within_margins() {
+    // 横版封面放进竖版页面：宽度撑满边距，垂直居中
+    let placement = fit(100.0, 200.0, 10.0, 160.0, 90.0);
+    assert_eq!((placement.x, placement.width), (10.0, 80.0));
+    assert_eq!(placement.height, 45.0);
+    assert_eq!(placement.y, (200.0 - 45.0) / 2.0);
+  }
+
+  #[test]
+  fn zero_size_canvas_is_rejected() {
+    let project = crate::cfj::parse(VALID).unwrap().project;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("covers.pdf");
+    for (width, height) in [(0.0, 383.0), (900.0, 0.0), (-1.0, 383.0), (f64::NAN, 383.0)] {
+      let mut empty = project.clone();
+      empty.canvas_config.width = width;
+      empty.canvas_config.height = height;
+      let err = export_to(&[project.clone(), empty], &spec(true), &path).unwrap_err();
+      assert!(err.to_string().contains("cover 1 "), "{}", err);
+      assert!(err.to_string().contains("invalid canvas size"), "{}", err);
+    }
+    // 出错时不写出文件
+    assert!(!path.exists());
+  }
+}
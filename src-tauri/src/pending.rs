@@ -0,0 +1,331 @@
+// 模块：待打开的项目文件（启动参数、第二实例、系统打开事件）
+use crate::{autosave, cfj, recent, shortcut, window_state};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Webview};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+pub const OPEN_EVENT: &str = "cfj://open";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+  // 前端处理完后通过 ack_open 回传
+  pub token: u64,
+  pub path: String,
+}
+
+// 前端订阅前到达的路径先缓存，frontend_ready 后按顺序补发；
+// 已发出但未确认的请求保留，页面重新加载后再次就绪时重发
+#[derive(Default)]
+pub struct OpenQueue {
+  ready: bool,
+  next_token: u64,
+  paths: Vec<String>,
+  unacked: Vec<OpenRequest>,
+}
+
+impl OpenQueue {
+  fn issue(&mut self, path: String) -> OpenRequest {
+    self.next_token += 1;
+    let request = OpenRequest {
+      token: self.next_token,
+      path,
+    };
+    self.unacked.push(request.clone());
+    request
+  }
+
+  pub fn push(&mut self, paths: Vec<String>) -> Vec<OpenRequest> {
+    if !self.ready {
+      self.paths.extend(paths);
+      return Vec::new();
+    }
+    paths.into_iter().map(|path| self.issue(path)).collect()
+  }
+
+  pub fn mark_ready(&mut self) -> Vec<OpenRequest> {
+    self.ready = true;
+    let mut requests = self.unacked.clone();
+    let paths: Vec<String> = self.paths.drain(..).collect();
+    requests.extend(paths.into_iter().map(|path| self.issue(path)));
+    requests
+  }
+
+  pub fn ack(&mut self, token: u64) -> bool {
+    let before = self.unacked.len();
+    self.unacked.retain(|request| request.token != token);
+    self.unacked.len() != before
+  }
+
+  pub fn reset(&mut self) {
+    self.ready = false;
+  }
+}
+
+#[derive(Clone, Default)]
+pub struct PendingOpen(Arc<Mutex<OpenQueue>>);
+
+fn ack(app: &AppHandle, token: u64) -> bool {
+  app
+    .state::<PendingOpen>()
+    .0
+    .lock()
+    .map(|mut queue| queue.ack(token))
+    .unwrap_or(false)
+}
+
+fn deliver(app: &AppHandle, requests: Vec<OpenRequest>) {
+  for request in requests {
+    let path = &request.path;
+    // 损坏的文件直接提示，不再交给前端
+    if let Err(err) = cfj::load(Path::new(path)) {
+      log::warn!("pending open rejected: {}: {}", path, err);
+      ack(app, request.token);
+      app
+        .dialog()
+        .message(err.to_string())
+        .kind(MessageDialogKind::Error)
+        .title("CoverFlow")
+        .show(|_| {});
+      continue;
+    }
+    recent::push(app, path);
+    log::info!("pending open delivered: {} (#{})", path, request.token);
+    if let Err(err) = app.emit(OPEN_EVENT, &request) {
+      log::error!("emit {} failed: {}", OPEN_EVENT, err);
+    }
+  }
+}
+
+// 解析为规范路径，且必须是真实存在的 .cfj/.cfjz 普通文件（符号链接按目标判断）
+pub fn validate_path(path: &str) -> Result<String, String> {
+  let canonical = std::fs::canonicalize(path).map_err(|err| err.to_string())?;
+  if !canonical.is_file() {
+    return Err("not a regular file".to_string());
+  }
+  if !cfj::is_project_path(&canonical) {
+    return Err("not a .cfj or .cfjz file".to_string());
+  }
+  Ok(canonical.to_string_lossy().into_owned())
+}
+
+pub fn enqueue(app: &AppHandle, paths: Vec<String>) {
+  let mut accepted: Vec<String> = Vec::new();
+  for path in paths {
+    match validate_path(&path) {
+      Ok(canonical) if !accepted.contains(&canonical) => accepted.push(canonical),
+      Ok(_) => {}
+      Err(err) => log::warn!("ignored open request: {}: {}", path, err),
+    }
+  }
+  let paths = accepted;
+  if paths.is_empty() {
+    return;
+  }
+  log::debug!("pending open queued: {:?}", paths);
+  let requests = match app.state::<PendingOpen>().0.lock() {
+    Ok(mut queue) => queue.push(paths),
+    Err(_) => return,
+  };
+  deliver(app, requests);
+}
+
+// 页面重新加载后需等待前端再次确认就绪
+pub fn on_page_load<R: Runtime>(webview: &Webview<R>, payload: &PageLoadPayload<'_>) {
+  if payload.event() != PageLoadEvent::Started {
+    return;
+  }
+  if let Ok(mut queue) = webview.state::<PendingOpen>().0.lock() {
+    queue.reset();
+  };
+}
+
+#[tauri::command]
+pub fn frontend_ready(app: AppHandle) {
+  let requests = match app.state::<PendingOpen>().0.lock() {
+    Ok(mut queue) => queue.mark_ready(),
+    Err(_) => return,
+  };
+  log::debug!("frontend ready, flushing {} pending", requests.len());
+  deliver(&app, requests);
+  autosave::announce(&app);
+  shortcut::announce(&app);
+}
+
+// 前端已处理某个打开请求，之后不再重发
+#[tauri::command]
+pub fn ack_open(app: AppHandle, token: u64) {
+  if !ack(&app, token) {
+    log::debug!("ack_open: unknown token #{}", token);
+  }
+}
+
+// 从命令行参数中取出全部项目文件（跳过 argv[0]，按出现顺序去重）
+pub fn paths_from_args(args: &[String], cwd: &Path) -> Vec<String> {
+  let mut paths: Vec<String> = Vec::new();
+  for arg in args.iter().skip(1) {
+    // coverflow://open?path=x.cfj 也以 .cfj 结尾，由 deeplink 处理
+    if arg.contains("://") || !cfj::is_project_path(Path::new(arg)) {
+      continue;
+    }
+    // components() 会去掉多余的 "."，便于判重
+    let path: PathBuf = cwd.join(arg).components().collect();
+    let path = path.to_string_lossy().into_owned();
+    if !paths.contains(&path) {
+      paths.push(path);
+    }
+  }
+  paths
+}
+
+// 第二个实例启动时转交参数，并唤起已有窗口
+pub fn on_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+  let paths = paths_from_args(&args, Path::new(&cwd));
+
+  window_state::bring_to_front(app);
+  enqueue(app, paths);
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn open_urls(app: &AppHandle, urls: Vec<tauri::Url>) {
+  let paths: Vec<String> = urls
+    .into_iter()
+    .filter(|url| url.scheme() == "file")
+    .filter_map(|url| url.to_file_path().ok())
+    .filter(|path| cfj::is_project_path(path))
+    .map(|path| path.to_string_lossy().into_owned())
+    .collect();
+  enqueue(app, paths);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn paths(requests: &[OpenRequest]) -> Vec<&str> {
+    requests.iter().map(|request| request.path.as_str()).collect()
+  }
+
+  #[test]
+  fn queues_paths_until_ready() {
+    let mut queue = OpenQueue::default();
+    assert!(queue.push(vec!["a.cfj".to_string()]).is_empty());
+    assert!(queue.push(vec!["b.cfj".to_string(), "c.cfj".to_string()]).is_empty());
+    let requests = queue.mark_ready();
+    assert_eq!(paths(&requests), ["a.cfj", "b.cfj", "c.cfj"]);
+    // 就绪后直接发出
+    assert_eq!(paths(&queue.push(vec!["d.cfj".to_string()])), ["d.cfj"]);
+  }
+
+  #[test]
+  fn tokens_are_unique_and_acked_once() {
+    let mut queue = OpenQueue::default();
+    queue.mark_ready();
+    let requests = queue.push(vec!["a.cfj".to_string(), "b.cfj".to_string()]);
+    assert_ne!(requests[0].token, requests[1].token);
+    assert!(queue.ack(requests[0].token));
+    assert!(!queue.ack(requests[0].token));
+    assert!(!queue.ack(u64::MAX));
+    assert!(queue.ack(requests[1].token));
+  }
+
+  #[test]
+  fn collects_project_args_in_order() {
+    let args: Vec<String> = ["coverflow", "b.cfj", "--verbose", "./a.cfjz", "b.cfj", "coverflow://open?path=c.cfj", "notes.txt"]
+      .iter()
+      .map(|arg| arg.to_string())
+      .collect();
+    let cwd = Path::new("work");
+    let expected: Vec<String> = ["b.cfj", "a.cfjz"]
+      .iter()
+      .map(|name| cwd.join(name).to_string_lossy().into_owned())
+      .collect();
+    assert_eq!(paths_from_args(&args, cwd), expected);
+    // argv[0] 本身不算
+    assert!(paths_from_args(&["cover.cfj".to_string()], cwd).is_empty());
+  }
+
+  #[test]
+  fn validates_open_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("cover.cfj");
+    std::fs::write(&project, "{}").unwrap();
+    // 从当前目录退到根目录再进入临时目录，得到一个带 .. 的相对路径
+    let cwd = std::fs::canonicalize(".").unwrap();
+    let target = std::fs::canonicalize(dir.path()).unwrap();
+    let normal = |path: &Path| -> Vec<PathBuf> {
+      path
+        .components()
+        .filter(|component| matches!(component, std::path::Component::Normal(_)))
+        .map(|component| PathBuf::from(component.as_os_str()))
+        .collect()
+    };
+    let mut relative = PathBuf::from(".");
+    for _ in normal(&cwd) {
+      relative.push("..");
+    }
+    relative.extend(normal(&target));
+    relative.push("sub");
+    relative.push("..");
+    relative.push("cover.cfj");
+    std::fs::create_dir(dir.path().join("sub")).unwrap();
+    let canonical = validate_path(&relative.to_string_lossy()).unwrap();
+    assert!(Path::new(&canonical).is_absolute());
+    assert_eq!(Path::new(&canonical), std::fs::canonicalize(&project).unwrap());
+
+    assert!(validate_path(&dir.path().join("missing.cfj").to_string_lossy()).is_err());
+    let folder = dir.path().join("folder.cfj");
+    std::fs::create_dir(&folder).unwrap();
+    assert_eq!(validate_path(&folder.to_string_lossy()).unwrap_err(), "not a regular file");
+    let text = dir.path().join("notes.txt");
+    std::fs::write(&text, "").unwrap();
+    assert_eq!(validate_path(&text.to_string_lossy()).unwrap_err(), "not a .cfj or .cfjz file");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlinks_are_judged_by_target() {
+    let outside = tempfile::tempdir().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let secret = outside.path().join("id_rsa");
+    std::fs::write(&secret, "").unwrap();
+    let disguised = dir.path().join("cover.cfj");
+    std::os::unix::fs::symlink(&secret, &disguised).unwrap();
+    assert!(validate_path(&disguised.to_string_lossy()).is_err());
+
+    let target = outside.path().join("real.cfj");
+    std::fs::write(&target, "{}").unwrap();
+    let link = dir.path().join("link.cfj");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+    assert_eq!(
+      Path::new(&validate_path(&link.to_string_lossy()).unwrap()),
+      std::fs::canonicalize(&target).unwrap()
+    );
+  }
+
+  #[test]
+  fn redelivers_unacked_across_page_loads() {
+    let mut queue = OpenQueue::default();
+    queue.push(vec!["a.cfj".to_string()]);
+    let first = queue.mark_ready();
+    assert_eq!(paths(&first), ["a.cfj"]);
+
+    // 第一页未确认就被重新加载，期间又到达一个路径
+    queue.reset();
+    assert!(queue.push(vec!["b.cfj".to_string()]).is_empty());
+    let second = queue.mark_ready();
+    assert_eq!(paths(&second), ["a.cfj", "b.cfj"]);
+    // 重发沿用原 token，前端可据此去重
+    assert_eq!(second[0].token, first[0].token);
+
+    assert!(queue.ack(second[0].token));
+    queue.reset();
+    assert_eq!(paths(&queue.mark_ready()), ["b.cfj"]);
+    assert!(queue.ack(second[1].token));
+    queue.reset();
+    assert!(queue.mark_ready().is_empty());
+  }
+}
@@ -0,0 +1,98 @@
+// 模块：打印单个封面（按打印分辨率生成单页 PDF，交给系统打印队列或默认程序）
+use crate::cfj::CfjProject;
+use crate::limits;
+use crate::pdf::{self, PdfPageSpec};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+const PRINT_DPI: u32 = 300;
+// A4，纵向时的宽高
+const PAGE_SHORT_MM: f32 = 210.0;
+const PAGE_LONG_MM: f32 = 297.0;
+const PAGE_MARGIN_MM: f32 = 10.0;
+
+// 横版封面用横向纸张，避免缩得过小
+pub fn page_for(project: &CfjProject) -> PdfPageSpec {
+  let landscape = project.canvas_config.width > project.canvas_config.height;
+  let (width_mm, height_mm) = if landscape {
+    (PAGE_LONG_MM, PAGE_SHORT_MM)
+  } else {
+    (PAGE_SHORT_MM, PAGE_LONG_MM)
+  };
+  PdfPageSpec {
+    width_mm,
+    height_mm,
+    margin_mm: PAGE_MARGIN_MM,
+    dpi: PRINT_DPI,
+    lossless: true,
+  }
+}
+
+fn temp_pdf(project: &CfjProject) -> PathBuf {
+  let id: String = project.id.chars().filter(|c| c.is_ascii_alphanumeric()).take(32).collect();
+  std::env::temp_dir().join(format!("coverflow-print-{}.pdf", id))
+}
+
+// CUPS 的 lp 直接提交到默认打印机；找不到 lp 时返回 None 交给回退流程
+#[cfg(unix)]
+fn spool(path: &Path) -> Option<Result<(), String>> {
+  let output = match std::process::Command::new("lp").arg("--").arg(path).output() {
+    Ok(output) => output,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+    Err(err) => return Some(Err(err.to_string())),
+  };
+  if output.status.success() {
+    log::info!("print_cover: {}", String::from_utf8_lossy(&output.stdout).trim());
+    return Some(Ok(()));
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  // 未配置打印机时 lp 报错，回退到默认程序让用户手动选择
+  if stderr.contains("No default destination") || stderr.contains("no default destination") {
+    log::warn!("print_cover: no default printer, falling back: {}", stderr.trim());
+    return None;
+  }
+  Some(Err(format!("print spooler failed: {}", stderr.trim())))
+}
+
+// Windows 没有免依赖的打印接口，直接走回退
+#[cfg(not(unix))]
+fn spool(_path: &Path) -> Option<Result<(), String>> {
+  None
+}
+
+// 与 export_svg 一致接收封面列表，index 指定打印哪一张
+#[tauri::command]
+pub async fn print_cover(app: AppHandle, projects: Vec<CfjProject>, index: usize) -> Result<(), String> {
+  limits::check(&app, "print_cover", &projects)?;
+  let total = projects.len();
+  let project = projects
+    .into_iter()
+    .nth(index)
+    .ok_or_else(|| format!("cover index {} out of range ({} cover(s))", index, total))?;
+  let path = temp_pdf(&project);
+  let spooled = {
+    let path = path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+      pdf::export_to(std::slice::from_ref(&project), &page_for(&project), &path).map_err(|err| err.to_string())?;
+      Ok::<_, String>(spool(&path))
+    })
+    .await
+    .map_err(|err| err.to_string())??
+  };
+  match spooled {
+    Some(result) => {
+      // 已提交到队列，临时文件不再需要
+      let _ = std::fs::remove_file(&path);
+      result
+    }
+    None => {
+      // 用默认 PDF 阅读器打开，由用户在其中打印；文件留在临时目录
+      log::info!("print_cover: opening {}", path.display());
+      app
+        .opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|err| err.to_string())
+    }
+  }
+}
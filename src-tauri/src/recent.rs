@@ -0,0 +1,141 @@
+// 模块：最近打开的项目文件（持久化到应用配置目录）
+use crate::{atomic, menu, pending};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const RECENT_FILE: &str = "recent.json";
+const MAX_RECENT: usize = 10;
+
+static RECENT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentEntry {
+  pub path: String,
+  pub opened_at: u64,
+}
+
+fn recent_file(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(RECENT_FILE))
+}
+
+// 文件缺失或损坏时从空列表开始
+fn read_entries(file: &Path) -> Vec<RecentEntry> {
+  std::fs::read_to_string(file)
+    .ok()
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_entries(file: &Path, entries: &[RecentEntry]) -> std::io::Result<()> {
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir)?;
+  }
+  let bytes = serde_json::to_vec_pretty(entries)?;
+  atomic::write_with(file, |handle| handle.write_all(&bytes))
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+fn normalize(path: &str) -> String {
+  std::fs::canonicalize(path)
+    .map(|p| p.to_string_lossy().into_owned())
+    .unwrap_or_else(|_| path.to_string())
+}
+
+fn insert_entry(mut entries: Vec<RecentEntry>, path: String, opened_at: u64) -> Vec<RecentEntry> {
+  entries.retain(|entry| entry.path != path);
+  entries.insert(0, RecentEntry { path, opened_at });
+  entries.sort_by_key(|entry| std::cmp::Reverse(entry.opened_at));
+  entries.truncate(MAX_RECENT);
+  entries
+}
+
+pub fn push(app: &AppHandle, path: &str) {
+  let Some(file) = recent_file(app) else {
+    return;
+  };
+  let guard = RECENT_LOCK.lock();
+  let entries = insert_entry(read_entries(&file), normalize(path), now_ms());
+  if let Err(err) = write_entries(&file, &entries) {
+    log::warn!("recent files not saved: {}: {}", file.display(), err);
+  }
+  drop(guard);
+  menu::refresh_recent(app);
+}
+
+// 读取时过滤掉已不存在的文件，但不从记录中删除
+pub fn list(app: &AppHandle) -> Vec<RecentEntry> {
+  let Some(file) = recent_file(app) else {
+    return Vec::new();
+  };
+  let _guard = RECENT_LOCK.lock();
+  let mut entries = read_entries(&file);
+  entries.retain(|entry| Path::new(&entry.path).is_file());
+  entries.sort_by_key(|entry| std::cmp::Reverse(entry.opened_at));
+  entries
+}
+
+// 按规范路径比较
+pub fn contains(app: &AppHandle, path: &str) -> bool {
+  let path = normalize(path);
+  list(app).iter().any(|entry| normalize(&entry.path) == path)
+}
+
+// 首页预览与缩略图只读取最近列表中的项目文件，且按打开请求的规则校验，返回规范路径
+pub fn require(app: &AppHandle, path: &str) -> Result<String, String> {
+  if !contains(app, path) {
+    return Err(format!("not a recent project: {}", path));
+  }
+  pending::validate_path(path).map_err(|err| format!("{}: {}", path, err))
+}
+
+#[tauri::command]
+pub fn get_recent_files(app: AppHandle) -> Vec<RecentEntry> {
+  list(&app)
+}
+
+#[tauri::command]
+pub fn push_recent_file(app: AppHandle, path: String) {
+  push(&app, &path);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn entries_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config").join(RECENT_FILE);
+    assert!(read_entries(&file).is_empty());
+    let mut entries = Vec::new();
+    for (index, path) in ["a.cfj", "b.cfjz", "a.cfj"].iter().enumerate() {
+      entries = insert_entry(entries, path.to_string(), index as u64);
+    }
+    write_entries(&file, &entries).unwrap();
+    let read = read_entries(&file);
+    assert_eq!(read, entries);
+    // 重复打开只保留最新的一条
+    let paths: Vec<&str> = read.iter().map(|entry| entry.path.as_str()).collect();
+    assert_eq!(paths, ["a.cfj", "b.cfjz"]);
+  }
+
+  #[test]
+  fn keeps_most_recent_entries() {
+    let entries = (0..MAX_RECENT as u64 + 5).fold(Vec::new(), |entries, index| {
+      insert_entry(entries, format!("{}.cfj", index), index)
+    });
+    assert_eq!(entries.len(), MAX_RECENT);
+    assert_eq!(entries[0].path, format!("{}.cfj", MAX_RECENT + 4));
+  }
+}
@@ -0,0 +1,241 @@
+// 模块：覆盖或删除项目文件前先移到系统回收站；回收站不可用时改名为同目录下带时间戳的 .bak 备份
+use crate::{atomic, cfj, naming};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+// 另存为覆盖了已有文件时发出，载荷为 Displaced
+pub const REPLACED_EVENT: &str = "file://replaced";
+
+const SETTINGS_FILE: &str = "trash.json";
+
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashSettings {
+  // 关闭后另存为直接覆盖已有文件
+  #[serde(default = "default_true")]
+  pub use_trash: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+impl Default for TrashSettings {
+  fn default() -> Self {
+    TrashSettings { use_trash: true }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Disposal {
+  Trash,
+  Backup,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Displaced {
+  pub path: String,
+  pub method: Disposal,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub backup_path: Option<String>,
+  // 回退为备份时记录回收站失败的原因
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub trash_error: Option<String>,
+}
+
+fn settings_file(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> TrashSettings {
+  settings_file(app)
+    .and_then(|file| std::fs::read_to_string(file).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_settings(app: &AppHandle, settings: &TrashSettings) -> Result<(), String> {
+  let file = settings_file(app).ok_or_else(|| "config dir unavailable".to_string())?;
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+  }
+  let bytes = serde_json::to_vec_pretty(settings).map_err(|err| err.to_string())?;
+  atomic::write_with(&file, |handle| handle.write_all(&bytes)).map_err(|err| err.to_string())
+}
+
+// cover.cfj -> cover.cfj.20260101-120000.bak（UTC），同一秒内重复时追加序号
+pub fn backup_path(path: &Path, time: SystemTime) -> PathBuf {
+  let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+  let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) % 86_400;
+  let stamp = format!(
+    "{}-{:02}{:02}{:02}",
+    naming::date_string(time).replace('-', ""),
+    secs / 3600,
+    secs / 60 % 60,
+    secs % 60
+  );
+  let mut candidate = path.with_file_name(format!("{}.{}.bak", name, stamp));
+  let mut suffix = 2;
+  while candidate.exists() {
+    candidate = path.with_file_name(format!("{}.{}-{}.bak", name, stamp, suffix));
+    suffix += 1;
+  }
+  candidate
+}
+
+// trasher 失败（平台没有回收站、文件所在卷不支持等）时改为备份
+pub fn displace_with<F>(path: &Path, trasher: F) -> Result<Displaced, String>
+where
+  F: FnOnce(&Path) -> Result<(), String>,
+{
+  let trash_error = match trasher(path) {
+    Ok(()) => {
+      return Ok(Displaced {
+        path: path.to_string_lossy().into_owned(),
+        method: Disposal::Trash,
+        backup_path: None,
+        trash_error: None,
+      })
+    }
+    Err(err) => err,
+  };
+  let backup = backup_path(path, SystemTime::now());
+  std::fs::rename(path, &backup).map_err(|err| {
+    format!(
+      "cannot move {} to trash ({}) or to a backup: {}",
+      path.display(),
+      trash_error,
+      err
+    )
+  })?;
+  Ok(Displaced {
+    path: path.to_string_lossy().into_owned(),
+    method: Disposal::Backup,
+    backup_path: Some(backup.to_string_lossy().into_owned()),
+    trash_error: Some(trash_error),
+  })
+}
+
+pub fn displace(path: &Path) -> Result<Displaced, String> {
+  displace_with(path, |path| trash::delete(path).map_err(|err| err.to_string()))
+}
+
+// 写入失败时把备份改回原名；已进回收站的文件需用户自行还原
+pub fn restore(displaced: &Displaced) {
+  let Some(backup) = &displaced.backup_path else {
+    log::warn!("save failed after moving {} to trash", displaced.path);
+    return;
+  };
+  if let Err(err) = std::fs::rename(backup, &displaced.path) {
+    log::error!("backup not restored: {} -> {}: {}", backup, displaced.path, err);
+  }
+}
+
+// 另存为覆盖已有文件前调用；设置关闭、目标不存在或不可写（由保存本身报错）时返回 None
+pub fn before_overwrite(app: &AppHandle, path: &Path) -> Result<Option<Displaced>, String> {
+  if !path.is_file() || !read_settings(app).use_trash || atomic::check_writable(path).is_err() {
+    return Ok(None);
+  }
+  let displaced = displace(path)?;
+  log::info!("replacing {}: existing file moved to {:?}", path.display(), displaced.method);
+  Ok(Some(displaced))
+}
+
+// 写入成功后通知前端，备份时提示备份位置
+pub fn report(app: &AppHandle, displaced: Option<Displaced>) {
+  if let Some(displaced) = displaced {
+    let _ = app.emit(REPLACED_EVENT, displaced);
+  }
+}
+
+// 最近列表中删除项目文件；只接受 .cfj / .cfjz，与覆盖时的设置无关
+#[tauri::command]
+pub fn move_to_trash(path: String) -> Result<Displaced, String> {
+  let target = Path::new(&path);
+  if !cfj::is_project_path(target) || !target.is_file() {
+    return Err(format!("not a project file: {}", path));
+  }
+  let displaced = displace(target)?;
+  log::info!("move_to_trash: {} ({:?})", path, displaced.method);
+  Ok(displaced)
+}
+
+#[tauri::command]
+pub fn get_trash_settings(app: AppHandle) -> TrashSettings {
+  let _guard = SETTINGS_LOCK.lock();
+  read_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_use_trash(app: AppHandle, enabled: bool) -> Result<(), String> {
+  let _guard = SETTINGS_LOCK.lock();
+  let mut settings = read_settings(&app);
+  settings.use_trash = enabled;
+  write_settings(&app, &settings)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn project_file(dir: &Path) -> PathBuf {
+    let path = dir.join("cover.cfj");
+    std::fs::write(&path, "{}").unwrap();
+    path
+  }
+
+  #[test]
+  fn trash_success_leaves_no_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = project_file(dir.path());
+    let displaced = displace_with(&path, |target| std::fs::remove_file(target).map_err(|err| err.to_string())).unwrap();
+    assert_eq!(displaced.method, Disposal::Trash);
+    assert_eq!(displaced.backup_path, None);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+  }
+
+  #[test]
+  fn trash_failure_falls_back_to_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = project_file(dir.path());
+    let displaced = displace_with(&path, |_| Err("no trash here".to_string())).unwrap();
+    assert_eq!(displaced.method, Disposal::Backup);
+    assert_eq!(displaced.trash_error.as_deref(), Some("no trash here"));
+    let backup = PathBuf::from(displaced.backup_path.clone().unwrap());
+    assert!(!path.exists());
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{}");
+
+    // 写入失败时改回原名
+    restore(&displaced);
+    assert!(path.exists() && !backup.exists());
+  }
+
+  #[test]
+  fn reports_both_failures() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.cfj");
+    let err = displace_with(&missing, |_| Err("no trash here".to_string())).unwrap_err();
+    assert!(err.contains("no trash here"), "{}", err);
+  }
+
+  #[test]
+  fn backup_names_are_timestamped_and_unique() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    // 2024-02-29 13:05:09 UTC
+    let time = UNIX_EPOCH + Duration::from_secs(19_782 * 86_400 + 13 * 3600 + 5 * 60 + 9);
+    let first = backup_path(&path, time);
+    assert_eq!(first, dir.path().join("cover.cfj.20240229-130509.bak"));
+    std::fs::write(&first, "").unwrap();
+    assert_eq!(backup_path(&path, time), dir.path().join("cover.cfj.20240229-130509-2.bak"));
+  }
+}
@@ -0,0 +1,1055 @@
+// 模块：将项目合成为 SVG 文档（布局规则与前端 LivePreview 一致），供导出与栅格化使用
+use crate::cfj::{BackgroundType, CfjProject, FontWeight, Layer, LayerType};
+use base64::Engine;
+use std::fmt::Write;
+
+const TEXT_PADDING: f64 = 8.0;
+const LINE_HEIGHT: f64 = 1.1;
+const DEFAULT_DECORATION_COLOR: &str = "#38bdf8";
+
+pub struct ComposedSvg {
+  pub markup: String,
+  pub warnings: Vec<String>,
+  // 用了 SVG 无法表达的效果的图层 id，导出 SVG 时改为栅格化嵌入
+  pub unsupported: Vec<String>,
+}
+
+struct Composer {
+  defs: String,
+  body: String,
+  warnings: Vec<String>,
+  unsupported: Vec<String>,
+  next_id: usize,
+}
+
+impl Composer {
+  fn id(&mut self, prefix: &str) -> String {
+    self.next_id += 1;
+    format!("cf-{}-{}", prefix, self.next_id)
+  }
+
+  fn warn(&mut self, layer: &Layer, detail: &str) {
+    self.warnings.push(format!("layer \"{}\": {}", layer.name, detail));
+  }
+
+  fn unsupported(&mut self, layer: &Layer, detail: &str) {
+    self.warn(layer, detail);
+    if !self.unsupported.contains(&layer.id) {
+      self.unsupported.push(layer.id.clone());
+    }
+  }
+}
+
+pub fn escape(value: &str) -> String {
+  let mut out = String::with_capacity(value.len());
+  for ch in value.chars() {
+    match ch {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' => out.push_str("&quot;"),
+      '\'' => out.push_str("&apos;"),
+      _ => out.push(ch),
+    }
+  }
+  out
+}
+
+fn num(value: f64) -> String {
+  if !value.is_finite() {
+    return "0".to_string();
+  }
+  let rounded = (value * 1000.0).round() / 1000.0;
+  if rounded == rounded.trunc() {
+    format!("{}", rounded as i64)
+  } else {
+    format!("{}", rounded)
+  }
+}
+
+// 按顶层逗号拆分（忽略括号内的逗号）
+fn split_top_level(value: &str, separator: char) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut depth = 0i32;
+  let mut current = String::new();
+  for ch in value.chars() {
+    match ch {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => {}
+    }
+    if ch == separator && depth == 0 {
+      parts.push(current.trim().to_string());
+      current.clear();
+    } else {
+      current.push(ch);
+    }
+  }
+  if !current.trim().is_empty() {
+    parts.push(current.trim().to_string());
+  }
+  parts
+}
+
+fn split_whitespace_top_level(value: &str) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut depth = 0i32;
+  let mut current = String::new();
+  for ch in value.chars() {
+    match ch {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => {}
+    }
+    if ch.is_whitespace() && depth == 0 {
+      if !current.is_empty() {
+        parts.push(std::mem::take(&mut current));
+      }
+    } else {
+      current.push(ch);
+    }
+  }
+  if !current.is_empty() {
+    parts.push(current);
+  }
+  parts
+}
+
+fn parse_length(value: &str, basis: f64) -> Option<f64> {
+  let value = value.trim();
+  if let Some(percent) = value.strip_suffix('%') {
+    return percent.trim().parse::<f64>().ok().map(|p| p / 100.0 * basis);
+  }
+  if let Some(px) = value.strip_suffix("px") {
+    return px.trim().parse::<f64>().ok();
+  }
+  value.parse::<f64>().ok()
+}
+
+fn parse_angle(value: &str) -> Option<f64> {
+  let value = value.trim();
+  if let Some(deg) = value.strip_suffix("deg") {
+    return deg.parse::<f64>().ok();
+  }
+  if let Some(turn) = value.strip_suffix("turn") {
+    return turn.parse::<f64>().ok().map(|t| t * 360.0);
+  }
+  if let Some(rad) = value.strip_suffix("rad") {
+    return rad.parse::<f64>().ok().map(|r| r.to_degrees());
+  }
+  None
+}
+
+#[derive(Clone, Copy)]
+pub struct Rect {
+  pub x: f64,
+  pub y: f64,
+  pub w: f64,
+  pub h: f64,
+}
+
+struct Stop {
+  color: String,
+  position: Option<f64>,
+}
+
+// 解析色标，position 为渐变线上的像素长度
+fn parse_stops(args: &[String], line_length: f64) -> Vec<Stop> {
+  let mut stops = Vec::new();
+  for arg in args {
+    let tokens = split_whitespace_top_level(arg);
+    let Some(color) = tokens.first() else {
+      continue;
+    };
+    let positions: Vec<f64> = tokens[1..]
+      .iter()
+      .filter_map(|token| parse_length(token, line_length))
+      .collect();
+    if positions.is_empty() {
+      stops.push(Stop { color: color.clone(), position: None });
+    }
+    for position in positions {
+      stops.push(Stop { color: color.clone(), position: Some(position) });
+    }
+  }
+  stops
+}
+
+// CSS 规则：缺省位置在相邻已知位置间均分，且位置单调不减
+fn resolve_stops(stops: &[Stop], line_length: f64) -> Vec<(String, f64)> {
+  let count = stops.len();
+  let mut positions: Vec<Option<f64>> = stops.iter().map(|stop| stop.position).collect();
+  if count == 0 {
+    return Vec::new();
+  }
+  if positions[0].is_none() {
+    positions[0] = Some(0.0);
+  }
+  if positions[count - 1].is_none() {
+    positions[count - 1] = Some(line_length);
+  }
+  let mut max_so_far = f64::MIN;
+  for position in positions.iter_mut().flatten() {
+    if *position < max_so_far {
+      *position = max_so_far;
+    }
+    max_so_far = *position;
+  }
+  let mut index = 0;
+  while index < count {
+    if positions[index].is_some() {
+      index += 1;
+      continue;
+    }
+    let start = index - 1;
+    let mut end = index;
+    while positions[end].is_none() {
+      end += 1;
+    }
+    let from = positions[start].unwrap_or(0.0);
+    let to = positions[end].unwrap_or(line_length);
+    let steps = (end - start) as f64;
+    for (offset, slot) in positions[start + 1..end].iter_mut().enumerate() {
+      *slot = Some(from + (to - from) * (offset + 1) as f64 / steps);
+    }
+    index = end;
+  }
+  stops
+    .iter()
+    .zip(positions)
+    .map(|(stop, position)| (stop.color.clone(), position.unwrap_or(0.0)))
+    .collect()
+}
+
+fn write_stops(defs: &mut String, stops: &[(String, f64)], length: f64) {
+  for (color, position) in stops {
+    let offset = if length > 0.0 { (position / length).clamp(0.0, 1.0) } else { 0.0 };
+    let _ = write!(defs, "<stop offset=\"{}\" stop-color=\"{}\"/>", num(offset), escape(color));
+  }
+}
+
+fn linear_direction(arg: &str, rect: Rect) -> Option<(f64, f64)> {
+  if let Some(angle) = parse_angle(arg) {
+    let radians = angle.to_radians();
+    return Some((radians.sin(), -radians.cos()));
+  }
+  let keywords = arg.trim().strip_prefix("to ")?;
+  let mut dx = 0.0;
+  let mut dy = 0.0;
+  for keyword in keywords.split_whitespace() {
+    match keyword {
+      "left" => dx = -1.0,
+      "right" => dx = 1.0,
+      "top" => dy = -1.0,
+      "bottom" => dy = 1.0,
+      _ => return None,
+    }
+  }
+  // 角落方向：渐变线垂直于另外两个角的连线
+  let (dx, dy) = if dx != 0.0 && dy != 0.0 {
+    (dx * rect.h, dy * rect.w)
+  } else {
+    (dx, dy)
+  };
+  let length = (dx * dx + dy * dy).sqrt();
+  if length == 0.0 {
+    return None;
+  }
+  Some((dx / length, dy / length))
+}
+
+fn position_keyword(value: &str, basis: f64) -> Option<f64> {
+  match value {
+    "left" | "top" => Some(0.0),
+    "center" => Some(basis / 2.0),
+    "right" | "bottom" => Some(basis),
+    _ => parse_length(value, basis),
+  }
+}
+
+// 将 CSS 渐变转换为 SVG 渐变定义，返回 paint 引用
+fn gradient_paint(composer: &mut Composer, value: &str, rect: Rect) -> Option<String> {
+  let value = value.trim();
+  let open = value.find('(')?;
+  let name = value[..open].trim().to_lowercase();
+  let inner = value[open + 1..].strip_suffix(')')?;
+  let args = split_top_level(inner, ',');
+  if args.is_empty() {
+    return None;
+  }
+  let repeating = name.starts_with("repeating-");
+  let id = composer.id("grad");
+
+  match name.trim_start_matches("repeating-") {
+    "linear-gradient" => {
+      let (direction, stop_args) = match linear_direction(&args[0], rect) {
+        Some(direction) => (direction, &args[1..]),
+        None => ((0.0, 1.0), &args[..]),
+      };
+      let (dx, dy) = direction;
+      let line_length = (rect.w * dx).abs() + (rect.h * dy).abs();
+      let stops = resolve_stops(&parse_stops(stop_args, line_length), line_length);
+      let cx = rect.x + rect.w / 2.0;
+      let cy = rect.y + rect.h / 2.0;
+      let x1 = cx - dx * line_length / 2.0;
+      let y1 = cy - dy * line_length / 2.0;
+      // 重复渐变：把渐变向量缩短到一个周期，再用 spreadMethod=repeat 平铺
+      let period = if repeating {
+        stops.last().map(|(_, position)| *position).unwrap_or(line_length)
+      } else {
+        line_length
+      };
+      if period <= 0.0 {
+        return None;
+      }
+      let _ = write!(
+        composer.defs,
+        "<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}>",
+        id,
+        num(x1),
+        num(y1),
+        num(x1 + dx * period),
+        num(y1 + dy * period),
+        if repeating { " spreadMethod=\"repeat\"" } else { "" }
+      );
+      write_stops(&mut composer.defs, &stops, period);
+      composer.defs.push_str("</linearGradient>");
+    }
+    "radial-gradient" => {
+      let first = args[0].to_lowercase();
+      let is_shape = first.contains("circle")
+        || first.contains("ellipse")
+        || first.contains("at ")
+        || first.starts_with("at")
+        || first.contains("closest")
+        || first.contains("farthest");
+      let circle = is_shape && first.contains("circle");
+      let mut cx = rect.w / 2.0;
+      let mut cy = rect.h / 2.0;
+      if is_shape {
+        if let Some(at) = first.split("at ").nth(1) {
+          let parts: Vec<&str> = at.split_whitespace().collect();
+          let (mut horizontal, mut vertical) = (parts.first().copied(), parts.get(1).copied());
+          if matches!(horizontal, Some("top") | Some("bottom")) {
+            std::mem::swap(&mut horizontal, &mut vertical);
+          }
+          if let Some(h) = horizontal.and_then(|value| position_keyword(value, rect.w)) {
+            cx = h;
+          }
+          if let Some(v) = vertical.and_then(|value| position_keyword(value, rect.h)) {
+            cy = v;
+          }
+        }
+      }
+      let far_x = cx.max(rect.w - cx);
+      let far_y = cy.max(rect.h - cy);
+      let (rx, ry) = if circle {
+        let r = (far_x * far_x + far_y * far_y).sqrt();
+        (r, r)
+      } else {
+        (far_x * std::f64::consts::SQRT_2, far_y * std::f64::consts::SQRT_2)
+      };
+      if rx <= 0.0 || ry <= 0.0 {
+        return None;
+      }
+      let stop_args = if is_shape { &args[1..] } else { &args[..] };
+      let stops = resolve_stops(&parse_stops(stop_args, rx), rx);
+      let period = if repeating {
+        stops.last().map(|(_, position)| *position).unwrap_or(rx)
+      } else {
+        rx
+      };
+      if period <= 0.0 {
+        return None;
+      }
+      let (ax, ay) = (rect.x + cx, rect.y + cy);
+      let _ = write!(
+        composer.defs,
+        "<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\" gradientTransform=\"translate({} {}) scale(1 {}) translate({} {})\"{}>",
+        id,
+        num(ax),
+        num(ay),
+        num(period),
+        num(ax),
+        num(ay),
+        num(ry / rx),
+        num(-ax),
+        num(-ay),
+        if repeating { " spreadMethod=\"repeat\"" } else { "" }
+      );
+      write_stops(&mut composer.defs, &stops, period);
+      composer.defs.push_str("</radialGradient>");
+    }
+    _ => return None,
+  }
+  Some(format!("url(#{})", id))
+}
+
+// 绘制一组 CSS 背景层（第一层在最上）
+fn paint_backgrounds(composer: &mut Composer, value: &str, rect: Rect, shape_attrs: &str) -> bool {
+  let mut painted = true;
+  for layer in split_top_level(value, ',').iter().rev() {
+    let paint = if layer.contains('(') && layer.to_lowercase().contains("gradient") {
+      gradient_paint(composer, layer, rect)
+    } else if layer.eq_ignore_ascii_case("none") {
+      continue;
+    } else {
+      Some(escape(layer))
+    };
+    match paint {
+      Some(paint) => {
+        let _ = write!(
+          composer.body,
+          "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{} fill=\"{}\"/>",
+          num(rect.x),
+          num(rect.y),
+          num(rect.w),
+          num(rect.h),
+          shape_attrs,
+          paint
+        );
+      }
+      None => painted = false,
+    }
+  }
+  painted
+}
+
+fn compose_background(composer: &mut Composer, project: &CfjProject, width: f64, height: f64) {
+  let bg = &project.background;
+  let rect = Rect { x: 0.0, y: 0.0, w: width, h: height };
+  match bg.background_type {
+    BackgroundType::Color => {
+      let _ = write!(
+        composer.body,
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        num(width),
+        num(height),
+        escape(&bg.value)
+      );
+    }
+    BackgroundType::Gradient => {
+      if !paint_backgrounds(composer, &bg.value, rect, "") {
+        composer.warnings.push(format!("background: unsupported gradient \"{}\"", bg.value));
+      }
+    }
+    BackgroundType::Image => {
+      if is_remote(&bg.value) {
+        composer.warnings.push("background: remote images are not embedded".to_string());
+      }
+      let _ = write!(
+        composer.body,
+        "<image width=\"{}\" height=\"{}\" preserveAspectRatio=\"xMidYMid slice\" href=\"{}\"/>",
+        num(width),
+        num(height),
+        escape(bg.value.trim())
+      );
+    }
+  }
+  compose_overlay(composer, project, width, height);
+}
+
+// 叠加纹理：与 buildBackgroundStyles 的 dots/grid/stripes 保持一致
+fn compose_overlay(composer: &mut Composer, project: &CfjProject, width: f64, height: f64) {
+  let bg = &project.background;
+  if bg.overlay_type == "none" || bg.overlay_type.is_empty() {
+    return;
+  }
+  let size = if bg.overlay_scale > 0.0 { bg.overlay_scale } else { 20.0 };
+  let (color, opacity) = if bg.overlay_color.starts_with('#') {
+    (bg.overlay_color.clone(), bg.overlay_opacity.clamp(0.0, 1.0))
+  } else {
+    (bg.overlay_color.clone(), 1.0)
+  };
+  let fill = format!("fill=\"{}\" fill-opacity=\"{}\"", escape(&color), num(opacity));
+  let tile = match bg.overlay_type.as_str() {
+    "dots" => format!("<circle cx=\"{}\" cy=\"{}\" r=\"2\" {}/>", num(size / 2.0), num(size / 2.0), fill),
+    "grid" => format!(
+      "<rect width=\"{}\" height=\"1\" {}/><rect width=\"1\" height=\"{}\" {}/>",
+      num(size),
+      fill,
+      num(size),
+      fill
+    ),
+    "stripes" => {
+      let tile_rect = Rect { x: 0.0, y: 0.0, w: size, h: size };
+      let css = format!(
+        "repeating-linear-gradient(45deg, {c}, {c} 2px, transparent 2px, transparent {}px)",
+        num(size / 2.0),
+        c = color
+      );
+      match gradient_paint(composer, &css, tile_rect) {
+        Some(paint) => format!(
+          "<rect width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+          num(size),
+          num(size),
+          paint,
+          num(opacity)
+        ),
+        None => return,
+      }
+    }
+    other => {
+      composer.warnings.push(format!("background: unknown overlay \"{}\"", other));
+      return;
+    }
+  };
+  let id = composer.id("pattern");
+  let _ = write!(
+    composer.defs,
+    "<pattern id=\"{}\" patternUnits=\"userSpaceOnUse\" width=\"{}\" height=\"{}\">{}</pattern>",
+    id,
+    num(size),
+    num(size),
+    tile
+  );
+  let _ = write!(
+    composer.body,
+    "<rect width=\"{}\" height=\"{}\" fill=\"url(#{})\"/>",
+    num(width),
+    num(height),
+    id
+  );
+}
+
+pub fn is_remote(href: &str) -> bool {
+  let lower = href.trim().to_lowercase();
+  lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn font_weight(layer: &Layer) -> String {
+  match &layer.font_weight {
+    Some(FontWeight::Number(weight)) => num(*weight),
+    Some(FontWeight::Name(name)) => name.clone(),
+    None => "bold".to_string(),
+  }
+}
+
+fn compose_text(composer: &mut Composer, layer: &Layer) {
+  let font_size = layer.font_size.unwrap_or_else(|| (layer.height * 0.7).max(12.0));
+  let family = layer.font_family.clone().unwrap_or_else(|| "Inter, sans-serif".to_string());
+  let align = layer.text_align.as_deref().unwrap_or("center");
+  let vertical = layer.writing_mode.as_deref() == Some("vertical");
+  let line_height = font_size * LINE_HEIGHT;
+  let lines: Vec<&str> = layer.content.split('\n').collect();
+  let block = line_height * lines.len() as f64;
+  let rect = Rect { x: 0.0, y: 0.0, w: layer.width, h: layer.height };
+
+  let fill = match &layer.text_gradient {
+    Some(gradient) if gradient.enabled => {
+      let css = format!("linear-gradient({}deg, {}, {})", num(gradient.angle), gradient.from, gradient.to);
+      gradient_paint(composer, &css, rect).unwrap_or_else(|| escape(&gradient.from))
+    }
+    _ => escape(layer.color.as_deref().unwrap_or("#ffffff")),
+  };
+
+  let mut filter = String::new();
+  if let Some(shadow) = &layer.text_shadow {
+    if shadow.enabled {
+      let id = composer.id("shadow");
+      let _ = write!(
+        composer.defs,
+        "<filter id=\"{}\" x=\"-50%\" y=\"-50%\" width=\"200%\" height=\"200%\"><feDropShadow dx=\"{}\" dy=\"{}\" stdDeviation=\"{}\" flood-color=\"{}\"/></filter>",
+        id,
+        num(shadow.offset_x),
+        num(shadow.offset_y),
+        num(shadow.blur / 2.0),
+        escape(&shadow.color)
+      );
+      filter = format!(" filter=\"url(#{})\"", id);
+    }
+  }
+
+  let _ = write!(
+    composer.body,
+    "<text font-family=\"{}\" font-size=\"{}\" font-weight=\"{}\" font-style=\"{}\" fill=\"{}\"{}",
+    escape(&family),
+    num(font_size),
+    escape(&font_weight(layer)),
+    escape(layer.font_style.as_deref().unwrap_or("normal")),
+    fill,
+    filter
+  );
+
+  if vertical {
+    // 竖排：列从右向左排列，整体按 textAlign 水平放置、垂直居中
+    let (anchor, y) = match align {
+      "left" => ("start", TEXT_PADDING),
+      "right" => ("end", layer.height - TEXT_PADDING),
+      _ => ("middle", layer.height / 2.0),
+    };
+    let block_right = match align {
+      "left" => block,
+      "right" => layer.width,
+      _ => (layer.width + block) / 2.0,
+    };
+    let _ = write!(
+      composer.body,
+      " writing-mode=\"tb\" text-anchor=\"{}\" style=\"text-orientation:upright\">",
+      anchor
+    );
+    for (index, line) in lines.iter().enumerate() {
+      let x = block_right - line_height * (index as f64 + 0.5);
+      let _ = write!(composer.body, "<tspan x=\"{}\" y=\"{}\">{}</tspan>", num(x), num(y), escape(line));
+    }
+  } else {
+    let (anchor, x) = match align {
+      "left" => ("start", TEXT_PADDING),
+      "right" => ("end", layer.width - TEXT_PADDING),
+      _ => ("middle", layer.width / 2.0),
+    };
+    let _ = write!(composer.body, " text-anchor=\"{}\" dominant-baseline=\"central\">", anchor);
+    let top = (layer.height - block) / 2.0;
+    for (index, line) in lines.iter().enumerate() {
+      let y = top + line_height * (index as f64 + 0.5);
+      let _ = write!(composer.body, "<tspan x=\"{}\" y=\"{}\">{}</tspan>", num(x), num(y), escape(line));
+    }
+  }
+  composer.body.push_str("</text>");
+}
+
+struct SvgTag {
+  end: usize,
+  attrs: Vec<(String, String)>,
+}
+
+// 解析第一个 <svg> 开始标签的属性
+fn parse_svg_tag(content: &str) -> Option<SvgTag> {
+  let bytes = content.as_bytes();
+  // 按字节忽略大小写查找，to_lowercase 会改变非 ASCII 字符的字节长度
+  let start = bytes.windows(4).position(|window| window.eq_ignore_ascii_case(b"<svg"))?;
+  let mut index = start + 4;
+  let mut attrs = Vec::new();
+  loop {
+    while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+      index += 1;
+    }
+    if index >= bytes.len() {
+      return None;
+    }
+    if bytes[index] == b'>' || content[index..].starts_with("/>") {
+      let end = index + content[index..].find('>')? + 1;
+      return Some(SvgTag { end, attrs });
+    }
+    let name_start = index;
+    while index < bytes.len() && !matches!(bytes[index], b'=' | b'>' | b'/') && !bytes[index].is_ascii_whitespace() {
+      index += 1;
+    }
+    let name = content[name_start..index].to_string();
+    while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+      index += 1;
+    }
+    let mut value = String::new();
+    if index < bytes.len() && bytes[index] == b'=' {
+      index += 1;
+      while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+        index += 1;
+      }
+      if index < bytes.len() && (bytes[index] == b'"' || bytes[index] == b'\'') {
+        let quote = bytes[index] as char;
+        let value_start = index + 1;
+        let value_end = value_start + content[value_start..].find(quote)?;
+        value = content[value_start..value_end].to_string();
+        index = value_end + 1;
+      } else {
+        let value_start = index;
+        while index < bytes.len() && bytes[index] != b'>' && !bytes[index].is_ascii_whitespace() {
+          index += 1;
+        }
+        value = content[value_start..index].to_string();
+      }
+    }
+    // 没有名字的属性直接丢弃；什么都没读到时跳过一个完整字符，不能切在多字节字符中间
+    if name.is_empty() {
+      if index == name_start {
+        index += content[index..].chars().next().map_or(1, char::len_utf8);
+      }
+      continue;
+    }
+    attrs.push((name, value));
+  }
+}
+
+// 按图层尺寸重写根 svg 的宽高与 preserveAspectRatio（同 applySvgAspectRatio）
+fn fit_svg_markup(content: &str, width: f64, height: f64, ratio_locked: bool) -> String {
+  let Some(tag) = parse_svg_tag(content) else {
+    return format!(
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 100 100\" preserveAspectRatio=\"{}\">{}</svg>",
+      num(width),
+      num(height),
+      if ratio_locked { "xMidYMid meet" } else { "none" },
+      content
+    );
+  };
+  let get = |key: &str| {
+    tag
+      .attrs
+      .iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(key))
+      .map(|(_, value)| value.clone())
+  };
+  let view_box = get("viewBox").unwrap_or_else(|| {
+    let w = get("width").and_then(|v| parse_length(&v, 0.0)).unwrap_or(0.0);
+    let h = get("height").and_then(|v| parse_length(&v, 0.0)).unwrap_or(0.0);
+    if w > 0.0 && h > 0.0 {
+      format!("0 0 {} {}", num(w), num(h))
+    } else {
+      "0 0 100 100".to_string()
+    }
+  });
+  let aspect = if ratio_locked {
+    get("preserveAspectRatio").unwrap_or_else(|| "xMidYMid meet".to_string())
+  } else {
+    "none".to_string()
+  };
+
+  let mut open = String::from("<svg");
+  let skip = ["width", "height", "x", "y", "viewbox", "preserveaspectratio"];
+  for (name, value) in &tag.attrs {
+    if skip.contains(&name.to_lowercase().as_str()) || name.to_lowercase().starts_with("on") {
+      continue;
+    }
+    let _ = write!(open, " {}=\"{}\"", name, value.replace('"', "&quot;"));
+  }
+  if get("xmlns").is_none() {
+    open.push_str(" xmlns=\"http://www.w3.org/2000/svg\"");
+  }
+  let _ = write!(
+    open,
+    " width=\"{}\" height=\"{}\" viewBox=\"{}\" preserveAspectRatio=\"{}\">",
+    num(width),
+    num(height),
+    escape(&view_box),
+    escape(&aspect)
+  );
+  format!("{}{}", open, &content[tag.end..])
+}
+
+fn compose_svg_layer(composer: &mut Composer, layer: &Layer) {
+  let color = layer.color.as_deref().unwrap_or("#ffffff");
+  let content = layer.content.replace("currentColor", color);
+  let markup = fit_svg_markup(&content, layer.width, layer.height, layer.ratio_locked.unwrap_or(false));
+  // 以独立图片嵌入，单个图层的 SVG 出错不影响整张封面
+  let encoded = base64::engine::general_purpose::STANDARD.encode(markup.as_bytes());
+  let _ = write!(
+    composer.body,
+    "<image width=\"{}\" height=\"{}\" href=\"data:image/svg+xml;base64,{}\"/>",
+    num(layer.width),
+    num(layer.height),
+    encoded
+  );
+}
+
+fn compose_image_layer(composer: &mut Composer, layer: &Layer) {
+  if is_remote(&layer.content) {
+    composer.warn(layer, "remote images are not embedded");
+  }
+  let aspect = if layer.ratio_locked.unwrap_or(false) { "xMidYMid meet" } else { "none" };
+  let _ = write!(
+    composer.body,
+    "<image width=\"{}\" height=\"{}\" preserveAspectRatio=\"{}\" href=\"{}\"/>",
+    num(layer.width),
+    num(layer.height),
+    aspect,
+    escape(layer.content.trim())
+  );
+}
+
+fn parse_declarations(css: &str) -> Vec<(String, String)> {
+  split_top_level(css, ';')
+    .into_iter()
+    .filter_map(|declaration| {
+      let (property, value) = declaration.split_once(':')?;
+      let property = property.trim().to_lowercase();
+      let value = value.trim().to_string();
+      if property.is_empty() || value.is_empty() {
+        return None;
+      }
+      Some((property, value))
+    })
+    .collect()
+}
+
+struct Border {
+  width: f64,
+  color: String,
+}
+
+fn parse_border(value: &str) -> Option<Border> {
+  let mut width = None;
+  let mut color = None;
+  for token in split_whitespace_top_level(value) {
+    if let Some(length) = parse_length(&token, 0.0).filter(|_| token.ends_with("px") || token == "0") {
+      width = Some(length);
+    } else if matches!(token.as_str(), "solid" | "dashed" | "dotted" | "double") {
+      continue;
+    } else if token == "none" {
+      return None;
+    } else {
+      color = Some(token);
+    }
+  }
+  let width = width.unwrap_or(3.0);
+  if width <= 0.0 {
+    return None;
+  }
+  Some(Border { width, color: color.unwrap_or_else(|| "currentColor".to_string()) })
+}
+
+fn parse_polygon(value: &str, rect: Rect) -> Option<String> {
+  let inner = value.trim().strip_prefix("polygon(")?.strip_suffix(')')?;
+  let mut points = Vec::new();
+  for pair in split_top_level(inner, ',') {
+    let coords: Vec<&str> = pair.split_whitespace().collect();
+    if coords.len() != 2 {
+      return None;
+    }
+    let x = parse_length(coords[0], rect.w)?;
+    let y = parse_length(coords[1], rect.h)?;
+    points.push(format!("{},{}", num(x), num(y)));
+  }
+  Some(points.join(" "))
+}
+
+// 装饰图层：支持 background/border/border-radius/outline/box-shadow/clip-path(polygon)/opacity
+fn compose_decoration(composer: &mut Composer, layer: &Layer) {
+  let color = layer.color.as_deref().unwrap_or(DEFAULT_DECORATION_COLOR);
+  let css = layer.content.replace("currentColor", color);
+  let rect = Rect { x: 0.0, y: 0.0, w: layer.width, h: layer.height };
+  let declarations = parse_declarations(&css);
+  let get = |key: &str| {
+    declarations
+      .iter()
+      .rev()
+      .find(|(property, _)| property == key)
+      .map(|(_, value)| value.clone())
+  };
+
+  let radius = get("border-radius")
+    .and_then(|value| {
+      let first = split_whitespace_top_level(&value).into_iter().next()?;
+      let rx = parse_length(&first, rect.w)?;
+      let ry = parse_length(&first, rect.h)?;
+      Some((rx.min(rect.w / 2.0), ry.min(rect.h / 2.0)))
+    })
+    .unwrap_or((0.0, 0.0));
+  let radius_attrs = |rx: f64, ry: f64| {
+    if rx > 0.0 || ry > 0.0 {
+      format!(" rx=\"{}\" ry=\"{}\"", num(rx.max(0.0)), num(ry.max(0.0)))
+    } else {
+      String::new()
+    }
+  };
+
+  let mut open = String::from("<g");
+  if let Some(opacity) = get("opacity").and_then(|value| value.parse::<f64>().ok()) {
+    let _ = write!(open, " opacity=\"{}\"", num(opacity.clamp(0.0, 1.0)));
+  }
+  if let Some(points) = get("clip-path").and_then(|value| parse_polygon(&value, rect)) {
+    let id = composer.id("clip");
+    let _ = write!(composer.defs, "<clipPath id=\"{}\"><polygon points=\"{}\"/></clipPath>", id, points);
+    let _ = write!(open, " clip-path=\"url(#{})\"", id);
+  } else if get("clip-path").is_some() {
+    composer.unsupported(layer, "clip-path shape is not supported in export");
+  }
+  open.push('>');
+  composer.body.push_str(&open);
+
+  if let Some(shadow) = get("box-shadow") {
+    compose_box_shadow(composer, layer, &shadow, rect, radius);
+  }
+
+  let background = get("background")
+    .into_iter()
+    .chain(get("background-color"))
+    .chain(get("background-image"))
+    .collect::<Vec<_>>();
+  let shape = radius_attrs(radius.0, radius.1);
+  for value in background {
+    if !paint_backgrounds(composer, &value, rect, &shape) {
+      composer.unsupported(layer, &format!("unsupported background \"{}\"", value));
+    }
+  }
+
+  if let Some(border) = get("border").and_then(|value| parse_border(&value)) {
+    let inset = border.width / 2.0;
+    let _ = write!(
+      composer.body,
+      "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{} fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+      num(inset),
+      num(inset),
+      num((rect.w - border.width).max(0.0)),
+      num((rect.h - border.width).max(0.0)),
+      radius_attrs(radius.0 - inset, radius.1 - inset),
+      escape(&border.color),
+      num(border.width)
+    );
+  }
+
+  if let Some(outline) = get("outline").and_then(|value| parse_border(&value)) {
+    let offset = get("outline-offset").and_then(|value| parse_length(&value, 0.0)).unwrap_or(0.0);
+    let grow = offset + outline.width / 2.0;
+    let _ = write!(
+      composer.body,
+      "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{} fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+      num(-grow),
+      num(-grow),
+      num((rect.w + grow * 2.0).max(0.0)),
+      num((rect.h + grow * 2.0).max(0.0)),
+      radius_attrs(radius.0 + grow, radius.1 + grow),
+      escape(&outline.color),
+      num(outline.width)
+    );
+  }
+
+  for property in ["filter", "backdrop-filter", "mix-blend-mode"] {
+    if get(property).is_some() {
+      composer.unsupported(layer, &format!("{} is not supported in export", property));
+    }
+  }
+  composer.body.push_str("</g>");
+}
+
+fn compose_box_shadow(composer: &mut Composer, layer: &Layer, value: &str, rect: Rect, radius: (f64, f64)) {
+  let Some(first) = split_top_level(value, ',').into_iter().next() else {
+    return;
+  };
+  let tokens = split_whitespace_top_level(&first);
+  if tokens.iter().any(|token| token == "inset") {
+    composer.unsupported(layer, "inset box-shadow is not supported in export");
+    return;
+  }
+  let lengths: Vec<f64> = tokens.iter().filter_map(|token| parse_length(token, 0.0)).collect();
+  let color = tokens
+    .iter()
+    .find(|token| parse_length(token, 0.0).is_none())
+    .cloned()
+    .unwrap_or_else(|| "rgba(0,0,0,0.5)".to_string());
+  let (dx, dy) = (lengths.first().copied().unwrap_or(0.0), lengths.get(1).copied().unwrap_or(0.0));
+  let blur = lengths.get(2).copied().unwrap_or(0.0);
+  let spread = lengths.get(3).copied().unwrap_or(0.0);
+  let mut filter = String::new();
+  if blur > 0.0 {
+    let id = composer.id("blur");
+    let _ = write!(
+      composer.defs,
+      "<filter id=\"{}\" x=\"-50%\" y=\"-50%\" width=\"200%\" height=\"200%\"><feGaussianBlur stdDeviation=\"{}\"/></filter>",
+      id,
+      num(blur / 2.0)
+    );
+    filter = format!(" filter=\"url(#{})\"", id);
+  }
+  let _ = write!(
+    composer.body,
+    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" ry=\"{}\" fill=\"{}\"{}/>",
+    num(dx - spread),
+    num(dy - spread),
+    num(rect.w + spread * 2.0),
+    num(rect.h + spread * 2.0),
+    num((radius.0 + spread).max(0.0)),
+    num((radius.1 + spread).max(0.0)),
+    escape(&color),
+    filter
+  );
+}
+
+fn compose_layer(composer: &mut Composer, layer: &Layer) {
+  let mut transform = format!("translate({} {})", num(layer.x), num(layer.y));
+  if layer.rotation != 0.0 {
+    let _ = write!(
+      transform,
+      " rotate({} {} {})",
+      num(layer.rotation),
+      num(layer.width / 2.0),
+      num(layer.height / 2.0)
+    );
+  }
+  let _ = write!(
+    composer.body,
+    "<g transform=\"{}\" opacity=\"{}\">",
+    transform,
+    num(layer.opacity.clamp(0.0, 1.0))
+  );
+  match layer.layer_type {
+    LayerType::Svg => compose_svg_layer(composer, layer),
+    LayerType::Text => compose_text(composer, layer),
+    LayerType::Image => compose_image_layer(composer, layer),
+    LayerType::Decoration => compose_decoration(composer, layer),
+    LayerType::Group => {}
+  }
+  composer.body.push_str("</g>");
+}
+
+// 图层顺序：过滤隐藏与分组图层后按 zIndex 升序绘制
+pub fn visible_layers(project: &CfjProject) -> Vec<&Layer> {
+  let mut layers: Vec<&Layer> = project
+    .layers
+    .iter()
+    .filter(|layer| layer.visible && layer.layer_type != LayerType::Group)
+    .collect();
+  layers.sort_by(|a, b| a.z_index.partial_cmp(&b.z_index).unwrap_or(std::cmp::Ordering::Equal));
+  layers
+}
+
+pub fn compose(project: &CfjProject) -> ComposedSvg {
+  let width = project.canvas_config.width.max(1.0);
+  let height = project.canvas_config.height.max(1.0);
+  let mut composer = Composer {
+    defs: String::new(),
+    body: String::new(),
+    warnings: Vec::new(),
+    unsupported: Vec::new(),
+    next_id: 0,
+  };
+
+  compose_background(&mut composer, project, width, height);
+  for layer in visible_layers(project) {
+    compose_layer(&mut composer, layer);
+  }
+
+  let markup = format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\"><title>{}</title><defs>{}</defs>{}</svg>",
+    escape(&project.title),
+    composer.defs,
+    composer.body,
+    w = num(width),
+    h = num(height)
+  );
+  ComposedSvg {
+    markup,
+    warnings: composer.warnings,
+    unsupported: composer.unsupported,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn finds_svg_tag_after_non_ascii_text() {
+    // "İ" 小写后变长，按小写字符串算出的偏移会落在错误位置
+    let content = r#"<!-- İİİ 图标 --><SVG viewBox="0 0 24 24" data-名="值"><path d="M0 0"/></SVG>"#;
+    let tag = parse_svg_tag(content).unwrap();
+    assert!(content[..tag.end].ends_with("data-名=\"值\">"));
+    assert_eq!(tag.attrs[0], ("viewBox".to_string(), "0 0 24 24".to_string()));
+    assert_eq!(tag.attrs[1], ("data-名".to_string(), "值".to_string()));
+  }
+
+  #[test]
+  fn missing_svg_tag() {
+    assert!(parse_svg_tag("<path d=\"M0 0\"/>").is_none());
+    assert!(parse_svg_tag("<svg viewBox=\"0 0 1 1\"").is_none());
+  }
+
+  #[test]
+  fn nameless_attribute_before_non_ascii() {
+    let tag = parse_svg_tag(r#"<svg ="x"é>"#).unwrap();
+    assert_eq!(tag.attrs, vec![("é".to_string(), String::new())]);
+    let content = r#"<svg = 'x'/名="值" width="1">"#;
+    let tag = parse_svg_tag(content).unwrap();
+    assert_eq!(tag.end, content.len());
+    assert_eq!(tag.attrs[0], ("名".to_string(), "值".to_string()));
+    assert!(parse_svg_tag("<svg =\"x\"é").is_none());
+  }
+}
@@ -0,0 +1,155 @@
+// 模块：前端经 fs 插件可访问的本地文件（应用配置/数据目录 + 当前项目文件 + 用户拖入的文件）
+// 静态能力只授权 $APPCONFIG 与 $APPDATA，其余在运行时经插件的作用域 API 逐个授权；
+// 插件的 forbid 无法撤销，所以项目本身的读写一律走后端命令（load_cfj 等），重新打开同一项目不受影响
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_fs::FsExt;
+
+// 当前授权的项目文件（规范路径），切换或关闭项目时据此收回
+#[derive(Default)]
+pub struct ProjectScope(Mutex<Option<PathBuf>>);
+
+fn forbid<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+  match app.fs_scope().forbid_file(path) {
+    Ok(()) => log::info!("fs scope: revoked {}", path.display()),
+    Err(err) => log::warn!("fs scope: cannot revoke {}: {}", path.display(), err),
+  }
+}
+
+// 授权项目文件，并收回上一个项目
+pub fn grant_project<R: Runtime>(app: &AppHandle<R>, project_path: &Path) {
+  let Ok(canonical) = std::fs::canonicalize(project_path) else {
+    return;
+  };
+  let state = app.state::<ProjectScope>();
+  let Ok(mut granted) = state.0.lock() else {
+    return;
+  };
+  if granted.as_deref() == Some(canonical.as_path()) {
+    return;
+  }
+  if let Err(err) = app.fs_scope().allow_file(&canonical) {
+    log::warn!("fs scope: cannot grant {}: {}", canonical.display(), err);
+    return;
+  }
+  log::info!("fs scope: granted {}", canonical.display());
+  if let Some(previous) = granted.replace(canonical) {
+    forbid(app, &previous);
+  }
+}
+
+pub fn revoke_project<R: Runtime>(app: &AppHandle<R>) {
+  let state = app.state::<ProjectScope>();
+  let Ok(mut granted) = state.0.lock() else {
+    return;
+  };
+  if let Some(previous) = granted.take() {
+    forbid(app, &previous);
+  }
+}
+
+// 拖放进来的图片等单个文件
+pub fn grant_file<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+  let Ok(canonical) = std::fs::canonicalize(path) else {
+    return;
+  };
+  if let Err(err) = app.fs_scope().allow_file(&canonical) {
+    log::warn!("fs scope: cannot grant {}: {}", canonical.display(), err);
+  }
+}
+
+// 先解析符号链接与 ..，再按插件作用域判断；供需要同样限制的后端命令使用
+pub fn check<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<PathBuf, String> {
+  let canonical = std::fs::canonicalize(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+  if app.fs_scope().is_allowed(&canonical) {
+    Ok(canonical)
+  } else {
+    log::warn!("fs scope: denied {}", canonical.display());
+    Err(format!("path outside the allowed scope: {}", path.display()))
+  }
+}
+
+#[tauri::command]
+pub fn revoke_project_scope(app: AppHandle) {
+  revoke_project(&app);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+
+  fn app() -> tauri::App<MockRuntime> {
+    mock_builder()
+      .plugin(tauri_plugin_fs::init())
+      .manage(ProjectScope::default())
+      .build(mock_context(noop_assets()))
+      .unwrap()
+  }
+
+  fn touch(path: &Path) {
+    std::fs::write(path, b"{}").unwrap();
+  }
+
+  #[test]
+  fn denies_paths_outside_scope() {
+    let app = app();
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("a.cfj");
+    let secret = dir.path().join("secret.txt");
+    touch(&project);
+    touch(&secret);
+    assert!(check(app.handle(), &project).is_err());
+    assert!(check(app.handle(), &secret).is_err());
+    assert!(check(app.handle(), &dir.path().join("missing.cfj")).is_err());
+  }
+
+  #[test]
+  fn allows_project_after_open() {
+    let app = app();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("sub")).unwrap();
+    let project = dir.path().join("a.cfj");
+    touch(&project);
+    touch(&dir.path().join("secret.txt"));
+
+    grant_project(app.handle(), &project);
+    assert!(check(app.handle(), &project).is_ok());
+    // .. 先解析再判断
+    assert!(check(app.handle(), &dir.path().join("sub").join("..").join("a.cfj")).is_ok());
+    // 只授权项目文件本身，同目录的其他文件仍被拒绝
+    assert!(check(app.handle(), &dir.path().join("secret.txt")).is_err());
+  }
+
+  #[test]
+  fn switching_or_closing_revokes_previous_project() {
+    let app = app();
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("a.cfj");
+    let second = dir.path().join("b.cfj");
+    touch(&first);
+    touch(&second);
+
+    grant_project(app.handle(), &first);
+    grant_project(app.handle(), &second);
+    assert!(check(app.handle(), &first).is_err());
+    assert!(check(app.handle(), &second).is_ok());
+
+    revoke_project(app.handle());
+    assert!(check(app.handle(), &second).is_err());
+  }
+
+  #[test]
+  fn dropped_files_are_granted_individually() {
+    let app = app();
+    let dir = tempfile::tempdir().unwrap();
+    let image = dir.path().join("photo.png");
+    touch(&image);
+    touch(&dir.path().join("other.png"));
+
+    grant_file(app.handle(), &image);
+    assert!(check(app.handle(), &image).is_ok());
+    assert!(check(app.handle(), &dir.path().join("other.png")).is_err());
+  }
+}
@@ -0,0 +1,127 @@
+// 模块：全局快捷键唤起主窗口（可重新绑定，保存到应用配置目录）
+use crate::{atomic, window_state};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+pub const ACTIVATED_EVENT: &str = "window://activated";
+pub const FAILED_EVENT: &str = "shortcut://failed";
+
+const SETTINGS_FILE: &str = "shortcut.json";
+const DEFAULT_ACCELERATOR: &str = "CmdOrCtrl+Shift+C";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutSettings {
+  pub accelerator: String,
+}
+
+impl Default for ShortcutSettings {
+  fn default() -> Self {
+    ShortcutSettings {
+      accelerator: DEFAULT_ACCELERATOR.to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutFailure {
+  pub accelerator: String,
+  pub error: String,
+}
+
+// 当前注册的快捷键；启动时注册失败的信息等前端就绪后再通知
+#[derive(Default)]
+pub struct ActivationShortcut {
+  current: Mutex<Option<Shortcut>>,
+  failure: Mutex<Option<ShortcutFailure>>,
+}
+
+fn settings_file(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> ShortcutSettings {
+  settings_file(app)
+    .and_then(|file| std::fs::read_to_string(file).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_settings(app: &AppHandle, settings: &ShortcutSettings) -> Result<(), String> {
+  let file = settings_file(app).ok_or_else(|| "config dir unavailable".to_string())?;
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+  }
+  let bytes = serde_json::to_vec_pretty(settings).map_err(|err| err.to_string())?;
+  atomic::write_with(&file, |handle| handle.write_all(&bytes)).map_err(|err| err.to_string())
+}
+
+fn activate(app: &AppHandle) {
+  window_state::bring_to_front(app);
+  let _ = app.emit(ACTIVATED_EVENT, ());
+}
+
+// 先注册新快捷键，成功后再注销旧的，失败时旧绑定保持可用
+fn register(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+  let shortcut: Shortcut = accelerator
+    .parse()
+    .map_err(|err| format!("invalid shortcut {}: {}", accelerator, err))?;
+  let state = app.state::<ActivationShortcut>();
+  let mut current = state.current.lock().map_err(|err| err.to_string())?;
+  if current.as_ref() == Some(&shortcut) {
+    return Ok(());
+  }
+  app
+    .global_shortcut()
+    .on_shortcut(shortcut, |app, _, event| {
+      if event.state() == ShortcutState::Pressed {
+        activate(app);
+      }
+    })
+    .map_err(|err| format!("cannot register {}: {}", accelerator, err))?;
+  if let Some(previous) = current.replace(shortcut) {
+    let _ = app.global_shortcut().unregister(previous);
+  }
+  Ok(())
+}
+
+pub fn init(app: &AppHandle) {
+  let settings = read_settings(app);
+  if let Err(err) = register(app, &settings.accelerator) {
+    log::warn!("activation shortcut unavailable: {}", err);
+    if let Ok(mut failure) = app.state::<ActivationShortcut>().failure.lock() {
+      *failure = Some(ShortcutFailure {
+        accelerator: settings.accelerator,
+        error: err,
+      });
+    }
+  }
+}
+
+// 前端订阅就绪后调用
+pub fn announce(app: &AppHandle) {
+  let failure = match app.state::<ActivationShortcut>().failure.lock() {
+    Ok(mut failure) => failure.take(),
+    Err(_) => return,
+  };
+  if let Some(failure) = failure {
+    let _ = app.emit(FAILED_EVENT, &failure);
+  }
+}
+
+#[tauri::command]
+pub fn get_activation_shortcut(app: AppHandle) -> ShortcutSettings {
+  read_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_activation_shortcut(app: AppHandle, accel: String) -> Result<(), String> {
+  let accelerator = accel.trim().to_string();
+  register(&app, &accelerator).inspect_err(|err| log::warn!("activation shortcut not changed: {}", err))?;
+  write_settings(&app, &ShortcutSettings { accelerator })
+}
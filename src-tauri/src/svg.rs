@@ -0,0 +1,230 @@
+// 模块：导出矢量 SVG（文字保留为 <text>，本地图片与项目字体内嵌为 base64）
+use crate::cfj::{BackgroundConfig, BackgroundType, CfjProject, Layer, LayerType};
+use crate::export::{self, ExportError, ExportFormat};
+use crate::{assets, atomic, fetch, limits, render};
+use base64::Engine;
+use serde::Serialize;
+use std::path::Path;
+use tauri::AppHandle;
+
+// 单独栅格化图层时四周留白，外阴影与描边不被裁掉
+const RASTER_MARGIN: f64 = 64.0;
+const RASTER_SCALE: f32 = 2.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSvg {
+  pub path: String,
+  // 合成时无法用 SVG 还原、已省略或降级的效果
+  pub warnings: Vec<String>,
+}
+
+fn image_mime(path: &Path, bytes: &[u8]) -> Option<&'static str> {
+  if let Some(format) = fetch::sniff(bytes) {
+    return Some(format.to_mime_type());
+  }
+  let is_svg = path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+  is_svg.then_some("image/svg+xml")
+}
+
+// 本地路径换成 data URL，导出的文件可单独交给印刷厂
+fn embed_href(href: &str, label: &str, warnings: &mut Vec<String>) -> Option<String> {
+  let path = assets::local_path(href)?;
+  let embedded = std::fs::read(&path)
+    .ok()
+    .and_then(|bytes| image_mime(&path, &bytes).map(|mime| (mime, bytes)));
+  match embedded {
+    Some((mime, bytes)) => Some(format!(
+      "data:{};base64,{}",
+      mime,
+      base64::engine::general_purpose::STANDARD.encode(bytes)
+    )),
+    None => {
+      warnings.push(format!("{}: image file {} not embedded", label, path.display()));
+      None
+    }
+  }
+}
+
+fn embed_images(project: &mut CfjProject, warnings: &mut Vec<String>) {
+  if project.background.background_type == BackgroundType::Image {
+    if let Some(data) = embed_href(&project.background.value, "background", warnings) {
+      project.background.value = data;
+    }
+  }
+  for layer in project.layers.iter_mut().filter(|layer| layer.layer_type == LayerType::Image) {
+    let label = format!("layer \"{}\"", layer.name);
+    if let Some(data) = embed_href(&layer.content, &label, warnings) {
+      layer.content = data;
+    }
+  }
+}
+
+fn font_mime(bytes: &[u8]) -> &'static str {
+  match bytes.get(..4) {
+    Some(b"OTTO") => "font/otf",
+    Some(b"wOFF") => "font/woff",
+    Some(b"wOF2") => "font/woff2",
+    _ => "font/ttf",
+  }
+}
+
+// 项目字体写成 @font-face，打开 SVG 的机器无需安装字体
+fn font_faces(project: &CfjProject) -> Result<String, ExportError> {
+  let mut css = String::new();
+  for font in &project.fonts {
+    let bytes = export::font_bytes(font).ok_or_else(|| ExportError::MissingFont(export::describe_font(font)))?;
+    css.push_str(&format!(
+      "@font-face{{font-family:\"{}\";font-weight:{};font-style:{};src:url(data:{};base64,{})}}",
+      font.family.replace('"', ""),
+      font.weight.unwrap_or(400),
+      if font.italic { "italic" } else { "normal" },
+      font_mime(&bytes),
+      base64::engine::general_purpose::STANDARD.encode(&bytes)
+    ));
+  }
+  Ok(css)
+}
+
+// 只含该图层的透明画布，图层放在留白之内，旋转与不透明度留给外层 <g>
+fn rasterize_layer(project: &CfjProject, layer: &Layer) -> Result<(String, Vec<String>), ExportError> {
+  let mut single = project.clone();
+  single.canvas_config.width = layer.width.max(1.0) + RASTER_MARGIN * 2.0;
+  single.canvas_config.height = layer.height.max(1.0) + RASTER_MARGIN * 2.0;
+  single.background = BackgroundConfig {
+    background_type: BackgroundType::Color,
+    value: "none".to_string(),
+    overlay_type: "none".to_string(),
+    overlay_color: String::new(),
+    overlay_opacity: 0.0,
+    overlay_scale: 1.0,
+  };
+  let mut copy = layer.clone();
+  copy.x = RASTER_MARGIN;
+  copy.y = RASTER_MARGIN;
+  copy.rotation = 0.0;
+  copy.opacity = 1.0;
+  single.layers = vec![copy];
+  let (pixmap, warnings) = export::rasterize(&single, RASTER_SCALE)?;
+  let png = export::encode(&pixmap, ExportFormat::Png)?;
+  let data = format!(
+    "data:image/png;base64,{}",
+    base64::engine::general_purpose::STANDARD.encode(png)
+  );
+  Ok((data, warnings))
+}
+
+// SVG 无法表达的效果（滤镜、混合模式、内阴影等）不直接丢弃：该图层按 PNG 导出的渲染结果嵌入为 <image>
+fn rasterize_unsupported(project: &mut CfjProject, ids: &[String], warnings: &mut Vec<String>) {
+  for id in ids {
+    let Some(index) = project.layers.iter().position(|layer| &layer.id == id) else {
+      continue;
+    };
+    let layer = project.layers[index].clone();
+    match rasterize_layer(project, &layer) {
+      Ok((data, raster_warnings)) => {
+        warnings.extend(raster_warnings);
+        warnings.push(format!("layer \"{}\": rasterized in SVG export", layer.name));
+        let image = &mut project.layers[index];
+        image.layer_type = LayerType::Image;
+        image.content = data;
+        image.ratio_locked = Some(false);
+        image.x -= RASTER_MARGIN;
+        image.y -= RASTER_MARGIN;
+        image.width = layer.width.max(1.0) + RASTER_MARGIN * 2.0;
+        image.height = layer.height.max(1.0) + RASTER_MARGIN * 2.0;
+      }
+      Err(err) => warnings.push(format!("layer \"{}\": cannot rasterize: {}", layer.name, err)),
+    }
+  }
+}
+
+pub fn svg_markup(project: &CfjProject) -> Result<(String, Vec<String>), ExportError> {
+  let mut project = project.clone();
+  let mut warnings = Vec::new();
+  embed_images(&mut project, &mut warnings);
+  let css = font_faces(&project)?;
+  let mut composed = render::compose(&project);
+  if !composed.unsupported.is_empty() {
+    rasterize_unsupported(&mut project, &composed.unsupported, &mut warnings);
+    composed = render::compose(&project);
+  }
+  warnings.extend(composed.warnings);
+  let markup = if css.is_empty() {
+    composed.markup
+  } else {
+    composed.markup.replacen("<defs>", &format!("<defs><style>{}</style>", render::escape(&css)), 1)
+  };
+  Ok((markup, warnings))
+}
+
+pub fn export_to(project: &CfjProject, out_path: &Path) -> Result<ExportedSvg, ExportError> {
+  let (markup, warnings) = svg_markup(project)?;
+  atomic::write(out_path, markup.as_bytes()).map_err(|err| ExportError::Write(err.to_string()))?;
+  log::info!("exported svg ({} warning(s)): {}", warnings.len(), out_path.display());
+  Ok(ExportedSvg {
+    path: out_path.to_string_lossy().into_owned(),
+    warnings,
+  })
+}
+
+// 与 export_all / export_pdf 一致接收封面列表，index 指定导出哪一张
+#[tauri::command]
+pub async fn export_svg(
+  app: AppHandle,
+  projects: Vec<CfjProject>,
+  index: usize,
+  out_path: String,
+) -> Result<ExportedSvg, String> {
+  limits::check(&app, "export_svg", &projects)?;
+  let total = projects.len();
+  let project = projects
+    .into_iter()
+    .nth(index)
+    .ok_or_else(|| format!("cover index {} out of range ({} cover(s))", index, total))?;
+  tauri::async_runtime::spawn_blocking(move || export_to(&project, Path::new(&out_path)))
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cfj;
+
+  fn cover() -> CfjProject {
+    cfj::parse(include_str!("../tests/fixtures/valid.cfj")).unwrap().project
+  }
+
+  #[test]
+  fn text_stays_vector() {
+    let (markup, warnings) = svg_markup(&cover()).unwrap();
+    assert!(markup.contains("<text"));
+    assert!(markup.contains("Hello 封面"));
+    assert!(!markup.contains("<image"));
+    assert!(warnings.is_empty(), "{:?}", warnings);
+  }
+
+  #[test]
+  fn unsupported_effects_are_rasterized() {
+    let mut project = cover();
+    let mut layer = project.layers[0].clone();
+    layer.id = "fx".to_string();
+    layer.name = "blurred".to_string();
+    layer.layer_type = LayerType::Decoration;
+    layer.content = "background: #ff0000; filter: blur(4px)".to_string();
+    layer.rotation = 15.0;
+    project.layers.push(layer);
+
+    let (markup, warnings) = svg_markup(&project).unwrap();
+    // 文字图层仍为矢量，装饰图层换成嵌入的 PNG
+    assert!(markup.contains("<text"));
+    assert_eq!(markup.matches("href=\"data:image/png;base64,").count(), 1);
+    assert!(warnings.iter().any(|warning| warning == "layer \"blurred\": rasterized in SVG export"));
+    assert!(!markup.contains("#ff0000"));
+  }
+}
@@ -0,0 +1,176 @@
+// 模块：随应用打包的模板（资源目录 templates/ 下的 .cfj，新建时复制为未保存项目）
+use crate::cfj::{self, CfjProject};
+use crate::{thumbnail, validate};
+use base64::Engine;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const TEMPLATE_DIR: &str = "templates";
+const THUMBNAIL_PX: u32 = 320;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+  // 文件名（不含扩展名）
+  pub id: String,
+  pub name: String,
+  pub description: String,
+  // PNG data URL，渲染失败时为 None
+  pub thumbnail: Option<String>,
+}
+
+// 只允许文件名字符，防止 id 指向模板目录之外
+fn is_valid_id(id: &str) -> bool {
+  !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn template_path(dir: &Path, id: &str) -> Option<PathBuf> {
+  is_valid_id(id).then(|| dir.join(format!("{}.cfj", id)))
+}
+
+// 与打开文件相同的加载与校验，任何错误都视为模板损坏
+pub fn load_template(path: &Path) -> Result<CfjProject, String> {
+  let report = validate::validate(path);
+  if !report.ok {
+    return Err(format!("{}: {}", path.display(), report.errors.join("; ")));
+  }
+  cfj::load(path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+// 按文件名排序返回全部模板；损坏的模板单独列出
+pub fn scan(dir: &Path) -> (Vec<(String, PathBuf, CfjProject)>, Vec<String>) {
+  let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+    .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+    .unwrap_or_default();
+  paths.retain(|path| path.extension().and_then(|ext| ext.to_str()) == Some("cfj"));
+  paths.sort();
+  let mut templates = Vec::new();
+  let mut broken = Vec::new();
+  for path in paths {
+    let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).filter(|id| is_valid_id(id)) else {
+      broken.push(format!("{}: invalid template id", path.display()));
+      continue;
+    };
+    let id = id.to_string();
+    match load_template(&path) {
+      Ok(project) => templates.push((id, path, project)),
+      Err(err) => broken.push(err),
+    }
+  }
+  (templates, broken)
+}
+
+fn template_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .resource_dir()
+    .map(|dir| dir.join(TEMPLATE_DIR))
+    .map_err(|err| err.to_string())
+}
+
+fn info(id: String, path: &Path, project: &CfjProject, cache_dir: Option<&Path>) -> TemplateInfo {
+  let description = project
+    .extra
+    .get("description")
+    .and_then(|value| value.as_str())
+    .unwrap_or_default()
+    .to_string();
+  let thumbnail = cache_dir
+    .and_then(|dir| thumbnail::thumbnail_png(dir, path, THUMBNAIL_PX).ok())
+    .map(|bytes| {
+      format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+      )
+    });
+  TemplateInfo {
+    id,
+    name: project.title.clone(),
+    description,
+    thumbnail,
+  }
+}
+
+#[tauri::command]
+pub async fn list_templates(app: AppHandle) -> Vec<TemplateInfo> {
+  let Ok(dir) = template_dir(&app) else {
+    return Vec::new();
+  };
+  let cache_dir = thumbnail::cache_dir(&app).ok();
+  tauri::async_runtime::spawn_blocking(move || {
+    let (templates, broken) = scan(&dir);
+    for err in &broken {
+      log::error!("broken template: {}", err);
+    }
+    templates
+      .into_iter()
+      .map(|(id, path, project)| info(id, &path, &project, cache_dir.as_deref()))
+      .collect()
+  })
+  .await
+  .unwrap_or_default()
+}
+
+// 返回换过 id 的未保存项目，由前端按新项目处理
+#[tauri::command]
+pub fn new_from_template(app: AppHandle, id: String) -> Result<CfjProject, String> {
+  let dir = template_dir(&app)?;
+  let path = template_path(&dir, &id).ok_or_else(|| format!("invalid template id: {}", id))?;
+  if !path.is_file() {
+    return Err(format!("unknown template: {}", id));
+  }
+  let mut project = load_template(&path).map_err(|err| {
+    log::error!("broken template: {}", err);
+    err
+  })?;
+  cfj::reassign_ids(&mut project);
+  project.extra.remove("description");
+  project.selected_layer_id = None;
+  project.updated_at = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as f64)
+    .unwrap_or(0.0);
+  log::info!("new_from_template: {}", id);
+  Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn bundled_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(TEMPLATE_DIR)
+  }
+
+  // 打包模板损坏时在测试中暴露，而不是运行时崩溃
+  #[test]
+  fn bundled_templates_parse() {
+    let mut count = 0;
+    for entry in std::fs::read_dir(bundled_dir()).unwrap() {
+      let path = entry.unwrap().path();
+      if path.extension().and_then(|ext| ext.to_str()) != Some("cfj") {
+        continue;
+      }
+      let text = std::fs::read_to_string(&path).unwrap();
+      if let Err(err) = cfj::parse(&text) {
+        panic!("{}: {}", path.display(), err);
+      }
+      count += 1;
+    }
+    assert!(count > 0);
+    let (templates, broken) = scan(&bundled_dir());
+    assert!(broken.is_empty(), "{:?}", broken);
+    assert_eq!(templates.len(), count);
+  }
+
+  #[test]
+  fn template_ids_stay_in_directory() {
+    let dir = bundled_dir();
+    assert_eq!(template_path(&dir, "book-portrait"), Some(dir.join("book-portrait.cfj")));
+    assert!(template_path(&dir, "../secret").is_none());
+    assert!(template_path(&dir, "a/b").is_none());
+    assert!(template_path(&dir, "").is_none());
+  }
+}
@@ -0,0 +1,154 @@
+// 模块：最近文件列表的缩略图（按路径与修改时间缓存到应用缓存目录）
+use crate::{atomic, cfj, export, pending, recent};
+use base64::Engine;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+const THUMBNAIL_DIR: &str = "thumbnails";
+const MIN_THUMB_PX: u32 = 16;
+const MAX_THUMB_PX: u32 = 1024;
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  value.hash(&mut hasher);
+  hasher.finish()
+}
+
+// 文件名前半段只与路径有关，便于源文件变化后清理旧缩略图
+pub fn cache_name(path: &Path, modified_ns: u128, max_px: u32) -> String {
+  format!("{:016x}-{:016x}.png", hash_of(path), hash_of(&(modified_ns, max_px)))
+}
+
+fn modified_ns(path: &Path) -> Result<u128, String> {
+  let modified = std::fs::metadata(path)
+    .and_then(|meta| meta.modified())
+    .map_err(|err| err.to_string())?;
+  Ok(modified.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0))
+}
+
+fn remove_stale(dir: &Path, keep: &str) {
+  let Some((prefix, _)) = keep.split_once('-') else {
+    return;
+  };
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().into_owned();
+    if name != keep && name.starts_with(prefix) {
+      let _ = std::fs::remove_file(entry.path());
+    }
+  }
+}
+
+// 命中缓存时直接读取 PNG，否则渲染并写入缓存；只接受 .cfj/.cfjz 普通文件
+pub fn thumbnail_png(cache_dir: &Path, path: &Path, max_px: u32) -> Result<Vec<u8>, String> {
+  let max_px = max_px.clamp(MIN_THUMB_PX, MAX_THUMB_PX);
+  let canonical = PathBuf::from(
+    pending::validate_path(&path.to_string_lossy()).map_err(|err| format!("{}: {}", path.display(), err))?,
+  );
+  let name = cache_name(&canonical, modified_ns(&canonical)?, max_px);
+  let cached = cache_dir.join(&name);
+  if let Ok(bytes) = std::fs::read(&cached) {
+    return Ok(bytes);
+  }
+
+  let project = cfj::load(&canonical).map_err(|err| err.to_string())?;
+  let (pixmap, _) = export::rasterize_fit(&project, max_px).map_err(|err| err.to_string())?;
+  let bytes = export::encode(&pixmap, export::ExportFormat::Png).map_err(|err| err.to_string())?;
+
+  // 缓存写入失败不影响返回结果
+  if std::fs::create_dir_all(cache_dir).is_ok() {
+    match atomic::write(&cached, &bytes) {
+      Ok(()) => remove_stale(cache_dir, &name),
+      Err(err) => log::warn!("thumbnail not cached: {}: {}", cached.display(), err),
+    }
+  }
+  Ok(bytes)
+}
+
+pub fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_cache_dir()
+    .map(|dir| dir.join(THUMBNAIL_DIR))
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn cfj_thumbnail(app: AppHandle, path: String, max_px: u32) -> Result<String, String> {
+  let path = recent::require(&app, &path)?;
+  let dir = cache_dir(&app)?;
+  let bytes = tauri::async_runtime::spawn_blocking(move || thumbnail_png(&dir, Path::new(&path), max_px))
+    .await
+    .map_err(|err| err.to_string())??;
+  Ok(format!(
+    "data:image/png;base64,{}",
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::{Duration, SystemTime};
+
+  const VALID: &str = include_str!("../tests/fixtures/valid.cfj");
+
+  fn cached_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir).unwrap().flatten().map(|entry| entry.path()).collect()
+  }
+
+  #[test]
+  fn reuses_cache_until_source_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("cover.cfj");
+    std::fs::write(&source, VALID).unwrap();
+    let cache = dir.path().join("cache");
+
+    let first = thumbnail_png(&cache, &source, 64).unwrap();
+    assert_eq!(&first[..4], b"\x89PNG");
+    let files = cached_files(&cache);
+    assert_eq!(files.len(), 1);
+
+    // 改写缓存文件，命中时应原样返回
+    std::fs::write(&files[0], b"cached").unwrap();
+    assert_eq!(thumbnail_png(&cache, &source, 64).unwrap(), b"cached");
+
+    // 修改时间变化后重新渲染，并清理旧缓存
+    let file = std::fs::File::options().write(true).open(&source).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+    let rendered = thumbnail_png(&cache, &source, 64).unwrap();
+    assert_eq!(&rendered[..4], b"\x89PNG");
+    assert_eq!(cached_files(&cache).len(), 1);
+    let image = image::load_from_memory(&rendered).unwrap();
+    assert_eq!(image.width().max(image.height()), 64);
+  }
+
+  #[test]
+  fn only_project_files_are_rendered() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let text = dir.path().join("notes.txt");
+    std::fs::write(&text, VALID).unwrap();
+    let folder = dir.path().join("folder.cfj");
+    std::fs::create_dir(&folder).unwrap();
+    for path in [text, folder, dir.path().join("missing.cfj")] {
+      assert!(thumbnail_png(&cache, &path, 64).is_err(), "{}", path.display());
+    }
+    assert!(!cache.exists());
+  }
+
+  #[test]
+  fn cache_name_depends_on_size_and_mtime() {
+    let path = Path::new("cover.cfj");
+    let name = cache_name(path, 1, 64);
+    assert_ne!(name, cache_name(path, 2, 64));
+    assert_ne!(name, cache_name(path, 1, 128));
+    // 同一路径的前缀不变
+    assert_eq!(name.split_once('-').unwrap().0, cache_name(path, 2, 128).split_once('-').unwrap().0);
+  }
+}
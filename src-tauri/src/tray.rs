@@ -0,0 +1,169 @@
+// 模块：系统托盘（显示窗口 / 新建项目 / 最近打开 / 退出），可选关闭主窗口时隐藏到托盘
+use crate::{atomic, menu, window_state};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::menu::{MenuBuilder, MenuEvent, MenuItemBuilder, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Window, WindowEvent};
+
+pub const NEW_PROJECT_EVENT: &str = "tray://new-project";
+
+const TRAY_ID: &str = "main";
+const SHOW_ID: &str = "tray.show";
+const NEW_ID: &str = "tray.new";
+const QUIT_ID: &str = "tray.quit";
+const SETTINGS_FILE: &str = "tray.json";
+
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+// 托盘退出时置位，关闭事件不再拦截
+static QUITTING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraySettings {
+  #[serde(default)]
+  pub minimize_to_tray: bool,
+}
+
+// 托盘里的“最近打开”子菜单，随应用菜单一起刷新
+pub struct TrayRecent(Submenu<tauri::Wry>);
+
+fn settings_file(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> TraySettings {
+  settings_file(app)
+    .and_then(|file| std::fs::read_to_string(file).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_settings(app: &AppHandle, settings: &TraySettings) -> Result<(), String> {
+  let file = settings_file(app).ok_or_else(|| "config dir unavailable".to_string())?;
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+  }
+  let bytes = serde_json::to_vec_pretty(settings).map_err(|err| err.to_string())?;
+  atomic::write_with(&file, |handle| handle.write_all(&bytes)).map_err(|err| err.to_string())
+}
+
+fn build(app: &AppHandle) -> tauri::Result<()> {
+  let show = MenuItemBuilder::with_id(SHOW_ID, "Show Window").build(app)?;
+  let new_project = MenuItemBuilder::with_id(NEW_ID, "New Project").build(app)?;
+  let recent = menu::recent_submenu(app)?;
+  let quit = MenuItemBuilder::with_id(QUIT_ID, "Quit").build(app)?;
+  let tray_menu = MenuBuilder::new(app)
+    .item(&show)
+    .item(&new_project)
+    .item(&recent)
+    .separator()
+    .item(&quit)
+    .build()?;
+
+  let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+    .menu(&tray_menu)
+    .show_menu_on_left_click(false)
+    .tooltip(app.package_info().name.clone())
+    .on_menu_event(on_menu_event)
+    .on_tray_icon_event(on_tray_event);
+  if let Some(icon) = app.default_window_icon() {
+    builder = builder.icon(icon.clone());
+  }
+  builder.build(app)?;
+  app.manage(TrayRecent(recent));
+  Ok(())
+}
+
+// 桌面环境不支持托盘时只记录日志，关闭窗口仍按正常流程退出
+pub fn init(app: &AppHandle) {
+  if let Err(err) = build(app) {
+    log::warn!("tray unavailable: {}", err);
+  }
+}
+
+pub fn refresh_recent(app: &AppHandle) {
+  if let Some(state) = app.try_state::<TrayRecent>() {
+    menu::refill_recent(app, &state.0);
+  }
+}
+
+fn toggle_window(app: &AppHandle) {
+  let Some(window) = app.get_webview_window("main") else {
+    return;
+  };
+  let visible = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+  if visible {
+    let _ = window.hide();
+  } else {
+    window_state::bring_to_front(app);
+  }
+}
+
+fn on_tray_event(tray: &TrayIcon, event: TrayIconEvent) {
+  if let TrayIconEvent::Click {
+    button: MouseButton::Left,
+    button_state: MouseButtonState::Up,
+    ..
+  } = event
+  {
+    toggle_window(tray.app_handle());
+  }
+}
+
+// “最近打开”的菜单项与应用菜单同 id，由 menu::on_menu_event 处理
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+  match event.id().as_ref() {
+    SHOW_ID => window_state::bring_to_front(app),
+    NEW_ID => {
+      window_state::bring_to_front(app);
+      let _ = app.emit(NEW_PROJECT_EVENT, ());
+    }
+    QUIT_ID => {
+      QUITTING.store(true, Ordering::SeqCst);
+      app.exit(0);
+    }
+    _ => {}
+  }
+}
+
+// 开启“最小化到托盘”且托盘可用时，关闭主窗口只隐藏
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+  let WindowEvent::CloseRequested { api, .. } = event else {
+    return;
+  };
+  let app = window.app_handle();
+  if window.label() != "main" || QUITTING.load(Ordering::SeqCst) || app.tray_by_id(TRAY_ID).is_none() {
+    return;
+  }
+  let enabled = {
+    let _guard = SETTINGS_LOCK.lock();
+    read_settings(app).minimize_to_tray
+  };
+  if enabled {
+    api.prevent_close();
+    let _ = window.hide();
+  }
+}
+
+// 退出时移除托盘图标，避免 Windows 通知区域残留
+pub fn remove(app: &AppHandle) {
+  let _ = app.remove_tray_by_id(TRAY_ID);
+}
+
+#[tauri::command]
+pub fn get_tray_settings(app: AppHandle) -> TraySettings {
+  let _guard = SETTINGS_LOCK.lock();
+  read_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_minimize_to_tray(app: AppHandle, enabled: bool) -> Result<(), String> {
+  let _guard = SETTINGS_LOCK.lock();
+  let mut settings = read_settings(&app);
+  settings.minimize_to_tray = enabled;
+  write_settings(&app, &settings)
+}
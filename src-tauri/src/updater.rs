@@ -0,0 +1,261 @@
+// 模块：应用更新（手动检查 + 可选的每日后台检查，签名校验失败即拒绝安装）
+use crate::atomic;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+pub const PROGRESS_EVENT: &str = "update://progress";
+pub const AVAILABLE_EVENT: &str = "update://available";
+
+const SETTINGS_FILE: &str = "updater.json";
+const CHECK_INTERVAL_MS: u64 = 24 * 60 * 60 * 1000;
+
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettings {
+  #[serde(default)]
+  pub auto_check: bool,
+  #[serde(default)]
+  pub last_check: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+  pub version: String,
+  pub current_version: String,
+  pub date: Option<String>,
+  pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateProgress {
+  downloaded: u64,
+  total: Option<u64>,
+}
+
+// 检查到的更新暂存，安装时取出
+#[derive(Default)]
+pub struct PendingUpdate(Mutex<Option<Update>>);
+
+fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+fn settings_file(app: &AppHandle) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> UpdateSettings {
+  settings_file(app)
+    .and_then(|file| std::fs::read_to_string(file).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+fn write_settings(app: &AppHandle, settings: &UpdateSettings) -> Result<(), String> {
+  let file = settings_file(app).ok_or_else(|| "config dir unavailable".to_string())?;
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+  }
+  let bytes = serde_json::to_vec_pretty(settings).map_err(|err| err.to_string())?;
+  atomic::write_with(&file, |handle| handle.write_all(&bytes)).map_err(|err| err.to_string())
+}
+
+fn info_of(update: &Update) -> UpdateInfo {
+  UpdateInfo {
+    version: update.version.clone(),
+    current_version: update.current_version.clone(),
+    date: update.date.map(|date| date.to_string()),
+    notes: update.body.clone(),
+  }
+}
+
+// tauri.conf.json 中的 pubkey 是 minisign 公钥文件整体的 base64：
+// 一行 untrusted comment，加一行 base64 的 "Ed" + 8 字节 key id + 32 字节公钥
+fn is_minisign_pubkey(pubkey: &str) -> bool {
+  let engine = base64::engine::general_purpose::STANDARD;
+  let Ok(text) = engine.decode(pubkey.trim()) else {
+    return false;
+  };
+  let Ok(text) = String::from_utf8(text) else {
+    return false;
+  };
+  let mut lines = text.lines();
+  if !lines.next().is_some_and(|line| line.starts_with("untrusted comment:")) {
+    return false;
+  }
+  lines
+    .next()
+    .and_then(|line| engine.decode(line.trim()).ok())
+    .is_some_and(|key| key.len() == 42 && key.starts_with(b"Ed"))
+}
+
+fn pubkey_configured(updater: Option<&serde_json::Value>) -> bool {
+  updater
+    .and_then(|updater| updater.get("pubkey"))
+    .and_then(|pubkey| pubkey.as_str())
+    .is_some_and(is_minisign_pubkey)
+}
+
+// 未配置有效的发布公钥时无法校验签名，整个更新功能不启用：插件不注册、不做后台检查，命令视为没有更新
+fn has_pubkey(app: &AppHandle) -> bool {
+  pubkey_configured(app.config().plugins.0.get("updater"))
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+  if !has_pubkey(app) {
+    log::warn!("updater disabled: no valid pubkey in tauri.conf.json");
+    return Ok(());
+  }
+  app.plugin(tauri_plugin_updater::Builder::new().build())?;
+  spawn_background_check(app);
+  Ok(())
+}
+
+// 离线或服务端异常都按“没有更新”处理，只记录日志
+async fn check(app: &AppHandle) -> Option<UpdateInfo> {
+  if !has_pubkey(app) {
+    log::warn!("update check skipped: updater pubkey is not configured");
+    return None;
+  }
+  let result = match app.updater() {
+    Ok(updater) => updater.check().await,
+    Err(err) => Err(err),
+  };
+  let update = match result {
+    Ok(update) => update?,
+    Err(err) => {
+      log::warn!("update check failed: {}", err);
+      return None;
+    }
+  };
+  let info = info_of(&update);
+  log::info!("update available: {} -> {}", info.current_version, info.version);
+  if let Ok(mut pending) = app.state::<PendingUpdate>().0.lock() {
+    *pending = Some(update);
+  }
+  Some(info)
+}
+
+// 开启自动检查时，距上次检查满一天才会再次联网
+fn spawn_background_check(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    // 读取与写回在同一把锁内，避免覆盖同时进行的 set_update_auto_check
+    {
+      let _guard = SETTINGS_LOCK.lock();
+      let mut settings = read_settings(&app);
+      let now = now_ms();
+      if !settings.auto_check || now.saturating_sub(settings.last_check) < CHECK_INTERVAL_MS {
+        return;
+      }
+      settings.last_check = now;
+      if let Err(err) = write_settings(&app, &settings) {
+        log::warn!("update settings not saved: {}", err);
+      }
+    }
+    if let Some(info) = check(&app).await {
+      let _ = app.emit(AVAILABLE_EVENT, &info);
+    }
+  });
+}
+
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+  Ok(check(&app).await)
+}
+
+// 下载包签名与内置公钥不符时 download_and_install 返回错误，不会安装
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+  if !has_pubkey(&app) {
+    return Err("updater pubkey is not configured".to_string());
+  }
+  let update = app
+    .state::<PendingUpdate>()
+    .0
+    .lock()
+    .map_err(|err| err.to_string())?
+    .take()
+    .ok_or_else(|| "no update available".to_string())?;
+  let mut downloaded = 0u64;
+  let progress_app = app.clone();
+  update
+    .download_and_install(
+      move |chunk, total| {
+        downloaded += chunk as u64;
+        let _ = progress_app.emit(PROGRESS_EVENT, UpdateProgress { downloaded, total });
+      },
+      || {},
+    )
+    .await
+    .map_err(|err| {
+      log::error!("update install failed: {}", err);
+      err.to_string()
+    })?;
+  log::info!("update installed, restarting");
+  app.restart()
+}
+
+#[tauri::command]
+pub fn get_update_settings(app: AppHandle) -> UpdateSettings {
+  let _guard = SETTINGS_LOCK.lock();
+  read_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_update_auto_check(app: AppHandle, enabled: bool) -> Result<(), String> {
+  let _guard = SETTINGS_LOCK.lock();
+  let mut settings = read_settings(&app);
+  settings.auto_check = enabled;
+  write_settings(&app, &settings)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  // 按 minisign 格式拼出的公钥，只用于检查格式判断
+  fn sample_pubkey() -> String {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut key = b"Ed".to_vec();
+    key.extend([7u8; 40]);
+    let file = format!("untrusted comment: minisign public key: 0707070707070707\n{}\n", engine.encode(key));
+    engine.encode(file)
+  }
+
+  #[test]
+  fn gate_needs_a_minisign_pubkey() {
+    assert!(pubkey_configured(Some(&json!({ "pubkey": sample_pubkey() }))));
+    assert!(!pubkey_configured(None));
+    assert!(!pubkey_configured(Some(&json!({}))));
+    assert!(!pubkey_configured(Some(&json!({ "pubkey": "" }))));
+    assert!(!pubkey_configured(Some(&json!({ "pubkey": "  " }))));
+    assert!(!pubkey_configured(Some(&json!({ "pubkey": "not base64!" }))));
+    let engine = base64::engine::general_purpose::STANDARD;
+    assert!(!pubkey_configured(Some(&json!({ "pubkey": engine.encode("untrusted comment: x\nRWQ=\n") }))));
+  }
+
+  #[test]
+  fn configured_key_matches_gate() {
+    let config: serde_json::Value = serde_json::from_str(include_str!("../tauri.conf.json")).unwrap();
+    let updater = config.pointer("/plugins/updater");
+    assert!(updater.is_some());
+    let pubkey = updater.and_then(|updater| updater["pubkey"].as_str()).unwrap_or_default();
+    // 没有公钥时更新功能关闭；填入公钥后必须是有效的 minisign 公钥，否则同样不会启用
+    assert_eq!(pubkey_configured(updater), !pubkey.trim().is_empty());
+  }
+}
@@ -0,0 +1,213 @@
+// 模块：不进入编辑器的 .cfj 校验（结构错误、可疑数值与缺失的引用文件）
+use crate::assets;
+use crate::cfj::{self, BackgroundType, CfjProject};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+  pub ok: bool,
+  pub schema_version: Option<u32>,
+  pub warnings: Vec<String>,
+  pub errors: Vec<String>,
+  // 按项目文件目录解析后的绝对路径，便于用户逐个修复
+  pub missing_assets: Vec<String>,
+}
+
+fn parse_component(value: &str, max: f64) -> Option<f64> {
+  match value.strip_suffix('%') {
+    Some(percent) => percent.trim().parse::<f64>().ok().map(|p| p / 100.0 * max),
+    None => value.trim().parse::<f64>().ok(),
+  }
+}
+
+// 只检查十六进制与 rgb()/rgba()，颜色名和其他函数交给渲染端处理
+pub fn color_problem(value: &str) -> Option<String> {
+  let value = value.trim();
+  if let Some(hex) = value.strip_prefix('#') {
+    let valid = matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    return (!valid).then(|| format!("invalid hex color \"{}\"", value));
+  }
+  let lower = value.to_ascii_lowercase();
+  let inner = lower
+    .strip_prefix("rgba(")
+    .or_else(|| lower.strip_prefix("rgb("))
+    .and_then(|rest| rest.strip_suffix(')'))?;
+  let parts: Vec<&str> = inner
+    .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+    .filter(|part| !part.is_empty())
+    .collect();
+  if parts.len() < 3 || parts.len() > 4 {
+    return Some(format!("invalid color \"{}\"", value));
+  }
+  for (index, part) in parts.iter().enumerate() {
+    let max = if index == 3 { 1.0 } else { 255.0 };
+    match parse_component(part, max) {
+      Some(component) if (0.0..=max).contains(&component) => {}
+      Some(_) => return Some(format!("out-of-range color \"{}\"", value)),
+      None => return Some(format!("invalid color \"{}\"", value)),
+    }
+  }
+  None
+}
+
+fn check_color(warnings: &mut Vec<String>, label: &str, value: &str) {
+  if let Some(problem) = color_problem(value) {
+    warnings.push(format!("{}: {}", label, problem));
+  }
+}
+
+fn check_project(report: &mut ValidationReport, project: &CfjProject, base_dir: Option<&Path>) {
+  let canvas = &project.canvas_config;
+  if !(canvas.width.is_finite() && canvas.height.is_finite() && canvas.width > 0.0 && canvas.height > 0.0) {
+    report.errors.push(format!("canvas size {}x{} is not positive", canvas.width, canvas.height));
+  }
+  if project.title.trim().is_empty() {
+    report.warnings.push("cover has an empty title".to_string());
+  }
+
+  let background = &project.background;
+  if background.background_type == BackgroundType::Color {
+    check_color(&mut report.warnings, "background", &background.value);
+  }
+  if !background.overlay_color.is_empty() {
+    check_color(&mut report.warnings, "background overlay", &background.overlay_color);
+  }
+  if !(0.0..=1.0).contains(&background.overlay_opacity) {
+    report.warnings.push(format!("background overlay: opacity {} outside 0-1", background.overlay_opacity));
+  }
+
+  let mut ids = HashSet::new();
+  for layer in &project.layers {
+    let label = format!("layer \"{}\"", layer.name);
+    if !ids.insert(layer.id.as_str()) {
+      report.warnings.push(format!("{}: duplicate id {}", label, layer.id));
+    }
+    if !(0.0..=1.0).contains(&layer.opacity) {
+      report.warnings.push(format!("{}: opacity {} outside 0-1", label, layer.opacity));
+    }
+    if let Some(color) = &layer.color {
+      check_color(&mut report.warnings, &label, color);
+    }
+    if let Some(gradient) = &layer.text_gradient {
+      check_color(&mut report.warnings, &label, &gradient.from);
+      check_color(&mut report.warnings, &label, &gradient.to);
+    }
+    if let Some(shadow) = &layer.text_shadow {
+      check_color(&mut report.warnings, &label, &shadow.color);
+    }
+  }
+  for layer in &project.layers {
+    let dangling = layer
+      .parent_id
+      .iter()
+      .chain(layer.children.iter().flatten())
+      .filter(|id| !ids.contains(id.as_str()));
+    for id in dangling {
+      report.warnings.push(format!("layer \"{}\": references unknown layer {}", layer.name, id));
+    }
+  }
+
+  for (asset, path) in assets::missing(project, base_dir) {
+    report.warnings.push(format!("{}: missing image file {}", asset.label, path.display()));
+    report.missing_assets.push(path.to_string_lossy().into_owned());
+  }
+  for font in &project.fonts {
+    let Some(path) = font.path.as_deref().filter(|_| font.data.is_none()) else {
+      continue;
+    };
+    let path = assets::resolve(Path::new(path), base_dir);
+    if !path.is_file() {
+      report.warnings.push(format!("font \"{}\": missing font file {}", font.family, path.display()));
+      report.missing_assets.push(path.to_string_lossy().into_owned());
+    }
+  }
+}
+
+// 任何问题都写进报告，不返回错误
+pub fn validate(path: &Path) -> ValidationReport {
+  let mut report = ValidationReport::default();
+  let parsed = cfj::read_text(path).and_then(|text| {
+    serde_json::from_str::<Value>(&text).map_err(|err| cfj::CfjError::Schema(err.to_string()))
+  });
+  match parsed {
+    Ok(raw) => {
+      if let Value::Object(map) = &raw {
+        match cfj::detect_schema(map) {
+          Ok(schema) => report.schema_version = Some(schema),
+          Err(err) => report.errors.push(err.to_string()),
+        }
+      }
+      if report.errors.is_empty() {
+        match cfj::migrate(raw) {
+          Ok(project) => check_project(&mut report, &project, path.parent()),
+          Err(err) => report.errors.push(err.to_string()),
+        }
+      }
+    }
+    Err(err) => report.errors.push(err.to_string()),
+  }
+  report.ok = report.errors.is_empty();
+  report
+}
+
+#[tauri::command]
+pub fn validate_cfj(path: String) -> ValidationReport {
+  let report = validate(Path::new(&path));
+  log::info!(
+    "validate_cfj: {}: {} error(s), {} warning(s)",
+    path,
+    report.errors.len(),
+    report.warnings.len()
+  );
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/validate").join(name)
+  }
+
+  #[test]
+  fn clean_project() {
+    let report = validate(&fixture("clean.cfj"));
+    assert!(report.ok, "{:?}", report);
+    assert!(report.errors.is_empty() && report.warnings.is_empty() && report.missing_assets.is_empty(), "{:?}", report);
+    assert_eq!(report.schema_version, Some(3));
+  }
+
+  #[test]
+  fn dangling_image_reference() {
+    let report = validate(&fixture("dangling.cfj"));
+    assert!(report.ok, "{:?}", report);
+    assert_eq!(report.missing_assets.len(), 1);
+    assert!(Path::new(&report.missing_assets[0]).ends_with("img/gone.png"), "{:?}", report);
+    assert!(report.warnings.iter().any(|warning| warning.contains("empty title")), "{:?}", report);
+    assert!(report.warnings.iter().any(|warning| warning.contains("out-of-range color")), "{:?}", report);
+  }
+
+  #[test]
+  fn structurally_broken_files() {
+    for name in ["broken.cfj", "wrongtype.cfj", "missing.cfj"] {
+      let report = validate(&fixture(name));
+      assert!(!report.ok, "{}", name);
+      assert_eq!(report.errors.len(), 1, "{}: {:?}", name, report);
+    }
+  }
+
+  #[test]
+  fn color_syntax() {
+    for valid in ["#fff", "#ffffff80", "white", "rgba(0, 0, 0, 0.5)", "rgb(10 20 30 / 50%)"] {
+      assert!(color_problem(valid).is_none(), "{}", valid);
+    }
+    for invalid in ["#ggg", "rgba(0,0,0,2)", "rgb(0,0)"] {
+      assert!(color_problem(invalid).is_some(), "{}", invalid);
+    }
+  }
+}
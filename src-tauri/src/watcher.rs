@@ -0,0 +1,176 @@
+// 模块：监视当前打开的项目文件，被其他程序修改时通知前端
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const CHANGED_EVENT: &str = "file://changed-externally";
+
+// 同步工具、编辑器保存时往往连续触发多次事件
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangedPayload {
+  path: String,
+}
+
+struct ActiveWatch {
+  path: PathBuf,
+  // 最近一次加载或由本应用写入时的内容哈希
+  known_hash: Arc<Mutex<Option<u64>>>,
+  _watcher: RecommendedWatcher,
+}
+
+#[derive(Default)]
+pub struct ProjectWatcher(Mutex<Option<ActiveWatch>>);
+
+fn content_hash(path: &Path) -> Option<u64> {
+  let bytes = std::fs::read(path).ok()?;
+  let mut hasher = DefaultHasher::new();
+  bytes.hash(&mut hasher);
+  Some(hasher.finish())
+}
+
+// 内容与已知哈希不同时记下新哈希并返回 true，同一次修改只报告一次
+pub(crate) fn take_change(known_hash: &Mutex<Option<u64>>, path: &Path) -> bool {
+  let current = content_hash(path);
+  match known_hash.lock() {
+    Ok(mut known) if *known != current => {
+      *known = current;
+      true
+    }
+    _ => false,
+  }
+}
+
+pub(crate) fn set_baseline(known_hash: &Mutex<Option<u64>>, path: &Path) {
+  if let Ok(mut known) = known_hash.lock() {
+    *known = content_hash(path);
+  }
+}
+
+// 合并短时间内的连续事件，内容与已知哈希不同才通知
+fn debounce_loop(app: AppHandle, path: PathBuf, known_hash: Arc<Mutex<Option<u64>>>, events: mpsc::Receiver<()>) {
+  while events.recv().is_ok() {
+    while events.recv_timeout(DEBOUNCE).is_ok() {}
+    if take_change(&known_hash, &path) {
+      log::info!("project changed externally: {}", path.display());
+      let payload = ChangedPayload {
+        path: path.to_string_lossy().into_owned(),
+      };
+      let _ = app.emit(CHANGED_EVENT, payload);
+    }
+  }
+}
+
+pub fn watch(app: &AppHandle, path: &Path) -> Result<(), String> {
+  let path = std::fs::canonicalize(path).map_err(|err| err.to_string())?;
+  // 监视所在目录：原子保存会替换文件，直接监视文件会在改名后失效
+  let dir = path.parent().ok_or_else(|| "no parent directory".to_string())?.to_path_buf();
+  let known_hash = Arc::new(Mutex::new(content_hash(&path)));
+  let (sender, receiver) = mpsc::channel();
+  let target = path.clone();
+  let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+    let Ok(event) = result else {
+      return;
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+      return;
+    }
+    if event.paths.iter().any(|changed| changed == &target) {
+      let _ = sender.send(());
+    }
+  })
+  .map_err(|err| err.to_string())?;
+  watcher
+    .watch(&dir, RecursiveMode::NonRecursive)
+    .map_err(|err| err.to_string())?;
+
+  let worker_app = app.clone();
+  let worker_path = path.clone();
+  let worker_hash = known_hash.clone();
+  std::thread::spawn(move || debounce_loop(worker_app, worker_path, worker_hash, receiver));
+
+  let state = app.state::<ProjectWatcher>();
+  let mut active = state.0.lock().map_err(|err| err.to_string())?;
+  log::debug!("watching project: {}", path.display());
+  // 替换旧的监视器；旧监视器释放后其防抖线程随通道关闭退出
+  *active = Some(ActiveWatch {
+    path,
+    known_hash,
+    _watcher: watcher,
+  });
+  Ok(())
+}
+
+pub fn unwatch(app: &AppHandle) {
+  if let Ok(mut active) = app.state::<ProjectWatcher>().0.lock() {
+    if let Some(watch) = active.take() {
+      log::debug!("stopped watching: {}", watch.path.display());
+    }
+  }
+}
+
+// save_cfj 写入或 reload_cfj 重新读取后调用，以当前内容为基准，
+// 避免把自己的保存或已重新加载的修改当成外部修改
+pub fn reset_baseline(app: &AppHandle, path: &Path) {
+  let state = app.state::<ProjectWatcher>();
+  let Ok(active) = state.0.lock() else {
+    return;
+  };
+  let Some(watch) = active.as_ref() else {
+    return;
+  };
+  let same = std::fs::canonicalize(path).is_ok_and(|path| path == watch.path);
+  if same {
+    set_baseline(&watch.known_hash, &watch.path);
+  }
+}
+
+#[tauri::command]
+pub fn watch_project(app: AppHandle, path: String) -> Result<(), String> {
+  watch(&app, Path::new(&path))
+}
+
+#[tauri::command]
+pub fn unwatch_project(app: AppHandle) {
+  unwatch(&app);
+}
+
+// 主窗口关闭时释放监视器
+pub fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+  if window.label() == "main" && matches!(event, tauri::WindowEvent::Destroyed) {
+    unwatch(window.app_handle());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reload_resets_baseline_after_edit_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cover.cfj");
+    std::fs::write(&path, "{}").unwrap();
+    let known = Mutex::new(content_hash(&path));
+    assert!(!take_change(&known, &path));
+
+    std::fs::write(&path, "{\"title\":\"edited\"}").unwrap();
+    assert!(take_change(&known, &path));
+    // 同一次修改的后续事件不再通知
+    assert!(!take_change(&known, &path));
+
+    // 防抖尚未处理的修改在重新加载时并入基准，重新加载本身不算外部修改
+    std::fs::write(&path, "{\"title\":\"again\"}").unwrap();
+    set_baseline(&known, &path);
+    assert!(!take_change(&known, &path));
+    assert_eq!(*known.lock().unwrap(), content_hash(&path));
+  }
+}
@@ -0,0 +1,253 @@
+// 模块：主窗口位置、尺寸与最大化状态的保存与恢复
+use crate::atomic;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, Window, WindowEvent};
+
+const STATE_FILE: &str = "window-state.json";
+const MIN_WIDTH: u32 = 640;
+const MIN_HEIGHT: u32 = 480;
+// 拖动、缩放时事件很密集，限制写盘频率
+const SAVE_INTERVAL: Duration = Duration::from_millis(500);
+// 至少要有这么多像素落在某个显示器内才认为窗口可见
+const VISIBLE_MARGIN: i32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub maximized: bool,
+  pub fullscreen: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Area {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+}
+
+// 记录最近一次“普通”（非最大化/全屏）状态下的几何信息
+static NORMAL: Mutex<Option<WindowState>> = Mutex::new(None);
+static LAST_SAVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn state_file<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+  app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE))
+}
+
+fn read_state<R: Runtime>(app: &AppHandle<R>) -> Option<WindowState> {
+  decode_state(&std::fs::read_to_string(state_file(app)?).ok()?)
+}
+
+// 文件损坏或缺字段时按首次运行处理
+fn decode_state(text: &str) -> Option<WindowState> {
+  serde_json::from_str(text).ok()
+}
+
+fn write_state<R: Runtime>(app: &AppHandle<R>, state: &WindowState) -> std::io::Result<()> {
+  let Some(file) = state_file(app) else {
+    return Ok(());
+  };
+  if let Some(dir) = file.parent() {
+    std::fs::create_dir_all(dir)?;
+  }
+  let bytes = serde_json::to_vec_pretty(state)?;
+  atomic::write_with(&file, |handle| handle.write_all(&bytes))
+}
+
+// 保存的位置已不在任何显示器上时，挪到主显示器居中；尺寸不超过显示器
+pub fn clamp_to_monitors(state: WindowState, monitors: &[Area]) -> WindowState {
+  let Some(primary) = monitors.first() else {
+    return state;
+  };
+  let visible = monitors.iter().find(|area| {
+    let right = area.x + area.width as i32;
+    let bottom = area.y + area.height as i32;
+    state.x + state.width as i32 - VISIBLE_MARGIN > area.x
+      && state.x + VISIBLE_MARGIN < right
+      && state.y >= area.y
+      && state.y + VISIBLE_MARGIN < bottom
+  });
+  let area = visible.unwrap_or(primary);
+  let width = state.width.clamp(MIN_WIDTH.min(area.width), area.width);
+  let height = state.height.clamp(MIN_HEIGHT.min(area.height), area.height);
+  let (x, y) = if visible.is_some() {
+    (
+      state.x.clamp(area.x, area.x + (area.width - width) as i32),
+      state.y.clamp(area.y, area.y + (area.height - height) as i32),
+    )
+  } else {
+    (
+      area.x + (area.width - width) as i32 / 2,
+      area.y + (area.height - height) as i32 / 2,
+    )
+  };
+  WindowState { x, y, width, height, ..state }
+}
+
+fn monitor_areas<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Vec<Area> {
+  let primary = window.primary_monitor().ok().flatten();
+  let mut monitors = window.available_monitors().unwrap_or_default();
+  // 主显示器排在最前，作为兜底位置
+  if let Some(primary) = primary {
+    monitors.sort_by_key(|monitor| monitor.name() != primary.name());
+  }
+  monitors
+    .iter()
+    .map(|monitor| {
+      let rect = monitor.work_area();
+      Area {
+        x: rect.position.x,
+        y: rect.position.y,
+        width: rect.size.width,
+        height: rect.size.height,
+      }
+    })
+    .collect()
+}
+
+// 在窗口显示前调用；首次运行时居中显示默认尺寸
+pub fn restore<R: Runtime>(app: &AppHandle<R>) {
+  let Some(window) = app.get_webview_window("main") else {
+    return;
+  };
+  match read_state(app) {
+    Some(saved) => {
+      let state = clamp_to_monitors(saved, &monitor_areas(&window));
+      if let Ok(mut normal) = NORMAL.lock() {
+        *normal = Some(state);
+      }
+      let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+      let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+      if state.maximized {
+        let _ = window.maximize();
+      }
+      if state.fullscreen {
+        let _ = window.set_fullscreen(true);
+      }
+    }
+    None => {
+      let _ = window.center();
+    }
+  }
+  let _ = window.show();
+}
+
+// 还原最小化并把主窗口带到前台
+pub fn bring_to_front<R: Runtime>(app: &AppHandle<R>) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+fn capture<R: Runtime>(window: &Window<R>) -> Option<WindowState> {
+  let maximized = window.is_maximized().unwrap_or(false);
+  let fullscreen = window.is_fullscreen().unwrap_or(false);
+  let minimized = window.is_minimized().unwrap_or(false);
+  let mut normal = NORMAL.lock().ok()?;
+  if !maximized && !fullscreen && !minimized {
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    *normal = Some(WindowState {
+      x: position.x,
+      y: position.y,
+      width: size.width,
+      height: size.height,
+      maximized: false,
+      fullscreen: false,
+    });
+  }
+  normal.map(|state| WindowState { maximized, fullscreen, ..state })
+}
+
+fn save<R: Runtime>(window: &Window<R>, force: bool) {
+  let Some(state) = capture(window) else {
+    return;
+  };
+  if let Ok(mut last) = LAST_SAVE.lock() {
+    if !force && last.is_some_and(|at| at.elapsed() < SAVE_INTERVAL) {
+      return;
+    }
+    *last = Some(Instant::now());
+  }
+  if let Err(err) = write_state(window.app_handle(), &state) {
+    log::warn!("window state not saved: {}", err);
+  }
+}
+
+pub fn on_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+  if window.label() != "main" {
+    return;
+  }
+  match event {
+    WindowEvent::Moved(_) | WindowEvent::Resized(_) => save(window, false),
+    WindowEvent::CloseRequested { .. } => save(window, true),
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PRIMARY: Area = Area { x: 0, y: 0, width: 1920, height: 1040 };
+  // 副显示器在主显示器左侧，坐标为负
+  const LEFT: Area = Area { x: -1280, y: 0, width: 1280, height: 984 };
+
+  fn state(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+    WindowState { x, y, width, height, maximized: false, fullscreen: false }
+  }
+
+  #[test]
+  fn keeps_window_on_connected_monitor() {
+    let saved = state(-1200, 100, 1000, 700);
+    assert_eq!(clamp_to_monitors(saved, &[PRIMARY, LEFT]), saved);
+    let inside = state(200, 150, 1200, 800);
+    assert_eq!(clamp_to_monitors(inside, &[PRIMARY]), inside);
+  }
+
+  #[test]
+  fn disconnected_monitor_moves_window_to_primary_center() {
+    let saved = WindowState { maximized: true, ..state(-1200, 100, 1000, 700) };
+    let restored = clamp_to_monitors(saved, &[PRIMARY]);
+    assert_eq!(restored, WindowState { maximized: true, ..state(460, 170, 1000, 700) });
+    // 标题栏在显示器上沿之外也拖不回来，同样居中
+    assert_eq!(clamp_to_monitors(state(100, -40, 1000, 700), &[PRIMARY]), state(460, 170, 1000, 700));
+  }
+
+  #[test]
+  fn partly_off_screen_window_is_pulled_back() {
+    assert_eq!(clamp_to_monitors(state(-300, 100, 800, 600), &[PRIMARY]), state(0, 100, 800, 600));
+    assert_eq!(clamp_to_monitors(state(1600, 900, 800, 600), &[PRIMARY]), state(1120, 440, 800, 600));
+    // 只露出不到 VISIBLE_MARGIN 像素时视为不可见
+    assert_eq!(clamp_to_monitors(state(1900, 100, 800, 600), &[PRIMARY]), state(560, 220, 800, 600));
+  }
+
+  #[test]
+  fn size_fits_monitor_and_minimum() {
+    assert_eq!(clamp_to_monitors(state(0, 0, 4000, 3000), &[PRIMARY]), state(0, 0, 1920, 1040));
+    assert_eq!(clamp_to_monitors(state(10, 10, 200, 100), &[PRIMARY]), state(10, 10, MIN_WIDTH, MIN_HEIGHT));
+    // 比最小尺寸还小的显示器按显示器大小
+    let small = Area { x: 0, y: 0, width: 600, height: 400 };
+    assert_eq!(clamp_to_monitors(state(0, 0, 800, 600), &[small]), state(0, 0, 600, 400));
+  }
+
+  #[test]
+  fn first_run_has_no_state() {
+    assert_eq!(decode_state(""), None);
+    assert_eq!(decode_state("{\"x\": 10}"), None);
+    let saved = state(10, 20, 800, 600);
+    assert_eq!(decode_state(&serde_json::to_string(&saved).unwrap()), Some(saved));
+    // 取不到显示器信息时原样使用
+    assert_eq!(clamp_to_monitors(saved, &[]), saved);
+  }
+}
@@ -15,6 +15,8 @@ export const translations = {
     },
     createNew: "新建项目",
     selectRatio: "选择画布比例",
+    startFromTemplate: "从模板开始",
+    templateLoadFailed: "模板加载失败：{detail}",
     import: "导入项目",
     openLocalProject: "打开项目",
     storageMode: "存储方式",
@@ -28,6 +30,10 @@ export const translations = {
     storageLoadFailed: "读取存储失败",
     storageSaveFailed: "保存失败",
     save: "保存项目",
+    savedCopy: "副本已保存",
+    projectReloaded: "已从磁盘重新加载",
+    confirmReload: "重新加载将丢弃未保存的修改，确定继续吗？",
+    projectSourceMissing: "文件已被删除或移动，无法重新加载",
     export: "导出图片",
     exportSettingsTitle: "导出设置",
     exportPixelRatio: "像素倍率",
@@ -161,6 +167,14 @@ export const translations = {
     success: "成功",
     importSuccess: "导入成功",
     parseFailed: "解析失败",
+    projectMigrated: "项目文件来自旧版本，已自动升级，保存后将使用新格式",
+    projectReadOnly: "文件位于只读位置，修改需另存为新文件",
+    shortcutFailed: "唤起窗口的全局快捷键注册失败，可能已被其他应用占用",
+    fileChangedExternally: "当前文件已被其他程序修改，继续保存将覆盖这些修改；可通过“File > Reload from Disk”载入新内容",
+    backupCreated: "原文件无法移到回收站，已保留备份：{path}",
+    moveToTrash: "移到回收站",
+    moveToTrashFailed: "无法删除文件",
+    recoveryAvailable: "检测到「{title}」有未保存的修改，是否恢复？",
     startColor: "起始颜色",
     endColor: "结束颜色",
     angle: "角度",
@@ -214,6 +228,8 @@ export const translations = {
     imageLayerName: "图片图层",
     textLayerPasted: "已粘贴文字图层",
     svgLayerPasted: "已粘贴 SVG 图层",
+    dropRejected: "不支持的文件：{detail}",
+    imageFetchFailed: "无法从网址导入图片：{detail}",
     imageLayerPasted: "已粘贴图片图层",
     projectJsonApplied: "已应用 JSON 项目内容",
     newTextLayerName: "新文本",
@@ -271,6 +287,8 @@ export const translations = {
     },
     createNew: "Create New Project",
     selectRatio: "Select Canvas Ratio",
+    startFromTemplate: "Start from a Template",
+    templateLoadFailed: "Failed to load template: {detail}",
     import: "Import",
     openLocalProject: "Open Project",
     storageMode: "Storage",
@@ -284,6 +302,10 @@ export const translations = {
     storageLoadFailed: "Failed to load storage",
     storageSaveFailed: "Failed to save",
     save: "Save Project",
+    savedCopy: "Copy saved",
+    projectReloaded: "Reloaded from disk",
+    confirmReload: "Reloading will discard your unsaved changes. Continue?",
+    projectSourceMissing: "The file was deleted or moved and cannot be reloaded",
     export: "Export Image",
     exportSettingsTitle: "Export Settings",
     exportPixelRatio: "Pixel Ratio",
@@ -417,6 +439,14 @@ export const translations = {
     success: "Success",
     importSuccess: "Imported Successfully",
     parseFailed: "Parse Failed",
+    projectMigrated: "This project was made with an older version and has been upgraded. Save to keep the new format.",
+    projectReadOnly: "This file is in a read-only location. Use Save As to keep your changes.",
+    shortcutFailed: "Could not register the global shortcut to show the window. It may be in use by another app.",
+    fileChangedExternally: "This file was changed by another program. Saving now will overwrite those changes. Use File > Reload from Disk to load them.",
+    backupCreated: "The original file could not be moved to the trash. A backup was kept at {path}",
+    moveToTrash: "Move File to Trash",
+    moveToTrashFailed: "Could not delete the file",
+    recoveryAvailable: "Unsaved changes to \"{title}\" were found. Restore them?",
     startColor: "Start Color",
     endColor: "End Color",
     angle: "Angle",
@@ -470,6 +500,8 @@ export const translations = {
     imageLayerName: "Image Layer",
     textLayerPasted: "Text layer pasted",
     svgLayerPasted: "SVG layer pasted",
+    dropRejected: "Unsupported files: {detail}",
+    imageFetchFailed: "Could not import image from URL: {detail}",
     imageLayerPasted: "Image layer pasted",
     projectJsonApplied: "Project JSON content applied",
     newTextLayerName: "New Text",
@@ -45,6 +45,7 @@ export interface Layer {
   fontSize?: number;
   fontFamily?: string;
   fontWeight?: string | number;
+  fontStyle?: 'normal' | 'italic';
   textAlign?: 'left' | 'center' | 'right';
   writingMode?: 'horizontal' | 'vertical';
   ratioLocked?: boolean;
@@ -80,6 +81,15 @@ export interface BackgroundConfig {
   overlayScale: number;
 }
 
+// 导出时加载的字体文件：path 为本机路径，data 为 base64 或 data URL
+export interface ProjectFont {
+  family: string;
+  weight?: number;
+  italic?: boolean;
+  path?: string;
+  data?: string;
+}
+
 export interface ProjectState {
   id: string;
   title: string;
@@ -88,6 +98,7 @@ export interface ProjectState {
   canvasConfig: CanvasConfig;
   selectedLayerId: string | null;
   updatedAt: number;
+  fonts?: ProjectFont[];
 }
 
 export interface PresetRatio {
@@ -98,6 +109,14 @@ export interface PresetRatio {
   height: number;
 }
 
+// 桌面端随应用打包的项目模板
+export interface TemplateInfo {
+  id: string;
+  name: string;
+  description: string;
+  thumbnail?: string | null;
+}
+
 export interface FAIconMetadata {
   label: string;
   search: { terms: string[] };